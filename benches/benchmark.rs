@@ -25,6 +25,8 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     // # LAYER: Benchmarks
     gen_header_bench!(c, bench_raw, &hex!("b4d100a1004815b3"), Raw);
     gen_header_bench!(c, bench_ether, &hex!("ec086b507d584ccc6ad61f760800"), Ether);
+    gen_header_bench!(c, bench_linux_sll, &hex!("000000010006000c29a1b2c300000800"), LinuxSll);
+    gen_header_bench!(c, bench_vlan, &hex!("a0640800"), Vlan);
     gen_header_bench!(
         c,
         bench_ipv4,
@@ -37,6 +39,7 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         &hex!("60000000012867403ffe802000000001026097fffe0769ea3ffe050100001c010200f8fffe03d9c0"),
         Ipv6
     );
+    gen_header_bench!(c, bench_ipv6ext, &hex!("3a00050200000100"), Ipv6Ext);
     gen_header_bench!(
         c,
         bench_tcp,
@@ -44,6 +47,112 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         Tcp
     );
     gen_header_bench!(c, bench_udp, &hex!("b4d100a1004815b3"), Udp);
+    gen_header_bench!(c, bench_vxlan, &hex!("0800000000002a00"), Vxlan);
+    gen_header_bench!(
+        c,
+        bench_bgp,
+        &hex!("ffffffffffffffffffffffffffffffff001d0104fde900b4c0a8000100"),
+        Bgp
+    );
+    gen_header_bench!(
+        c,
+        bench_smb,
+        &hex!("fe534d4240000100000000000500010000000000000000000400000000000000fffe000001000000110000000000000000000000000000000000000000000000"),
+        Smb
+    );
+    gen_header_bench!(
+        c,
+        bench_ptp,
+        &hex!("0002002c00000200000000000000000000000000001b19fffe0000010001123400fd000060a1b2c31a2b3c4d"),
+        Ptp
+    );
+    gen_header_bench!(
+        c,
+        bench_wireguard,
+        &hex!("04000000785634120100000000000000aabbccdd"),
+        WireGuard
+    );
+    gen_header_bench!(
+        c,
+        bench_igmp,
+        &hex!("22008a9d00000002 01000002e1010101c0a80001c0a80002 02000001e10202020a000001"),
+        Igmp
+    );
+    gen_header_bench!(
+        c,
+        bench_lldp,
+        &hex!("0207040011223344550405056574683006020078 0000"),
+        Lldp
+    );
+    gen_header_bench!(
+        c,
+        bench_avtp,
+        &hex!("02812a000011223344550001010203040210006000040000aabbccdd"),
+        Avtp
+    );
+    gen_header_bench!(
+        c,
+        bench_netflow_v5,
+        &hex!("00050001000010005f0000000000000000000001000000000a0000010a00010100000000000100020000000a000003e8000001000000020003e80050001806000000000018180000"),
+        NetflowV5
+    );
+    gen_header_bench!(c, bench_macsec, &hex!("2c000000002a0011223344550001aabbccdd"), MacSec);
+    gen_header_bench!(
+        c,
+        bench_arp,
+        &hex!("00010800060400010011223344550a0000010000000000000a000002"),
+        Arp
+    );
+    gen_header_bench!(c, bench_gtpu, &hex!("34ff0010123456780000008501000900"), Gtpu);
+    gen_header_bench!(
+        c,
+        bench_ospf,
+        &hex!("0201002c0a0000010000000130f300000000000000000000ffffff00000a020100000028c0a8010100000000"),
+        Ospf
+    );
+    gen_header_bench!(c, bench_radiotap, &hex!("00000f002e00000000028509a000d6"), Radiotap);
+    gen_header_bench!(c, bench_icmp, &hex!("0800b42b0001000168656c6c6f"), Icmp);
+    gen_header_bench!(c, bench_llc, &hex!("424203"), Llc);
+    gen_header_bench!(
+        c,
+        bench_bpdu,
+        &hex!("0000000000800000112233445500000004800000112233446680010000140002000f00"),
+        Bpdu
+    );
+    gen_header_bench!(
+        c,
+        bench_dhcpv6,
+        &hex!("011008740001000e000100011c39cf88080027fe8f95"),
+        Dhcpv6
+    );
+    gen_header_bench!(c, bench_coap, &hex!("420104d2abcdb773656e736f72730474656d70"), Coap);
+    gen_header_bench!(c, bench_null, &hex!("02000000"), Null);
+    gen_header_bench!(
+        c,
+        bench_radius,
+        &hex!("012a00210f403f9473978057bd83d5cb98f4227a010773746576650406c0a80001"),
+        Radius
+    );
+
+    // Ether / IPv4 / TCP / Raw
+    c.bench_function("bench_packet_update", |b| {
+        let mut pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64")).unwrap();
+        b.iter(|| black_box(&mut pkt).update().expect("expected Ok"))
+    });
+
+    // Ether / IPv4 / TCP / Raw, full parse against the flow fast path
+    let input = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+    c.bench_function("bench_packet_flow_key", |b| {
+        b.iter(|| {
+            Packet::from_bytes(black_box(&input))
+                .expect("expected Ok")
+                .flow_key()
+                .expect("expected Some")
+        })
+    });
+    c.bench_function("bench_packet_flow_parse", |b| {
+        b.iter(|| Packet::flow_parse(black_box(&input)).expect("expected Some"))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,19 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use rust_packet::prelude::*;
+
+fuzz_target!(|data: &[u8]| {
+    // the packets read are written back, neither may panic
+    for pkt in [
+        Packet::from_bytes(data),
+        Packet::from_bytes_truncated(data),
+        Packet::from_ip_bytes(data),
+    ]
+    .iter()
+    .flatten()
+    {
+        let _ = pkt.to_bytes();
+        let _ = pkt.validate();
+    }
+});
@@ -11,10 +11,68 @@ pub mod pcapfile;
 #[cfg(feature = "pnet")]
 pub mod pnet;
 
+#[cfg(all(feature = "raw_socket", target_os = "linux"))]
+pub mod rawsocket;
+
 pub mod error;
+pub mod pcapread;
 
 use crate::datalink::error::DataLinkError;
+use crate::layer::LayerType;
 use crate::packet::Packet;
+use std::time::{Duration, Instant};
+
+/// Link-layer header type of the packets on an interface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkType {
+    /// Ethernet (LINKTYPE_ETHERNET)
+    Ethernet,
+    /// Raw IPv4 or IPv6, no link-layer header (LINKTYPE_RAW)
+    Raw,
+    /// Linux cooked capture (LINKTYPE_LINUX_SLL)
+    LinuxSll,
+    /// 802.11 frames with a radiotap header (LINKTYPE_IEEE802_11_RADIOTAP)
+    Radiotap,
+    /// 802.11 frames (LINKTYPE_IEEE802_11), read as a Raw layer
+    Ieee80211,
+    /// BSD loopback, a 4-byte address family header (LINKTYPE_NULL and LINKTYPE_LOOP)
+    Null,
+}
+
+impl LinkType {
+    /// Returns the link type of a pcap `LINKTYPE_` value
+    pub fn from_pcap_linktype(linktype: u32) -> Option<LinkType> {
+        match linktype {
+            0 | 108 => Some(LinkType::Null),
+            1 => Some(LinkType::Ethernet),
+            101 => Some(LinkType::Raw),
+            105 => Some(LinkType::Ieee80211),
+            113 => Some(LinkType::LinuxSll),
+            127 => Some(LinkType::Radiotap),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the first layer of `input`, a packet captured on this link type
+    pub fn first_layer(self, input: &[u8]) -> LayerType {
+        match self {
+            LinkType::Ethernet => LayerType::Ether,
+            LinkType::LinuxSll => LayerType::LinuxSll,
+            LinkType::Radiotap => LayerType::Radiotap,
+            LinkType::Ieee80211 => LayerType::Raw,
+            LinkType::Null => LayerType::Null,
+            LinkType::Raw => match input.first().map(|v| v >> 4) {
+                Some(6) => LayerType::Ipv6,
+                _ => LayerType::Ipv4,
+            },
+        }
+    }
+
+    /// Read a packet captured on this link type
+    pub fn read_packet(self, input: &[u8]) -> Result<Packet, DataLinkError> {
+        Ok(Packet::from_bytes_for_link(input, self)?)
+    }
+}
 
 /// A generic Packet interface used to Read and Write packets
 pub struct Interface<T: PacketRead + PacketWrite>(T);
@@ -80,6 +138,42 @@ impl<T: PacketRead + PacketWrite> Interface<T> {
     pub fn new(name: &str) -> Result<Self, DataLinkError> {
         Ok(Interface(T::init(name)?))
     }
+
+    /// Write `packets` in order at a rate of `pps` packets per second
+    ///
+    /// Each packet is written at its offset from the first one, so the write time does not
+    /// accumulate into the pacing. A `pps` of 0 writes the packets without pacing.
+    pub fn replay_vec(&mut self, packets: &[Packet], pps: u32) -> Result<(), DataLinkError> {
+        self.replay_vec_with(packets, pps, Instant::now, std::thread::sleep)
+    }
+
+    /// `replay_vec` reading the time from `now` and waiting with `sleep`
+    fn replay_vec_with<N, S>(
+        &mut self,
+        packets: &[Packet],
+        pps: u32,
+        now: N,
+        mut sleep: S,
+    ) -> Result<(), DataLinkError>
+    where
+        N: Fn() -> Instant,
+        S: FnMut(Duration),
+    {
+        let start = now();
+        for (i, packet) in packets.iter().enumerate() {
+            if pps != 0 {
+                let target = start + Duration::from_secs(1) * i as u32 / pps;
+                let current = now();
+                if target > current {
+                    sleep(target - current);
+                }
+            }
+
+            self.write(packet.clone())?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Packet interface
@@ -101,3 +195,94 @@ pub trait PacketRead: PacketInterface {
 pub trait PacketWrite: PacketInterface {
     fn write(&mut self, packet: Packet) -> Result<(), DataLinkError>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(link_type, input,
+        case::ethernet(LinkType::Ethernet, &hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f")),
+        case::raw_ipv4(LinkType::Raw, &hex!("450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f")),
+        case::linux_sll(LinkType::LinuxSll, &hex!("000000010006000c29a1b2c300000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f")),
+        case::null(LinkType::Null, &hex!("02000000450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f")),
+    )]
+    fn test_link_type_read_packet(link_type: LinkType, input: &[u8]) {
+        let pkt = link_type.read_packet(input).unwrap();
+
+        assert_eq!(link_type == LinkType::Ethernet, pkt.ether().is_some());
+        assert_eq!(link_type == LinkType::LinuxSll, pkt.linux_sll().is_some());
+        assert_eq!(link_type == LinkType::Null, pkt.null().is_some());
+        assert_eq!(5678, pkt.udp().unwrap().dport);
+        assert_eq!(b"hello".to_vec(), pkt.raw().unwrap().data);
+    }
+
+    #[derive(Default)]
+    struct MockInterface {
+        written: Vec<Packet>,
+    }
+
+    impl PacketInterface for MockInterface {
+        fn init(_name: &str) -> Result<Self, DataLinkError> {
+            Ok(MockInterface::default())
+        }
+    }
+
+    impl PacketRead for MockInterface {
+        fn read(&mut self) -> Result<Packet, DataLinkError> {
+            Err(DataLinkError::BufferError)
+        }
+    }
+
+    impl PacketWrite for MockInterface {
+        fn write(&mut self, packet: Packet) -> Result<(), DataLinkError> {
+            self.written.push(packet);
+            Ok(())
+        }
+    }
+
+    #[rstest(pps, expected_sleeps,
+        case::paced(50, vec![Duration::from_millis(20); 4]),
+        case::unpaced(0, vec![]),
+    )]
+    fn test_interface_replay_vec(pps: u32, expected_sleeps: Vec<Duration>) {
+        let packets: Vec<Packet> = (1..=5)
+            .map(|i| Packet::new(vec![crate::udp! { dport: i }.unwrap()]))
+            .collect();
+
+        // the clock only moves forward when sleeping
+        let clock = std::cell::Cell::new(Instant::now());
+        let mut sleeps = Vec::new();
+
+        let mut interface = Interface::<MockInterface>::new("mock").unwrap();
+        interface
+            .replay_vec_with(
+                &packets,
+                pps,
+                || clock.get(),
+                |duration| {
+                    sleeps.push(duration);
+                    clock.set(clock.get() + duration);
+                },
+            )
+            .unwrap();
+
+        assert_eq!(packets, interface.0.written);
+        assert_eq!(expected_sleeps, sleeps);
+    }
+
+    #[rstest(linktype, expected,
+        case(0, Some(LinkType::Null)),
+        case(1, Some(LinkType::Ethernet)),
+        case(101, Some(LinkType::Raw)),
+        case(105, Some(LinkType::Ieee80211)),
+        case(108, Some(LinkType::Null)),
+        case(113, Some(LinkType::LinuxSll)),
+        case(127, Some(LinkType::Radiotap)),
+        case(228, None),
+    )]
+    fn test_link_type_from_pcap_linktype(linktype: u32, expected: Option<LinkType>) {
+        assert_eq!(expected, LinkType::from_pcap_linktype(linktype));
+    }
+}
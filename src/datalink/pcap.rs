@@ -25,6 +25,7 @@ impl PacketInterface for Pcap {
             .next()
             .ok_or(DataLinkError::InterfaceNotFound)?;
 
+        // libpnet only provides ethernet channels for live captures
         let (tx, rx) = match datalink::channel(&interface, Default::default()) {
             Ok(Channel::Ethernet(tx, rx)) => Ok((tx, rx)),
             Ok(_) => Err(DataLinkError::UnhandledInterfaceType),
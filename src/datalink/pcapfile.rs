@@ -6,34 +6,52 @@ Note: Pcap writing currently not supported
 libpcap interface exposed via libpnet
 */
 use pnet::datalink::{self, Channel, DataLinkReceiver};
+use std::fs::File;
+use std::io::Read;
 
-use super::{DataLinkError, PacketInterface, PacketRead, PacketWrite};
+use super::pcapread::{PcapHeader, PCAP_HEADER_LEN};
+use super::{DataLinkError, LinkType, PacketInterface, PacketRead, PacketWrite};
 use crate::packet::Packet;
 
 pub struct PcapFile {
     rx: Box<dyn DataLinkReceiver + 'static>,
     // tx: Box<dyn DataLinkSender + 'static>, // TODO: implement pcap writing
+    link_type: LinkType,
+}
+
+impl PcapFile {
+    /// Link-layer header type of the packets in the file
+    pub fn link_type(&self) -> LinkType {
+        self.link_type
+    }
+
+    // libpnet does not expose the datalink type of the capture, read it from the global header
+    fn read_link_type(filename: &str) -> Result<LinkType, DataLinkError> {
+        let mut header = [0u8; PCAP_HEADER_LEN];
+        File::open(filename)?.read_exact(&mut header)?;
+
+        Ok(PcapHeader::parse(&header)?.link_type)
+    }
 }
 
 impl PacketInterface for PcapFile {
     fn init(filename: &str) -> Result<Self, DataLinkError> {
+        let link_type = PcapFile::read_link_type(filename)?;
+
         let (_tx, rx) = match datalink::pcap::from_file(filename, Default::default()) {
             Ok(Channel::Ethernet(tx, rx)) => Ok((tx, rx)),
             Ok(_) => Err(DataLinkError::UnhandledInterfaceType),
             Err(e) => Err(DataLinkError::IoError(e)),
         }?;
 
-        Ok(PcapFile { rx })
+        Ok(PcapFile { rx, link_type })
     }
 }
 
 impl PacketRead for PcapFile {
     fn read(&mut self) -> Result<Packet, DataLinkError> {
         match self.rx.next() {
-            Ok(packet_bytes) => {
-                let packet = Packet::from_bytes(packet_bytes)?;
-                Ok(packet)
-            }
+            Ok(packet_bytes) => self.link_type.read_packet(packet_bytes),
             Err(e) => Err(DataLinkError::IoError(e)),
         }
     }
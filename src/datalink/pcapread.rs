@@ -0,0 +1,180 @@
+/*!
+Reading of packets and their timestamps from pcap file contents
+
+The records are read from memory, without `libpcap`
+*/
+use super::{DataLinkError, LinkType};
+use crate::layer::LayerError;
+use crate::packet::{Packet, PacketError};
+use std::convert::TryInto;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Length of the pcap global header
+pub const PCAP_HEADER_LEN: usize = 24;
+
+/// Timestamp resolution of the records of a pcap file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PcapResolution {
+    /// Microseconds, magic number `0xa1b2c3d4`
+    Micro,
+    /// Nanoseconds, magic number `0xa1b23c4d`
+    Nano,
+}
+
+/// Pcap global header fields used to read the records
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PcapHeader {
+    /// The header and record fields are big endian
+    pub big_endian: bool,
+    pub resolution: PcapResolution,
+    pub link_type: LinkType,
+}
+
+impl PcapHeader {
+    /// Read the global header at the start of a pcap file
+    ///
+    /// The byte order and timestamp resolution are detected from the magic number
+    pub fn parse(input: &[u8]) -> Result<PcapHeader, DataLinkError> {
+        if input.len() < PCAP_HEADER_LEN {
+            return Err(short_input("header"));
+        }
+
+        let (big_endian, resolution) = match &input[0..4] {
+            [0xd4, 0xc3, 0xb2, 0xa1] => (false, PcapResolution::Micro),
+            [0x4d, 0x3c, 0xb2, 0xa1] => (false, PcapResolution::Nano),
+            [0xa1, 0xb2, 0xc3, 0xd4] => (true, PcapResolution::Micro),
+            [0xa1, 0xb2, 0x3c, 0x4d] => (true, PcapResolution::Nano),
+            _ => return Err(DataLinkError::UnhandledInterfaceType),
+        };
+
+        let mut header = PcapHeader {
+            big_endian,
+            resolution,
+            link_type: LinkType::Ethernet,
+        };
+
+        // upper bits may contain FCS information
+        let linktype = header.read_u32(&input[20..24]) & 0xffff;
+        header.link_type =
+            LinkType::from_pcap_linktype(linktype).ok_or(DataLinkError::UnhandledInterfaceType)?;
+
+        Ok(header)
+    }
+
+    /// Returns the timestamp of a record from its seconds and fractional seconds fields
+    pub fn timestamp(&self, ts_sec: u32, ts_frac: u32) -> SystemTime {
+        let frac = match self.resolution {
+            PcapResolution::Micro => Duration::from_micros(u64::from(ts_frac)),
+            PcapResolution::Nano => Duration::from_nanos(u64::from(ts_frac)),
+        };
+
+        UNIX_EPOCH + Duration::from_secs(u64::from(ts_sec)) + frac
+    }
+
+    fn read_u32(&self, input: &[u8]) -> u32 {
+        let bytes: [u8; 4] = input.try_into().expect("slice of 4 bytes");
+        if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        }
+    }
+}
+
+fn short_input(what: &str) -> DataLinkError {
+    let e = LayerError::Parse(format!("not enough data to read pcap {}", what));
+    DataLinkError::from(PacketError::from(e))
+}
+
+/// Read the packets of pcap file contents, with the timestamp of each record
+pub fn read_pcap(input: &[u8]) -> Result<Vec<(Packet, SystemTime)>, DataLinkError> {
+    let header = PcapHeader::parse(input)?;
+
+    let mut ret = Vec::new();
+    let mut rest = &input[PCAP_HEADER_LEN..];
+    while !rest.is_empty() {
+        if rest.len() < 16 {
+            return Err(short_input("record header"));
+        }
+
+        let ts_sec = header.read_u32(&rest[0..4]);
+        let ts_frac = header.read_u32(&rest[4..8]);
+        let caplen = header.read_u32(&rest[8..12]) as usize;
+
+        let data = rest
+            .get(16..16 + caplen)
+            .ok_or_else(|| short_input("record"))?;
+
+        ret.push((
+            header.link_type.read_packet(data)?,
+            header.timestamp(ts_sec, ts_frac),
+        ));
+        rest = &rest[16 + caplen..];
+    }
+
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::ip::IpProtocol;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected_big_endian, expected_resolution,
+        case::micro(
+            include_bytes!("../../tests/data/raw_ipv4_usec.pcap"),
+            false,
+            PcapResolution::Micro,
+        ),
+        case::nano(
+            include_bytes!("../../tests/data/raw_ipv4_nsec.pcap"),
+            false,
+            PcapResolution::Nano,
+        ),
+        case::micro_swapped(
+            &hex!("a1b2c3d40002000400000000000000000000ffff00000065 5f5e1000 0001e240 00000021 00000021 450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f"),
+            true,
+            PcapResolution::Micro,
+        ),
+        case::nano_swapped(
+            &hex!("a1b23c4d0002000400000000000000000000ffff00000065 5f5e1000 075bca00 00000021 00000021 450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f"),
+            true,
+            PcapResolution::Nano,
+        ),
+    )]
+    fn test_read_pcap(
+        input: &[u8],
+        expected_big_endian: bool,
+        expected_resolution: PcapResolution,
+    ) {
+        let header = PcapHeader::parse(input).unwrap();
+        assert_eq!(
+            PcapHeader {
+                big_endian: expected_big_endian,
+                resolution: expected_resolution,
+                link_type: LinkType::Raw,
+            },
+            header
+        );
+
+        let packets = read_pcap(input).unwrap();
+        assert_eq!(1, packets.len());
+
+        // 1600000000.123456
+        let (pkt, ts) = &packets[0];
+        assert_eq!(UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_000), *ts);
+        assert_eq!(IpProtocol::UDP, pkt.ipv4().unwrap().protocol);
+        assert_eq!(b"hello".to_vec(), pkt.raw().unwrap().data);
+    }
+
+    #[rstest(input,
+        case::bad_magic(&hex!("000000000002000400000000000000000000ffff00000065")),
+        case::short_header(&hex!("d4c3b2a102000400")),
+        case::short_record(&hex!("d4c3b2a1020004000000000000000000ffff000065000000 00e1f505 40e20100 21000000 21000000 4500")),
+    )]
+    fn test_read_pcap_error(input: &[u8]) {
+        assert!(read_pcap(input).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+/*!
+Packet interface implementation using a Linux `AF_PACKET` raw socket
+
+Packets are sent and received directly on the socket, bypassing the libpnet channel
+*/
+use std::ffi::CString;
+use std::io;
+use std::mem;
+use std::os::unix::io::RawFd;
+
+use super::{DataLinkError, PacketInterface, PacketRead, PacketWrite};
+use crate::packet::Packet;
+
+// Large enough for any frame received on the interface
+const RECV_BUFFER_LEN: usize = 65536;
+
+pub struct RawSocket {
+    fd: RawFd,
+    buf: Vec<u8>,
+}
+
+impl PacketInterface for RawSocket {
+    fn init(interface_name: &str) -> Result<Self, DataLinkError> {
+        let name = CString::new(interface_name).map_err(|_| DataLinkError::InterfaceNotFound)?;
+
+        let ifindex = unsafe { libc::if_nametoindex(name.as_ptr()) };
+        if ifindex == 0 {
+            return Err(DataLinkError::InterfaceNotFound);
+        }
+
+        // all protocols, in network byte order
+        let protocol = (libc::ETH_P_ALL as u16).to_be();
+
+        let fd = unsafe { libc::socket(libc::AF_PACKET, libc::SOCK_RAW, i32::from(protocol)) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        // closes the socket on error
+        let socket = RawSocket {
+            fd,
+            buf: vec![0; RECV_BUFFER_LEN],
+        };
+
+        let mut addr: libc::sockaddr_ll = unsafe { mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as u16;
+        addr.sll_protocol = protocol;
+        addr.sll_ifindex = ifindex as i32;
+
+        let ret = unsafe {
+            libc::bind(
+                fd,
+                &addr as *const libc::sockaddr_ll as *const libc::sockaddr,
+                mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t,
+            )
+        };
+        if ret < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        Ok(socket)
+    }
+}
+
+impl PacketRead for RawSocket {
+    fn read(&mut self) -> Result<Packet, DataLinkError> {
+        let len = unsafe {
+            libc::recv(
+                self.fd,
+                self.buf.as_mut_ptr() as *mut libc::c_void,
+                self.buf.len(),
+                0,
+            )
+        };
+        if len < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let packet = Packet::from_bytes(&self.buf[..len as usize])?;
+        Ok(packet)
+    }
+}
+
+impl PacketWrite for RawSocket {
+    fn write(&mut self, packet: Packet) -> Result<(), DataLinkError> {
+        let bytes = packet.to_bytes()?;
+
+        let len = unsafe {
+            libc::send(
+                self.fd,
+                bytes.as_ptr() as *const libc::c_void,
+                bytes.len(),
+                0,
+            )
+        };
+        if len < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        if len as usize != bytes.len() {
+            return Err(DataLinkError::BufferError);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for RawSocket {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    #[ignore = "requires CAP_NET_RAW"]
+    fn test_rawsocket_write_loopback() {
+        // Ether / IP / UDP / "hello"
+        let test_data = hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f");
+
+        let mut socket = RawSocket::init("lo").unwrap();
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        socket.write(pkt).unwrap();
+    }
+}
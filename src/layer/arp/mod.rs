@@ -0,0 +1,120 @@
+/*!
+ARP layer
+*/
+use crate::layer::ether::{EtherType, MacAddress};
+use deku::prelude::*;
+use std::net::Ipv4Addr;
+
+/// ARP request operation
+pub const ARP_REQUEST: u16 = 1;
+
+/// ARP reply operation
+pub const ARP_REPLY: u16 = 2;
+
+/**
+ARP for Ethernet and IPv4
+
+The layer is read following the ARP ether type, the ethernet padding is left as Raw.
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|         Hardware Type         |         Protocol Type         |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  HW Addr Len  | Proto Addr Len|           Operation           |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                  Sender Hardware Address                      |
++                               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                               |   Sender Protocol Address     |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|   Sender Protocol Address     |                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+|                  Target Hardware Address                      |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                  Target Protocol Address                      |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Arp {
+    pub hardware_type: u16,      // Hardware Type, 1 for Ethernet
+    pub protocol_type: EtherType, // Protocol Type
+    #[deku(assert_eq = "6")]
+    pub hardware_len: u8, // Hardware Address Length
+    #[deku(assert_eq = "4")]
+    pub protocol_len: u8, // Protocol Address Length
+    pub operation: u16,           // Operation
+    pub sender_mac: MacAddress,   // Sender Hardware Address
+    pub sender_ip: Ipv4Addr,      // Sender Protocol Address
+    pub target_mac: MacAddress,   // Target Hardware Address
+    pub target_ip: Ipv4Addr,      // Target Protocol Address
+}
+
+impl Default for Arp {
+    fn default() -> Self {
+        Arp {
+            hardware_type: 1,
+            protocol_type: EtherType::IPv4,
+            hardware_len: 6,
+            protocol_len: 4,
+            operation: ARP_REQUEST,
+            sender_mac: MacAddress::default(),
+            sender_ip: Ipv4Addr::new(0, 0, 0, 0),
+            target_mac: MacAddress::default(),
+            target_ip: Ipv4Addr::new(0, 0, 0, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::request(
+            &hex!("0001 0800 06 04 0001 001122334455 0a000001 000000000000 0a000002"),
+            Arp {
+                hardware_type: 1,
+                protocol_type: EtherType::IPv4,
+                hardware_len: 6,
+                protocol_len: 4,
+                operation: ARP_REQUEST,
+                sender_mac: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                sender_ip: Ipv4Addr::new(10, 0, 0, 1),
+                target_mac: MacAddress([0x00; 6]),
+                target_ip: Ipv4Addr::new(10, 0, 0, 2),
+            },
+        ),
+        case::reply(
+            &hex!("0001 0800 06 04 0002 66778899aabb 0a000002 001122334455 0a000001"),
+            Arp {
+                hardware_type: 1,
+                protocol_type: EtherType::IPv4,
+                hardware_len: 6,
+                protocol_len: 4,
+                operation: ARP_REPLY,
+                sender_mac: MacAddress([0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb]),
+                sender_ip: Ipv4Addr::new(10, 0, 0, 2),
+                target_mac: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                target_ip: Ipv4Addr::new(10, 0, 0, 1),
+            },
+        ),
+        #[should_panic(expected = "Assertion")]
+        case::ipv6_len(
+            &hex!("0001 86dd 06 10 0001 001122334455 0a000001 000000000000 0a000002"),
+            Arp::default(),
+        ),
+    )]
+    fn test_arp(input: &[u8], expected: Arp) {
+        let ret_read = Arp::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+}
@@ -0,0 +1,168 @@
+/*!
+AVTP layer
+*/
+use deku::prelude::*;
+
+/// Length of the AVTP common stream header
+pub const AVTP_HEADER_LEN: usize = 24;
+
+/**
+AVTP (IEEE 1722) Common Stream Header
+
+The format specific fields and the stream data are kept opaque
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|    subtype    |S| ver |M|R|G|T| sequence_num  |  reserved   |U|
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                                                               |
++                           stream_id                           +
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                        avtp_timestamp                         |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                        format_specific                        |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|      stream_data_length       |        format_specific        |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                          stream_data                          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Avtp {
+    pub subtype: u8,
+    #[deku(bits = "1")]
+    pub stream_valid: u8, // stream_id is valid
+    #[deku(bits = "3")]
+    pub version: u8,
+    #[deku(bits = "1")]
+    pub media_clock_restart: u8,
+    #[deku(bits = "1")]
+    pub reserved1: u8,
+    #[deku(bits = "1")]
+    pub gateway_info_valid: u8,
+    #[deku(bits = "1")]
+    pub timestamp_valid: u8,
+    pub sequence_num: u8,
+    #[deku(bits = "7")]
+    pub reserved2: u8,
+    #[deku(bits = "1")]
+    pub timestamp_uncertain: u8,
+    pub stream_id: [u8; 8],
+    pub timestamp: u32, // avtp_timestamp, in nanoseconds
+    pub format_specific1: u32,
+    #[deku(update = "self.stream_data.len()")]
+    pub stream_data_length: u16,
+    pub format_specific2: u16,
+    #[deku(count = "stream_data_length")]
+    pub stream_data: Vec<u8>,
+}
+
+impl Default for Avtp {
+    fn default() -> Self {
+        Avtp {
+            subtype: 0,
+            stream_valid: 1,
+            version: 0,
+            media_clock_restart: 0,
+            reserved1: 0,
+            gateway_info_valid: 0,
+            timestamp_valid: 0,
+            sequence_num: 0,
+            reserved2: 0,
+            timestamp_uncertain: 0,
+            stream_id: [0; 8],
+            timestamp: 0,
+            format_specific1: 0,
+            stream_data_length: 0,
+            format_specific2: 0,
+            stream_data: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::aaf(
+            &hex!("02 81 2a 00 0011223344550001 01020304 02100060 0004 0000 aabbccdd"),
+            Avtp {
+                subtype: 0x02,
+                stream_valid: 1,
+                version: 0,
+                media_clock_restart: 0,
+                reserved1: 0,
+                gateway_info_valid: 0,
+                timestamp_valid: 1,
+                sequence_num: 42,
+                reserved2: 0,
+                timestamp_uncertain: 0,
+                stream_id: [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x00, 0x01],
+                timestamp: 0x01020304,
+                format_specific1: 0x02100060,
+                stream_data_length: 4,
+                format_specific2: 0,
+                stream_data: vec![0xaa, 0xbb, 0xcc, 0xdd],
+            },
+        ),
+        #[should_panic(expected = "Incomplete")]
+        case::short_stream_data(
+            &hex!("02 81 2a 00 0011223344550001 01020304 02100060 0008 0000 aabbccdd"),
+            Avtp::default(),
+        ),
+    )]
+    fn test_avtp(input: &[u8], expected: Avtp) {
+        let ret_read = Avtp::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_avtp_update_length() {
+        let mut avtp = Avtp {
+            stream_data: vec![0; 6],
+            ..Avtp::default()
+        };
+
+        avtp.update().unwrap();
+
+        assert_eq!(6, avtp.stream_data_length);
+        assert_eq!(AVTP_HEADER_LEN + 6, avtp.to_bytes().unwrap().len());
+    }
+
+    #[test]
+    fn test_avtp_default() {
+        assert_eq!(
+            Avtp {
+                subtype: 0,
+                stream_valid: 1,
+                version: 0,
+                media_clock_restart: 0,
+                reserved1: 0,
+                gateway_info_valid: 0,
+                timestamp_valid: 0,
+                sequence_num: 0,
+                reserved2: 0,
+                timestamp_uncertain: 0,
+                stream_id: [0; 8],
+                timestamp: 0,
+                format_specific1: 0,
+                stream_data_length: 0,
+                format_specific2: 0,
+                stream_data: vec![],
+            },
+            Avtp::default()
+        )
+    }
+}
@@ -0,0 +1,267 @@
+/*!
+BGP layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+use std::convert::TryFrom;
+use std::net::Ipv4Addr;
+
+/// Well-known TCP port for BGP
+pub const BGP_PORT: u16 = 179;
+
+/// Length of the fixed BGP header (marker, length and type)
+pub const BGP_HEADER_LEN: u16 = 19;
+
+// read all the rest of the message body
+fn read_data(rest: &BitSlice<Msb0, u8>) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+    let ret = rest.as_raw_slice().to_vec();
+    let (empty, _rest) = rest.split_at(0);
+    Ok((empty, ret))
+}
+
+/**
+BGP OPEN Message
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|    Version    |     My Autonomous System      |   Hold Time   :
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+:               |                BGP Identifier                 :
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+:               | Opt Parm Len  |  Optional Parameters (variable)
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct BgpOpen {
+    pub version: u8,
+    pub my_as: u16,
+    pub hold_time: u16,
+    pub bgp_id: Ipv4Addr,
+    #[deku(update = "self.opt_params.len()")]
+    pub opt_params_len: u8,
+    #[deku(count = "opt_params_len")]
+    pub opt_params: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(type = "u8", ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub enum BgpMessage {
+    #[deku(id = "1")]
+    Open(BgpOpen),
+    #[deku(id = "2")]
+    Update {
+        #[deku(update = "withdrawn_routes.len()")]
+        withdrawn_routes_len: u16,
+        #[deku(count = "withdrawn_routes_len")]
+        withdrawn_routes: Vec<u8>,
+        #[deku(update = "path_attributes.len()")]
+        path_attributes_len: u16,
+        #[deku(count = "path_attributes_len")]
+        path_attributes: Vec<u8>,
+        #[deku(reader = "read_data(deku::rest)")]
+        nlri: Vec<u8>,
+    },
+    #[deku(id = "3")]
+    Notification {
+        code: u8,
+        subcode: u8,
+        #[deku(reader = "read_data(deku::rest)")]
+        data: Vec<u8>,
+    },
+    #[deku(id = "4")]
+    Keepalive,
+    #[deku(id_pat = "_")]
+    Unknown {
+        msg_type: u8,
+        #[deku(reader = "read_data(deku::rest)")]
+        data: Vec<u8>,
+    },
+}
+
+/**
+BGP Message Header
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                                                               |
++                                                               +
+|                                                               |
++                                                               +
+|                           Marker                              |
++                                                               +
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|          Length               |      Type     |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Bgp {
+    pub marker: [u8; 16],
+    #[deku(update = "self.update_length()?")]
+    pub length: u16, // Length of the message, including the header
+    #[deku(reader = "Bgp::read_message(*length, deku::rest)")]
+    pub message: BgpMessage,
+}
+
+impl Bgp {
+    fn update_length(&self) -> Result<u16, DekuError> {
+        let bgp = self.to_bytes()?;
+        Ok(u16::try_from(bgp.len())?)
+    }
+
+    fn read_message(
+        length: u16, // length of the message, including the header
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, BgpMessage), DekuError> {
+        // the message type is part of the header but is read with the message
+        let message_len = length
+            .checked_sub(BGP_HEADER_LEN - 1)
+            .filter(|v| *v != 0)
+            .ok_or_else(|| DekuError::Parse("error: invalid bgp length".to_string()))?;
+
+        // slice off length of message
+        let bits = usize::from(message_len) * 8;
+
+        // Check split_at precondition
+        if bits > rest.len() {
+            return Err(DekuError::Parse(
+                "not enough data to read bgp message".to_string(),
+            ));
+        }
+
+        let (message_rest, rest) = rest.split_at(bits);
+        let (message_rest, message) = BgpMessage::read(message_rest, deku::ctx::Endian::Big)?;
+
+        // the written message would not match the length
+        if !message_rest.is_empty() {
+            return Err(DekuError::Parse(
+                "error: bgp length exceeds the message".to_string(),
+            ));
+        }
+
+        Ok((rest, message))
+    }
+}
+
+impl Default for Bgp {
+    fn default() -> Self {
+        Bgp {
+            marker: [0xff; 16],
+            length: BGP_HEADER_LEN,
+            message: BgpMessage::Keepalive,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::open(
+            &hex!("ffffffffffffffffffffffffffffffff001d0104fde900b4c0a8000100"),
+            Bgp {
+                marker: [0xff; 16],
+                length: 29,
+                message: BgpMessage::Open(BgpOpen {
+                    version: 4,
+                    my_as: 65001,
+                    hold_time: 180,
+                    bgp_id: Ipv4Addr::new(192, 168, 0, 1),
+                    opt_params_len: 0,
+                    opt_params: vec![],
+                }),
+            },
+        ),
+        case::update(
+            &hex!("ffffffffffffffffffffffffffffffff001e02000000071801020304aabb"),
+            Bgp {
+                marker: [0xff; 16],
+                length: 30,
+                message: BgpMessage::Update {
+                    withdrawn_routes_len: 0,
+                    withdrawn_routes: vec![],
+                    path_attributes_len: 7,
+                    path_attributes: vec![0x18, 0x01, 0x02, 0x03, 0x04, 0xaa, 0xbb],
+                    nlri: vec![],
+                },
+            },
+        ),
+        case::keepalive(
+            &hex!("ffffffffffffffffffffffffffffffff001304"),
+            Bgp::default(),
+        ),
+        #[should_panic(expected = "Parse(\"error: invalid bgp length\")")]
+        case::invalid_length(
+            &hex!("ffffffffffffffffffffffffffffffff001204"),
+            Bgp::default(),
+        ),
+        #[should_panic(expected = "Parse(\"error: bgp length exceeds the message\")")]
+        case::length_exceeds_message(
+            &hex!("ffffffffffffffffffffffffffffffff001704deadbeef"),
+            Bgp::default(),
+        ),
+    )]
+    fn test_bgp(input: &[u8], expected: Bgp) {
+        let ret_read = Bgp::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_bgp_open() {
+        let bgp = Bgp::try_from(
+            hex!("ffffffffffffffffffffffffffffffff001d0104fde900b4c0a8000100").as_ref(),
+        )
+        .unwrap();
+
+        if let BgpMessage::Open(open) = bgp.message {
+            assert_eq!(65001, open.my_as);
+            assert_eq!(Ipv4Addr::new(192, 168, 0, 1), open.bgp_id);
+        } else {
+            panic!("expected bgp open message");
+        }
+    }
+
+    #[test]
+    fn test_bgp_update_length() {
+        let mut bgp = Bgp {
+            length: 0,
+            message: BgpMessage::Notification {
+                code: 6,
+                subcode: 2,
+                data: vec![],
+            },
+            ..Bgp::default()
+        };
+
+        bgp.update().unwrap();
+
+        assert_eq!(21, bgp.length);
+    }
+
+    #[test]
+    fn test_bgp_default() {
+        assert_eq!(
+            Bgp {
+                marker: [0xff; 16],
+                length: 19,
+                message: BgpMessage::Keepalive,
+            },
+            Bgp::default()
+        )
+    }
+}
@@ -0,0 +1,138 @@
+/*!
+Spanning Tree BPDU layer
+
+Bridge Protocol Data Units of STP (IEEE 802.1D) and RSTP (IEEE 802.1w), carried over LLC
+*/
+use deku::prelude::*;
+
+/// Configuration BPDU type
+pub const BPDU_CONFIG: u8 = 0x00;
+
+/// Rapid Spanning Tree BPDU type
+pub const BPDU_RST: u8 = 0x02;
+
+/// Topology Change Notification BPDU type
+pub const BPDU_TCN: u8 = 0x80;
+
+/// Configuration fields of a Configuration or RST BPDU, the timers are in 1/256 seconds
+#[derive(Debug, PartialEq, Clone, Default, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct BpduConfig {
+    pub flags: u8,
+    pub root_id: u64, // Root bridge priority and MAC address
+    pub root_path_cost: u32,
+    pub bridge_id: u64, // Sender bridge priority and MAC address
+    pub port_id: u16,
+    pub message_age: u16,
+    pub max_age: u16,
+    pub hello_time: u16,
+    pub forward_delay: u16,
+}
+
+/**
+BPDU
+
+```text
+Octets
+ 1-2    Protocol Identifier
+ 3      Protocol Version Identifier
+ 4      BPDU Type
+ 5      Flags
+ 6-13   Root Identifier
+ 14-17  Root Path Cost
+ 18-25  Bridge Identifier
+ 26-27  Port Identifier
+ 28-29  Message Age
+ 30-31  Max Age
+ 32-33  Hello Time
+ 34-35  Forward Delay
+ 36     Version 1 Length (RST BPDU)
+```
+
+A Topology Change Notification only carries the first 4 bytes
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Bpdu {
+    pub protocol_id: u16,
+    pub version: u8, // 0 for STP, 2 for RSTP
+    pub bpdu_type: u8,
+    #[deku(cond = "*bpdu_type != BPDU_TCN")]
+    pub config: Option<BpduConfig>,
+    #[deku(cond = "*bpdu_type == BPDU_RST")]
+    pub version1_length: Option<u8>,
+}
+
+impl Default for Bpdu {
+    fn default() -> Self {
+        Bpdu {
+            protocol_id: 0,
+            version: 0,
+            bpdu_type: BPDU_CONFIG,
+            config: Some(BpduConfig::default()),
+            version1_length: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::config(
+            &hex!("0000 00 00 00 8000001122334455 00000004 8000001122334466 8001 0000 1400 0200 0f00"),
+            Bpdu {
+                config: Some(BpduConfig {
+                    flags: 0,
+                    root_id: 0x8000001122334455,
+                    root_path_cost: 4,
+                    bridge_id: 0x8000001122334466,
+                    port_id: 0x8001,
+                    message_age: 0,
+                    max_age: 0x1400,
+                    hello_time: 0x0200,
+                    forward_delay: 0x0f00,
+                }),
+                ..Bpdu::default()
+            },
+        ),
+        case::rst(
+            &hex!("0000 02 02 3c 8000001122334455 00000000 8000001122334455 8001 0000 1400 0200 0f00 00"),
+            Bpdu {
+                version: 2,
+                bpdu_type: BPDU_RST,
+                config: Some(BpduConfig {
+                    flags: 0x3c,
+                    root_id: 0x8000001122334455,
+                    root_path_cost: 0,
+                    bridge_id: 0x8000001122334455,
+                    port_id: 0x8001,
+                    message_age: 0,
+                    max_age: 0x1400,
+                    hello_time: 0x0200,
+                    forward_delay: 0x0f00,
+                }),
+                version1_length: Some(0),
+                ..Bpdu::default()
+            },
+        ),
+        case::tcn(
+            &hex!("0000 00 80"),
+            Bpdu {
+                bpdu_type: BPDU_TCN,
+                config: None,
+                ..Bpdu::default()
+            },
+        ),
+    )]
+    fn test_bpdu(input: &[u8], expected: Bpdu) {
+        let (_rest, ret_read) = Bpdu::from_bytes((input, 0)).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+}
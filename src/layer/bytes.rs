@@ -0,0 +1,146 @@
+/*!
+Fixed-size opaque byte array field
+
+For identifiers such as clock identities, SCIs or cookies, which are neither variable length
+data nor meaningful as integers
+*/
+use deku::bitvec::{BitSlice, BitVec, Msb0};
+use deku::prelude::*;
+
+/**
+Exactly `N` bytes, read and written as-is
+
+```rust
+# use rust_packet::layer::bytes::Bytes;
+let id = Bytes([0x00, 0x11, 0x22, 0xff]);
+assert_eq!("001122ff", id.to_string());
+assert_eq!(0x22, id[2]);
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bytes<const N: usize>(pub [u8; N]);
+
+impl<'a, Ctx: Copy, const N: usize> DekuRead<'a, Ctx> for Bytes<N>
+where
+    u8: DekuRead<'a, Ctx>,
+{
+    fn read(
+        input: &'a BitSlice<Msb0, u8>,
+        ctx: Ctx,
+    ) -> Result<(&'a BitSlice<Msb0, u8>, Self), DekuError> {
+        let mut rest = input;
+        let mut ret = [0u8; N];
+        for byte in ret.iter_mut() {
+            let (new_rest, value) = u8::read(rest, ctx)?;
+            *byte = value;
+            rest = new_rest;
+        }
+
+        Ok((rest, Bytes(ret)))
+    }
+}
+
+impl<Ctx: Copy, const N: usize> DekuWrite<Ctx> for Bytes<N>
+where
+    u8: DekuWrite<Ctx>,
+{
+    fn write(&self, output: &mut BitVec<Msb0, u8>, ctx: Ctx) -> Result<(), DekuError> {
+        for byte in self.0.iter() {
+            byte.write(output, ctx)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<const N: usize> Default for Bytes<N> {
+    fn default() -> Self {
+        Bytes([0; N])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bytes<N> {
+    fn from(v: [u8; N]) -> Self {
+        Bytes(v)
+    }
+}
+
+impl<const N: usize> std::ops::Deref for Bytes<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<const N: usize> std::ops::DerefMut for Bytes<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Lowercase hex, without separators
+impl<const N: usize> std::fmt::Display for Bytes<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deku::bitvec::BitView;
+    use hex_literal::hex;
+
+    #[derive(Debug, PartialEq, DekuRead, DekuWrite)]
+    #[deku(endian = "big")]
+    struct TestHeader {
+        version: u8,
+        id: Bytes<8>,
+        length: u16,
+    }
+
+    #[test]
+    fn test_bytes() {
+        let input = hex!("0011223344556677 aabb");
+
+        let (rest, ret_read) = Bytes::<8>::read(input.view_bits(), ()).unwrap();
+        assert_eq!(Bytes(hex!("0011223344556677")), ret_read);
+        assert_eq!(hex!("aabb").view_bits::<Msb0>(), rest);
+
+        let mut ret_write = BitVec::new();
+        ret_read.write(&mut ret_write, ()).unwrap();
+        assert_eq!(input[..8].to_vec(), ret_write.into_vec());
+
+        assert_eq!("0011223344556677", ret_read.to_string());
+        assert_eq!(0x77, ret_read[7]);
+    }
+
+    #[test]
+    fn test_bytes_field() {
+        let input = hex!("02 0011223344556677 0010");
+
+        let (_rest, ret_read) = TestHeader::from_bytes((input.as_ref(), 0)).unwrap();
+        assert_eq!(
+            TestHeader {
+                version: 2,
+                id: Bytes(hex!("0011223344556677")),
+                length: 16,
+            },
+            ret_read
+        );
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_bytes_not_enough_data() {
+        let input = hex!("001122");
+        assert!(Bytes::<4>::read(input.view_bits(), ()).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+/*!
+CoAP layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/// Well-known UDP port for CoAP
+pub const COAP_PORT: u16 = 5683;
+
+/// Confirmable message type
+pub const COAP_CONFIRMABLE: u8 = 0;
+
+/// Non-confirmable message type
+pub const COAP_NON_CONFIRMABLE: u8 = 1;
+
+/// Acknowledgement message type
+pub const COAP_ACKNOWLEDGEMENT: u8 = 2;
+
+/// Reset message type
+pub const COAP_RESET: u8 = 3;
+
+/// GET request code, 0.01
+pub const COAP_GET: u8 = 0x01;
+
+/// POST request code, 0.02
+pub const COAP_POST: u8 = 0x02;
+
+/// PUT request code, 0.03
+pub const COAP_PUT: u8 = 0x03;
+
+/// DELETE request code, 0.04
+pub const COAP_DELETE: u8 = 0x04;
+
+/// Uri-Path option number
+pub const COAP_OPTION_URI_PATH: u16 = 11;
+
+/// Content-Format option number
+pub const COAP_OPTION_CONTENT_FORMAT: u16 = 12;
+
+/// Byte separating the options from the payload
+pub const COAP_PAYLOAD_MARKER: u8 = 0xff;
+
+// read all the rest of the message
+fn read_data(rest: &BitSlice<Msb0, u8>) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+    let ret = rest.as_raw_slice().to_vec();
+    let (empty, _rest) = rest.split_at(0);
+    Ok((empty, ret))
+}
+
+/**
+CoAP Option
+
+```text
+  0   1   2   3   4   5   6   7
++---------------+---------------+
+|  Option Delta | Option Length |   1 byte
++---------------+---------------+
+/         Option Delta          /   0-2 bytes
+\          (extended)           \
++-------------------------------+
+/         Option Length         /   0-2 bytes
+\          (extended)           \
++-------------------------------+
+/         Option Value          /   0 or more bytes
++-------------------------------+
+```
+
+A delta or length nibble of 13 is followed by one extended byte, 14 by two. The option number
+is the sum of the deltas of the options up to this one.
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct CoapOption {
+    #[deku(bits = "4")]
+    pub delta: u8,
+    #[deku(bits = "4")]
+    pub length: u8,
+    #[deku(count = "CoapOption::ext_len(*delta)?")]
+    pub delta_ext: Vec<u8>,
+    #[deku(count = "CoapOption::ext_len(*length)?")]
+    pub length_ext: Vec<u8>,
+    #[deku(count = "CoapOption::ext_value(*length, length_ext)")]
+    pub value: Vec<u8>,
+}
+
+impl CoapOption {
+    // number of extended bytes following a delta or length nibble
+    fn ext_len(nibble: u8) -> Result<usize, DekuError> {
+        match nibble {
+            0..=12 => Ok(0),
+            13 => Ok(1),
+            14 => Ok(2),
+            _ => Err(DekuError::Parse("reserved coap option nibble".to_string())),
+        }
+    }
+
+    // value of a delta or length nibble with its extended bytes
+    fn ext_value(nibble: u8, ext: &[u8]) -> usize {
+        match (nibble, ext) {
+            (13, [v]) => 13 + usize::from(*v),
+            (14, [v0, v1]) => 269 + usize::from(u16::from_be_bytes([*v0, *v1])),
+            _ => usize::from(nibble),
+        }
+    }
+
+    /// Returns the option delta, including the extended bytes
+    pub fn delta(&self) -> usize {
+        CoapOption::ext_value(self.delta, &self.delta_ext)
+    }
+}
+
+/**
+CoAP Message
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|Ver| T |  TKL  |      Code     |          Message ID           |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|   Token (if any, TKL bytes) ...
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|   Options (if any) ...
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|1 1 1 1 1 1 1 1|    Payload (if any) ...
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Coap {
+    #[deku(bits = "2")]
+    pub version: u8,
+    #[deku(bits = "2")]
+    pub msg_type: u8,
+    #[deku(bits = "4", update = "self.token.len()")]
+    pub token_length: u8,
+    pub code: u8, // Class in the 3 high bits, detail in the 5 low bits
+    pub message_id: u16,
+    #[deku(count = "token_length")]
+    pub token: Vec<u8>,
+    #[deku(reader = "Coap::read_options(deku::rest)")]
+    pub options: Vec<CoapOption>,
+    #[deku(
+        reader = "Coap::read_payload_marker(deku::rest)",
+        update = "self.update_payload_marker()"
+    )]
+    pub payload_marker: Option<u8>, // Present if there is a payload
+    #[deku(reader = "read_data(deku::rest)")]
+    pub payload: Vec<u8>,
+}
+
+impl Coap {
+    // options run until the payload marker or the end of the message
+    fn read_options(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<CoapOption>), DekuError> {
+        let mut rest = rest;
+        let mut options = Vec::new();
+        while !rest.is_empty() {
+            let (_rest, byte) = u8::read(rest, deku::ctx::Endian::Big)?;
+            if byte == COAP_PAYLOAD_MARKER {
+                break;
+            }
+
+            let (new_rest, option) = CoapOption::read(rest, deku::ctx::Endian::Big)?;
+            options.push(option);
+            rest = new_rest;
+        }
+
+        Ok((rest, options))
+    }
+
+    fn read_payload_marker(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Option<u8>), DekuError> {
+        if rest.is_empty() {
+            return Ok((rest, None));
+        }
+
+        let (rest, marker) = u8::read(rest, deku::ctx::Endian::Big)?;
+        Ok((rest, Some(marker)))
+    }
+
+    fn update_payload_marker(&self) -> Option<u8> {
+        if self.payload.is_empty() {
+            None
+        } else {
+            Some(COAP_PAYLOAD_MARKER)
+        }
+    }
+
+    /// Returns the value of the first option of number `number`
+    pub fn option(&self, number: u16) -> Option<&[u8]> {
+        let mut option_number = 0;
+        for option in self.options.iter() {
+            option_number += option.delta();
+            if option_number == usize::from(number) {
+                return Some(&option.value);
+            }
+        }
+
+        None
+    }
+
+    /// Returns the path of the Uri-Path options, joined by `/`
+    pub fn uri_path(&self) -> String {
+        let mut option_number = 0;
+        let mut segments = Vec::new();
+        for option in self.options.iter() {
+            option_number += option.delta();
+            if option_number == usize::from(COAP_OPTION_URI_PATH) {
+                segments.push(String::from_utf8_lossy(&option.value));
+            }
+        }
+
+        segments.join("/")
+    }
+}
+
+impl Default for Coap {
+    fn default() -> Self {
+        Coap {
+            version: 1,
+            msg_type: COAP_CONFIRMABLE,
+            token_length: 0,
+            code: COAP_GET,
+            message_id: 0,
+            token: vec![],
+            options: vec![],
+            payload_marker: None,
+            payload: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::get(
+            &hex!("4201 04d2 abcd b773656e736f7273 0474656d70"),
+            Coap {
+                version: 1,
+                msg_type: COAP_CONFIRMABLE,
+                token_length: 2,
+                code: COAP_GET,
+                message_id: 0x04d2,
+                token: vec![0xab, 0xcd],
+                options: vec![
+                    CoapOption {
+                        delta: 11,
+                        length: 7,
+                        delta_ext: vec![],
+                        length_ext: vec![],
+                        value: b"sensors".to_vec(),
+                    },
+                    CoapOption {
+                        delta: 0,
+                        length: 4,
+                        delta_ext: vec![],
+                        length_ext: vec![],
+                        value: b"temp".to_vec(),
+                    },
+                ],
+                payload_marker: None,
+                payload: vec![],
+            },
+        ),
+        case::post_extended_option(
+            &hex!("5202 1234 aabb dd1602 636f61703a2f2f6578616d706c652f ff 6869"),
+            Coap {
+                version: 1,
+                msg_type: COAP_NON_CONFIRMABLE,
+                token_length: 2,
+                code: COAP_POST,
+                message_id: 0x1234,
+                token: vec![0xaa, 0xbb],
+                options: vec![CoapOption {
+                    delta: 13,
+                    length: 13,
+                    delta_ext: vec![22],
+                    length_ext: vec![2],
+                    value: b"coap://example/".to_vec(),
+                }],
+                payload_marker: Some(COAP_PAYLOAD_MARKER),
+                payload: b"hi".to_vec(),
+            },
+        ),
+        case::empty(&hex!("40000000"), Coap { code: 0, ..Coap::default() }),
+        #[should_panic(expected = "reserved coap option nibble")]
+        case::reserved_delta(&hex!("4001 0000 f0"), Coap::default()),
+        #[should_panic(expected = "Incomplete")]
+        case::short_value(&hex!("4001 0000 b474"), Coap::default()),
+    )]
+    fn test_coap(input: &[u8], expected: Coap) {
+        let ret_read = Coap::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_coap_options() {
+        let post = hex!("5202 1234 aabb dd1602 636f61703a2f2f6578616d706c652f ff 6869");
+        let coap = Coap::try_from(post.as_ref()).unwrap();
+        assert_eq!(Some(b"coap://example/".as_ref()), coap.option(35));
+        assert_eq!(None, coap.option(COAP_OPTION_URI_PATH));
+
+        let get = hex!("4201 04d2 abcd b773656e736f7273 0474656d70");
+        let coap = Coap::try_from(get.as_ref()).unwrap();
+        assert_eq!(Some(b"sensors".as_ref()), coap.option(COAP_OPTION_URI_PATH));
+        assert_eq!("sensors/temp", coap.uri_path());
+    }
+
+    #[test]
+    fn test_coap_update() {
+        let mut coap = Coap {
+            token: vec![0x01, 0x02, 0x03],
+            payload: b"22.5".to_vec(),
+            ..Coap::default()
+        };
+
+        coap.update().unwrap();
+        assert_eq!(3, coap.token_length);
+        assert_eq!(Some(COAP_PAYLOAD_MARKER), coap.payload_marker);
+        assert_eq!(hex!("4301 0000 010203 ff 32322e35").to_vec(), coap.to_bytes().unwrap());
+    }
+}
@@ -0,0 +1,194 @@
+/*!
+DHCPv6 layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/// UDP port on which clients listen for DHCPv6 messages
+pub const DHCPV6_CLIENT_PORT: u16 = 546;
+
+/// UDP port on which servers and relay agents listen for DHCPv6 messages
+pub const DHCPV6_SERVER_PORT: u16 = 547;
+
+/// Solicit message type
+pub const DHCPV6_SOLICIT: u8 = 1;
+
+/// Advertise message type
+pub const DHCPV6_ADVERTISE: u8 = 2;
+
+/// Request message type
+pub const DHCPV6_REQUEST: u8 = 3;
+
+/// Reply message type
+pub const DHCPV6_REPLY: u8 = 7;
+
+/// Client Identifier option code
+pub const DHCPV6_OPTION_CLIENTID: u16 = 1;
+
+/// Server Identifier option code
+pub const DHCPV6_OPTION_SERVERID: u16 = 2;
+
+/// Identity Association for Non-temporary Addresses option code
+pub const DHCPV6_OPTION_IA_NA: u16 = 3;
+
+/// Option Request option code
+pub const DHCPV6_OPTION_ORO: u16 = 6;
+
+/// Elapsed Time option code
+pub const DHCPV6_OPTION_ELAPSED_TIME: u16 = 8;
+
+/**
+DHCPv6 Option
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|          option-code          |           option-len          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                          option-data                          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct Dhcpv6Option {
+    pub code: u16,
+    #[deku(update = "self.data.len()")]
+    pub length: u16,
+    #[deku(count = "length")]
+    pub data: Vec<u8>,
+}
+
+/**
+DHCPv6 Client/Server Message
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|    msg-type   |               transaction-id                  |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                            options                            |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+
+Relay-forward and relay-reply messages, which have a different header, are not supported
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Dhcpv6 {
+    pub msg_type: u8,
+    #[deku(bits = "24")]
+    pub transaction_id: u32,
+    #[deku(
+        reader = "Dhcpv6::read_options(deku::rest)",
+        update = "self.update_options()?"
+    )]
+    pub options: Vec<Dhcpv6Option>,
+}
+
+impl Dhcpv6 {
+    fn update_options(&self) -> Result<Vec<Dhcpv6Option>, DekuError> {
+        let mut options = self.options.clone();
+        for option in options.iter_mut() {
+            option.update()?;
+        }
+
+        Ok(options)
+    }
+
+    // options run until the end of the message
+    fn read_options(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<Dhcpv6Option>), DekuError> {
+        let mut rest = rest;
+        let mut options = Vec::new();
+        while !rest.is_empty() {
+            let (new_rest, option) = Dhcpv6Option::read(rest, deku::ctx::Endian::Big)?;
+            options.push(option);
+            rest = new_rest;
+        }
+
+        Ok((rest, options))
+    }
+
+    /// Returns the data of the first option of code `code`
+    pub fn option(&self, code: u16) -> Option<&[u8]> {
+        self.options
+            .iter()
+            .find(|v| v.code == code)
+            .map(|v| v.data.as_slice())
+    }
+}
+
+impl Default for Dhcpv6 {
+    fn default() -> Self {
+        Dhcpv6 {
+            msg_type: DHCPV6_SOLICIT,
+            transaction_id: 0,
+            options: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::solicit(
+            &hex!("01 100874 0001000e000100011c39cf88080027fe8f95 000600040017 0018 000800020000"),
+            Dhcpv6 {
+                msg_type: DHCPV6_SOLICIT,
+                transaction_id: 0x100874,
+                options: vec![
+                    Dhcpv6Option {
+                        code: DHCPV6_OPTION_CLIENTID,
+                        length: 14,
+                        data: hex!("000100011c39cf88080027fe8f95").to_vec(),
+                    },
+                    Dhcpv6Option {
+                        code: DHCPV6_OPTION_ORO,
+                        length: 4,
+                        data: hex!("00170018").to_vec(),
+                    },
+                    Dhcpv6Option {
+                        code: DHCPV6_OPTION_ELAPSED_TIME,
+                        length: 2,
+                        data: vec![0x00, 0x00],
+                    },
+                ],
+            },
+        ),
+        case::no_options(&hex!("01 000000"), Dhcpv6::default()),
+        #[should_panic(expected = "Incomplete")]
+        case::short_option(&hex!("01 100874 00080004 0000"), Dhcpv6::default()),
+    )]
+    fn test_dhcpv6(input: &[u8], expected: Dhcpv6) {
+        let ret_read = Dhcpv6::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_dhcpv6_update_length() {
+        let mut dhcpv6 = Dhcpv6 {
+            options: vec![Dhcpv6Option {
+                code: DHCPV6_OPTION_ELAPSED_TIME,
+                length: 0,
+                data: vec![0x00, 0x64],
+            }],
+            ..Dhcpv6::default()
+        };
+
+        dhcpv6.update().unwrap();
+        assert_eq!(2, dhcpv6.options[0].length);
+        assert_eq!(Some([0x00, 0x64].as_ref()), dhcpv6.option(DHCPV6_OPTION_ELAPSED_TIME));
+    }
+}
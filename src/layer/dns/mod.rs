@@ -0,0 +1,180 @@
+/*!
+DNS utilities
+
+DNS-style name encoding is shared by DNS, mDNS, LLMNR and NBNS
+*/
+use crate::layer::LayerError;
+
+/// Default maximum number of compression pointers followed when decoding a name
+pub const DNS_MAX_POINTERS: usize = 128;
+
+/// Maximum length of a label
+pub const DNS_MAX_LABEL_LEN: usize = 63;
+
+/// Maximum length of an encoded name
+pub const DNS_MAX_NAME_LEN: usize = 255;
+
+/// Encode a domain name to the DNS length-prefixed label form
+///
+/// Labels of more than 63 bytes and names of more than 255 bytes once encoded are an error
+pub fn dns_encode_name(name: &str) -> Result<Vec<u8>, LayerError> {
+    let mut ret = Vec::with_capacity(name.len() + 2);
+    for label in name.split('.').filter(|v| !v.is_empty()) {
+        if label.len() > DNS_MAX_LABEL_LEN {
+            return Err(LayerError::Unexpected(format!(
+                "dns label of {} bytes exceeds {} bytes",
+                label.len(),
+                DNS_MAX_LABEL_LEN
+            )));
+        }
+
+        ret.push(label.len() as u8);
+        ret.extend_from_slice(label.as_bytes());
+    }
+    ret.push(0);
+
+    if ret.len() > DNS_MAX_NAME_LEN {
+        return Err(LayerError::Unexpected(format!(
+            "dns name of {} bytes exceeds {} bytes",
+            ret.len(),
+            DNS_MAX_NAME_LEN
+        )));
+    }
+
+    Ok(ret)
+}
+
+/// Decode a DNS length-prefixed name starting at `offset` in `buf`
+///
+/// Compression pointers are followed within `buf`, which should be the whole DNS message.
+/// Returns the name and the offset following the name in `buf`
+pub fn dns_decode_name(buf: &[u8], offset: usize) -> Result<(String, usize), LayerError> {
+    dns_decode_name_with_limit(buf, offset, DNS_MAX_POINTERS)
+}
+
+/// Decode a DNS length-prefixed name, following at most `max_pointers` compression pointers
+///
+/// See [dns_decode_name](fn.dns_decode_name.html)
+pub fn dns_decode_name_with_limit(
+    buf: &[u8],
+    offset: usize,
+    max_pointers: usize,
+) -> Result<(String, usize), LayerError> {
+    let out_of_bounds = || LayerError::Parse("dns name out of bounds".to_string());
+    let pointer_loop = || LayerError::Parse("dns name pointer loop".to_string());
+
+    let mut visited: Vec<usize> = Vec::new(); // pointer offsets followed
+    let mut labels: Vec<String> = Vec::new();
+    let mut pos = offset;
+    let mut end = None; // offset following the name, set by the first pointer
+
+    loop {
+        let len = *buf.get(pos).ok_or_else(out_of_bounds)?;
+        match len & 0xc0 {
+            0x00 => {
+                if len == 0 {
+                    pos += 1;
+                    break;
+                }
+
+                let start = pos + 1;
+                let stop = start + usize::from(len);
+                let label = buf.get(start..stop).ok_or_else(out_of_bounds)?;
+                labels.push(String::from_utf8_lossy(label).into_owned());
+
+                pos = stop;
+            }
+            0xc0 => {
+                let low = *buf.get(pos + 1).ok_or_else(out_of_bounds)?;
+                if end.is_none() {
+                    end = Some(pos + 2);
+                }
+
+                if visited.len() >= max_pointers || visited.contains(&pos) {
+                    return Err(pointer_loop());
+                }
+                visited.push(pos);
+
+                pos = usize::from(u16::from_be_bytes([len & 0x3f, low]));
+            }
+            _ => {
+                return Err(LayerError::Parse("invalid dns label type".to_string()));
+            }
+        }
+    }
+
+    Ok((labels.join("."), end.unwrap_or(pos)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case("www.example.com", &hex!("03777777076578616d706c6503636f6d00")),
+        case("www.example.com.", &hex!("03777777076578616d706c6503636f6d00")),
+        case("", &hex!("00")),
+    )]
+    fn test_dns_encode_name(input: &str, expected: &[u8]) {
+        assert_eq!(expected.to_vec(), dns_encode_name(input).unwrap());
+    }
+
+    #[rstest(input, expected,
+        case::max_label(&"a".repeat(63), Ok(64 + 1)),
+        case::label_too_long(&"a".repeat(64), Err(LayerError::Unexpected("dns label of 64 bytes exceeds 63 bytes".to_string()))),
+        // 4 labels of 63 bytes, 4 * 64 + 1 bytes once encoded
+        case::name_too_long(&vec!["a".repeat(63); 4].join("."), Err(LayerError::Unexpected("dns name of 257 bytes exceeds 255 bytes".to_string()))),
+        // 3 labels of 63 bytes and one of 61 bytes
+        case::max_name(&format!("{}.{}", vec!["a".repeat(63); 3].join("."), "a".repeat(61)), Ok(255)),
+    )]
+    fn test_dns_encode_name_len(input: &str, expected: Result<usize, LayerError>) {
+        assert_eq!(expected, dns_encode_name(input).map(|v| v.len()));
+    }
+
+    #[rstest(input, offset, expected,
+        case(&hex!("03777777076578616d706c6503636f6d00"), 0, ("www.example.com", 17)),
+        case(&hex!("00"), 0, ("", 1)),
+        // "www.example.com" followed by "foo" and a pointer to "example.com"
+        case(&hex!("03777777076578616d706c6503636f6d00 03666f6fc004"), 17, ("foo.example.com", 23)),
+        // pointer only
+        case(&hex!("03777777076578616d706c6503636f6d00 c000"), 17, ("www.example.com", 19)),
+        #[should_panic(expected = "Parse(\"dns name out of bounds\")")]
+        case(&hex!("03777777"), 0, ("", 0)),
+        #[should_panic(expected = "Parse(\"invalid dns label type\")")]
+        case(&hex!("40"), 0, ("", 0)),
+        #[should_panic(expected = "Parse(\"dns name pointer loop\")")]
+        case::self_pointer(&hex!("c000"), 0, ("", 0)),
+        #[should_panic(expected = "Parse(\"dns name pointer loop\")")]
+        case::pointer_cycle(&hex!("03666f6fc006 03626172c000"), 0, ("", 0)),
+    )]
+    fn test_dns_decode_name(input: &[u8], offset: usize, expected: (&str, usize)) {
+        let (name, end) = dns_decode_name(input, offset).unwrap();
+        assert_eq!(expected, (name.as_str(), end));
+    }
+
+    #[test]
+    fn test_dns_decode_name_pointer_limit() {
+        // "com" followed by a chain of 3 pointers, each to the previous one
+        let input = hex!("03636f6d00 c000 c005 c007");
+
+        assert_eq!(
+            ("com".to_string(), 11),
+            dns_decode_name_with_limit(&input, 9, 3).unwrap()
+        );
+        assert_eq!(
+            Err(LayerError::Parse("dns name pointer loop".to_string())),
+            dns_decode_name_with_limit(&input, 9, 2)
+        );
+    }
+
+    #[test]
+    fn test_dns_name_roundtrip() {
+        let encoded = dns_encode_name("www.example.com").unwrap();
+        let (name, end) = dns_decode_name(&encoded, 0).unwrap();
+
+        assert_eq!("www.example.com", name);
+        assert_eq!(encoded.len(), end);
+    }
+}
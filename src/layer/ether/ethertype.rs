@@ -2,7 +2,7 @@ use deku::prelude::*;
 
 // Inspired from https://github.com/secdev/scapy/blob/master/scapy/libs/ethertypes.py
 
-#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, DekuRead, DekuWrite)]
 #[deku(
     type = "u16",
     ctx = "endian: deku::ctx::Endian",
@@ -10,12 +10,6 @@ use deku::prelude::*;
     endian = "endian"
 )]
 pub enum EtherType {
-    /// IEEE 802.3 packet
-    #[deku(id = "0x0004")]
-    IEEE8023,
-    /// Xerox PUP protocol - see 0A00
-    #[deku(id = "0x0200")]
-    PUP,
     /// XNS
     #[deku(id = "0x0600")]
     NS,
@@ -67,6 +61,9 @@ pub enum EtherType {
     /// Datapoint Corporation (RCL lan protocol)
     #[deku(id = "0x1995")]
     RCL,
+    /// Audio Video Transport Protocol (IEEE 1722)
+    #[deku(id = "0x22f0")]
+    AVTP,
     /// 3Com NBP Connect complete not registered
     #[deku(id = "0x3c04")]
     NBPCC,
@@ -241,9 +238,15 @@ pub enum EtherType {
     /// Link Layer Discovery Protocol
     #[deku(id = "0x88cc")]
     LLDP,
+    /// MAC Security (IEEE 802.1AE)
+    #[deku(id = "0x88e5")]
+    MACSEC,
     /// 802.1Q Provider Backbone Bridging
     #[deku(id = "0x88e7")]
     PBB,
+    /// Precision Time Protocol (IEEE 1588)
+    #[deku(id = "0x88f7")]
+    PTP,
     /// 3Com (Formerly Bridge Communications), XNS Systems Management
     #[deku(id = "0x9001")]
     XNSSM,
@@ -262,6 +265,107 @@ pub enum EtherType {
     /// Maximum valid ethernet type, reserved
     #[deku(id = "0xffff")]
     MAX,
+    /// IEEE 802.3 length of the payload, which starts with an LLC header
+    #[deku(id_pat = "0x0000..=0x05dc")]
+    Length(u16),
+    /// Ether type with no known variant
+    #[deku(id_pat = "_")]
+    Unknown(u16),
+}
+
+impl EtherType {
+    /// Returns all the known ether types, by increasing number
+    ///
+    /// `Length` and `Unknown` are not included, they cover ranges of values
+    pub fn all() -> &'static [EtherType] {
+        &[
+            EtherType::NS,
+            EtherType::NSAT,
+            EtherType::DLOG1,
+            EtherType::DLOG2,
+            EtherType::IPv4,
+            EtherType::X75,
+            EtherType::NBS,
+            EtherType::ECMA,
+            EtherType::CHAOS,
+            EtherType::X25,
+            EtherType::ARP,
+            EtherType::FRARP,
+            EtherType::VINES,
+            EtherType::TRAIL,
+            EtherType::DCA,
+            EtherType::VALID,
+            EtherType::RCL,
+            EtherType::AVTP,
+            EtherType::NBPCC,
+            EtherType::NBPDG,
+            EtherType::PCS,
+            EtherType::IMLBL,
+            EtherType::MOPDL,
+            EtherType::MOPRC,
+            EtherType::LAT,
+            EtherType::SCA,
+            EtherType::AMBER,
+            EtherType::RAWFR,
+            EtherType::UBDL,
+            EtherType::UBNIU,
+            EtherType::UBNMC,
+            EtherType::UBBST,
+            EtherType::OS9,
+            EtherType::RACAL,
+            EtherType::HP,
+            EtherType::TIGAN,
+            EtherType::DECAM,
+            EtherType::VEXP,
+            EtherType::VPROD,
+            EtherType::ES,
+            EtherType::VEECO,
+            EtherType::ATT,
+            EtherType::MATRA,
+            EtherType::DDE,
+            EtherType::MERIT,
+            EtherType::ATALK,
+            EtherType::PACER,
+            EtherType::SNA,
+            EtherType::RETIX,
+            EtherType::AARP,
+            EtherType::VLAN,
+            EtherType::BOFL,
+            EtherType::HAYES,
+            EtherType::VGLAB,
+            EtherType::IPX,
+            EtherType::MUMPS,
+            EtherType::FLIP,
+            EtherType::NCD,
+            EtherType::ALPHA,
+            EtherType::SNMP,
+            EtherType::XTP,
+            EtherType::SGITW,
+            EtherType::STP,
+            EtherType::IPv6,
+            EtherType::RDP,
+            EtherType::MICP,
+            EtherType::IPAS,
+            EtherType::SLOW,
+            EtherType::PPP,
+            EtherType::MPLS,
+            EtherType::AXIS,
+            EtherType::PPPOE,
+            EtherType::PAE,
+            EtherType::AOE,
+            EtherType::QINQ,
+            EtherType::LLDP,
+            EtherType::MACSEC,
+            EtherType::PBB,
+            EtherType::PTP,
+            EtherType::XNSSM,
+            EtherType::TCPSM,
+            EtherType::DEBNI,
+            EtherType::SONIX,
+            EtherType::VITAL,
+            EtherType::MAX,
+        ]
+    }
 }
 
 impl Default for EtherType {
@@ -285,6 +389,40 @@ mod tests {
         assert_eq!(test_data, ret_write);
     }
 
+    #[test]
+    fn test_ethertype_length() {
+        let test_data = [0x00u8, 0x26].to_vec();
+
+        let (_rest, ret_read) = EtherType::from_bytes((&test_data, 0)).unwrap();
+        assert_eq!(EtherType::Length(38), ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(test_data, ret_write);
+    }
+
+    #[test]
+    fn test_ethertype_unknown() {
+        let test_data = [0x88u8, 0xb5].to_vec();
+
+        let (_rest, ret_read) = EtherType::from_bytes((&test_data, 0)).unwrap();
+        assert_eq!(EtherType::Unknown(0x88b5), ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(test_data, ret_write);
+    }
+
+    #[test]
+    fn test_ethertype_all() {
+        let all = EtherType::all();
+        assert_eq!(85, all.len());
+
+        for ether_type in all {
+            let ret_write = ether_type.to_bytes().unwrap();
+            let (_rest, ret_read) = EtherType::from_bytes((&ret_write, 0)).unwrap();
+            assert_eq!(ether_type, &ret_read);
+        }
+    }
+
     #[test]
     fn test_ethertype_default() {
         assert_eq!(EtherType::IPv4, EtherType::default())
@@ -51,6 +51,17 @@ impl std::str::FromStr for MacAddress {
     }
 }
 
+impl std::fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,4 +96,11 @@ mod tests {
         let mac: Result<MacAddress, LayerError> = input.parse();
         assert_eq!(expected, mac);
     }
+
+    #[test]
+    fn test_macaddress_display() {
+        let mac = MacAddress([0xAA, 0xFF, 0xFF, 0xFF, 0x0F, 0xBB]);
+        assert_eq!("aa:ff:ff:ff:0f:bb", mac.to_string());
+        assert_eq!(Ok(mac.clone()), mac.to_string().parse());
+    }
 }
@@ -5,11 +5,15 @@ Ethernet layer
 mod ethertype;
 mod macaddress;
 
+use crate::layer::LayerError;
 use deku::prelude::*;
 
 pub use ethertype::EtherType;
 pub use macaddress::MacAddress;
 
+/// Ethernet preamble followed by the start frame delimiter (SFD)
+pub const ETHER_PREAMBLE: [u8; 8] = [0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0xd5];
+
 /**
 Ethernet Frame Header
 
@@ -39,6 +43,46 @@ pub struct Ether {
     pub ether_type: EtherType,
 }
 
+impl Ether {
+    /// Returns true if the frame carries a VLAN tag (802.1Q or 802.1ad)
+    pub fn is_vlan_tagged(&self) -> bool {
+        matches!(self.ether_type, EtherType::VLAN | EtherType::QINQ)
+    }
+
+    /// Read an Ethernet header, reading the addresses when the ether type is cut off
+    ///
+    /// Returns the remaining bytes, the header and a note describing the truncated ether type,
+    /// if any. The ether type of a truncated header is `EtherType::Unknown(0)`, which is never
+    /// read from bytes, and the byte of a partial ether type is left in the remaining bytes.
+    /// Input too short for the addresses returns the Incomplete parse error, other parse errors
+    /// are returned as-is.
+    pub fn from_bytes_lenient(input: &[u8]) -> Result<(&[u8], Ether, Option<String>), LayerError> {
+        match Ether::from_bytes((input, 0)) {
+            Ok(((rest, _), ether)) => return Ok((rest, ether, None)),
+            Err(DekuError::Incomplete(_)) if input.len() >= 12 => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let mut ether = Ether {
+            ether_type: EtherType::Unknown(0),
+            ..Ether::default()
+        };
+        ether.dst.0.copy_from_slice(&input[..6]);
+        ether.src.0.copy_from_slice(&input[6..12]);
+
+        let rest = &input[12..];
+        let note = format!("ether type cut off, read {} of 2 bytes", rest.len());
+
+        Ok((rest, ether, Some(note)))
+    }
+}
+
+impl std::fmt::Display for Ether {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ether {} > {} type={:?}", self.src, self.dst, self.ether_type)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -61,6 +105,46 @@ mod tests {
         assert_eq!(input.to_vec(), ret_write);
     }
 
+    #[rstest(ether_type, expected,
+        case(EtherType::IPv4, false),
+        case(EtherType::VLAN, true),
+        case(EtherType::QINQ, true),
+    )]
+    fn test_ether_is_vlan_tagged(ether_type: EtherType, expected: bool) {
+        let ether = Ether {
+            ether_type,
+            ..Ether::default()
+        };
+
+        assert_eq!(expected, ether.is_vlan_tagged());
+    }
+
+    #[rstest(input, expected_rest, expected_ether_type, expected_note,
+        case::complete(&hex!("feff200001000000010000000800 aabb"), &hex!("aabb"), EtherType::IPv4, None),
+        case::no_ether_type(&hex!("feff20000100000001000000"), &[], EtherType::Unknown(0), Some("ether type cut off, read 0 of 2 bytes")),
+        case::partial_ether_type(&hex!("feff2000010000000100000008"), &hex!("08"), EtherType::Unknown(0), Some("ether type cut off, read 1 of 2 bytes")),
+    )]
+    fn test_ether_from_bytes_lenient(
+        input: &[u8],
+        expected_rest: &[u8],
+        expected_ether_type: EtherType,
+        expected_note: Option<&str>,
+    ) {
+        let (rest, ether, note) = Ether::from_bytes_lenient(input).unwrap();
+
+        assert_eq!(expected_rest, rest);
+        assert_eq!(expected_note.map(|v| v.to_string()), note);
+        assert_eq!(MacAddress([0xfe, 0xff, 0x20, 0x00, 0x01, 0x00]), ether.dst);
+        assert_eq!(MacAddress([0x00, 0x00, 0x01, 0x00, 0x00, 0x00]), ether.src);
+        assert_eq!(expected_ether_type, ether.ether_type);
+
+        // the addresses are cut off
+        assert!(matches!(
+            Ether::from_bytes_lenient(&input[..9]),
+            Err(LayerError::DekuError(_))
+        ));
+    }
+
     #[test]
     fn test_ether_default() {
         assert_eq!(
@@ -72,4 +156,18 @@ mod tests {
             Ether::default()
         )
     }
+
+    #[test]
+    fn test_ether_display() {
+        let ether = Ether {
+            dst: MacAddress([0xde, 0xad, 0xbe, 0xef, 0xc0, 0xfe]),
+            src: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            ether_type: EtherType::IPv4,
+        };
+
+        assert_eq!(
+            "Ether 00:11:22:33:44:55 > de:ad:be:ef:c0:fe type=IPv4",
+            ether.to_string()
+        );
+    }
 }
@@ -0,0 +1,199 @@
+/*!
+GTP-U layer
+*/
+use crate::layer::LayerError;
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+use std::convert::TryFrom;
+
+/// Well-known UDP port for GTP-U
+pub const GTPU_PORT: u16 = 2152;
+
+/// Message type of a G-PDU, carrying a user packet
+pub const GTPU_G_PDU: u8 = 0xff;
+
+/// Length of the mandatory GTP-U header, not included in the length field
+pub const GTPU_HEADER_LEN: usize = 8;
+
+/// GTP-U optional fields, present if any of the E, S or PN flags is set
+#[derive(Debug, PartialEq, Clone, Default, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct GtpuOptional {
+    pub sequence: u16,          // Sequence Number
+    pub npdu: u8,               // N-PDU Number
+    pub next_extension_type: u8, // Next Extension Header Type
+}
+
+/// GTP-U extension header
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct GtpuExtension {
+    /// Length of the extension header in 4-octet units
+    #[deku(update = "(content.len() + 2) / 4")]
+    pub length: u8,
+    #[deku(
+        count = "(usize::from(*length) * 4).checked_sub(2).ok_or_else(|| DekuError::Parse(\"invalid gtpu extension length\".to_string()))?"
+    )]
+    pub content: Vec<u8>,
+    pub next_extension_type: u8,
+}
+
+/**
+GTP-U Header
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|Version|P|R|E|S|N| Message Type  |            Length             |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|               Tunnel Endpoint Identifier (TEID)               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|        Sequence Number        |  N-PDU Number |  Next Ext Hdr |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                       Extension Headers                       |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Gtpu {
+    #[deku(bits = "3")]
+    pub version: u8, // Version, 1 for GTPv1
+    #[deku(bits = "1")]
+    pub protocol_type: u8, // Protocol Type, 1 for GTP
+    #[deku(bits = "1")]
+    pub reserved: u8,
+    #[deku(bits = "1")]
+    pub extension_flag: u8, // Extension Header flag (E)
+    #[deku(bits = "1")]
+    pub sequence_flag: u8, // Sequence Number flag (S)
+    #[deku(bits = "1")]
+    pub npdu_flag: u8, // N-PDU Number flag (PN)
+    pub message_type: u8,
+    pub length: u16, // Length following the mandatory header
+    pub teid: u32,   // Tunnel Endpoint Identifier
+    #[deku(cond = "*extension_flag == 1 || *sequence_flag == 1 || *npdu_flag == 1")]
+    pub optional: Option<GtpuOptional>,
+    #[deku(reader = "Gtpu::read_extensions(optional, deku::rest)")]
+    pub extensions: Vec<GtpuExtension>,
+}
+
+impl Gtpu {
+    fn read_extensions<'a>(
+        optional: &Option<GtpuOptional>,
+        rest: &'a BitSlice<Msb0, u8>,
+    ) -> Result<(&'a BitSlice<Msb0, u8>, Vec<GtpuExtension>), DekuError> {
+        let mut next_extension_type = optional.as_ref().map_or(0, |v| v.next_extension_type);
+
+        let mut rest = rest;
+        let mut extensions = Vec::new();
+        while next_extension_type != 0 {
+            let (new_rest, extension) = GtpuExtension::read(rest, deku::ctx::Endian::Big)?;
+            next_extension_type = extension.next_extension_type;
+
+            extensions.push(extension);
+            rest = new_rest;
+        }
+
+        Ok((rest, extensions))
+    }
+
+    /// Update the length from the written bytes of the following layers
+    pub fn update_length_payload(&mut self, data_buf: &[u8]) -> Result<(), LayerError> {
+        let header = self.to_bytes()?;
+        self.length = u16::try_from(header.len() - GTPU_HEADER_LEN + data_buf.len())?;
+
+        Ok(())
+    }
+}
+
+impl Default for Gtpu {
+    fn default() -> Self {
+        Gtpu {
+            version: 1,
+            protocol_type: 1,
+            reserved: 0,
+            extension_flag: 0,
+            sequence_flag: 0,
+            npdu_flag: 0,
+            message_type: GTPU_G_PDU,
+            length: 0,
+            teid: 0,
+            optional: None,
+            extensions: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::mandatory(
+            &hex!("30ff000412345678"),
+            Gtpu {
+                length: 4,
+                teid: 0x12345678,
+                ..Gtpu::default()
+            },
+        ),
+        case::sequence(
+            &hex!("32ff000812345678 00010000"),
+            Gtpu {
+                sequence_flag: 1,
+                length: 8,
+                teid: 0x12345678,
+                optional: Some(GtpuOptional {
+                    sequence: 1,
+                    npdu: 0,
+                    next_extension_type: 0,
+                }),
+                ..Gtpu::default()
+            },
+        ),
+        case::pdu_session_container(
+            &hex!("34ff001012345678 00000085 01 0009 00"),
+            Gtpu {
+                extension_flag: 1,
+                length: 16,
+                teid: 0x12345678,
+                optional: Some(GtpuOptional {
+                    sequence: 0,
+                    npdu: 0,
+                    next_extension_type: 0x85,
+                }),
+                extensions: vec![GtpuExtension {
+                    length: 1,
+                    content: vec![0x00, 0x09],
+                    next_extension_type: 0,
+                }],
+                ..Gtpu::default()
+            },
+        ),
+        #[should_panic(expected = "invalid gtpu extension length")]
+        case::extension_length_zero(&hex!("34ff001012345678 00000085 00 0009 00"), Gtpu::default()),
+    )]
+    fn test_gtpu(input: &[u8], expected: Gtpu) {
+        let (_rest, ret_read) = Gtpu::from_bytes((input, 0)).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_gtpu_update_length_payload() {
+        let mut gtpu = Gtpu {
+            sequence_flag: 1,
+            optional: Some(GtpuOptional::default()),
+            ..Gtpu::default()
+        };
+
+        gtpu.update_length_payload(&[0; 20]).unwrap();
+        assert_eq!(24, gtpu.length);
+    }
+}
@@ -0,0 +1,119 @@
+/*!
+ICMP layer
+*/
+use crate::layer::ip::checksum;
+use crate::layer::LayerError;
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/// ICMP echo reply
+pub const ICMP_ECHO_REPLY: u8 = 0;
+
+/// ICMP destination unreachable
+pub const ICMP_DEST_UNREACHABLE: u8 = 3;
+
+/// ICMP echo request
+pub const ICMP_ECHO_REQUEST: u8 = 8;
+
+/// ICMP time exceeded
+pub const ICMP_TIME_EXCEEDED: u8 = 11;
+
+// read all the rest of the message
+fn read_data(rest: &BitSlice<Msb0, u8>) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+    let ret = rest.as_raw_slice().to_vec();
+    let (empty, _rest) = rest.split_at(0);
+    Ok((empty, ret))
+}
+
+/**
+ICMP Message
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|     Type      |     Code      |          Checksum             |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                        Rest of Header                         |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                             Data                              |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Icmp {
+    pub icmp_type: u8,
+    pub code: u8,
+    #[deku(update = "self.update_checksum()?")]
+    pub checksum: u16,
+    pub rest_of_header: u32, // Identifier and sequence number of an echo, unused otherwise
+    /// Echo data, or the quoted datagram of an error message
+    #[deku(reader = "read_data(deku::rest)")]
+    pub data: Vec<u8>,
+}
+
+impl Icmp {
+    fn update_checksum(&self) -> Result<u16, DekuError> {
+        let mut icmp = self.clone();
+        icmp.checksum = 0;
+
+        checksum(&icmp.to_bytes()?).map_err(|e| DekuError::InvalidParam(e.to_string()))
+    }
+
+    /// Returns the checksum of the layer, without updating it
+    pub fn compute_checksum(&self) -> Result<u16, LayerError> {
+        Ok(self.update_checksum()?)
+    }
+}
+
+impl Default for Icmp {
+    fn default() -> Self {
+        Icmp {
+            icmp_type: ICMP_ECHO_REQUEST,
+            code: 0,
+            checksum: 0xf7ff,
+            rest_of_header: 0,
+            data: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::echo_request(
+            &hex!("0800b42b00010001 68656c6c6f"),
+            Icmp {
+                icmp_type: ICMP_ECHO_REQUEST,
+                code: 0,
+                checksum: 0xb42b,
+                rest_of_header: 0x00010001,
+                data: b"hello".to_vec(),
+            },
+        ),
+    )]
+    fn test_icmp(input: &[u8], expected: Icmp) {
+        let (_rest, ret_read) = Icmp::from_bytes((input, 0)).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_icmp_update_checksum() {
+        let mut icmp = Icmp {
+            rest_of_header: 0x00010001,
+            data: b"hello".to_vec(),
+            ..Icmp::default()
+        };
+
+        icmp.update().unwrap();
+        assert_eq!(0xb42b, icmp.checksum);
+    }
+}
@@ -0,0 +1,293 @@
+/*!
+IGMP layer
+*/
+use crate::layer::ip::checksum;
+use crate::layer::LayerError;
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+use std::net::Ipv4Addr;
+
+/// IGMP membership query
+pub const IGMP_MEMBERSHIP_QUERY: u8 = 0x11;
+
+/// IGMPv2 membership report
+pub const IGMP_V2_MEMBERSHIP_REPORT: u8 = 0x16;
+
+/// IGMPv2 leave group
+pub const IGMP_V2_LEAVE_GROUP: u8 = 0x17;
+
+/// IGMPv3 membership report
+pub const IGMP_V3_MEMBERSHIP_REPORT: u8 = 0x22;
+
+// read all the rest of the message
+fn read_data(rest: &BitSlice<Msb0, u8>) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+    let ret = rest.as_raw_slice().to_vec();
+    let (empty, _rest) = rest.split_at(0);
+    Ok((empty, ret))
+}
+
+/**
+IGMPv3 Group Record
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  Record Type  |  Aux Data Len |     Number of Sources (N)     |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                       Multicast Address                       |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                       Source Address [1..N]                   |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                         Auxiliary Data                        |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct IgmpGroupRecord {
+    pub record_type: u8,
+    #[deku(update = "self.aux_data.len() / 4")]
+    pub aux_data_len: u8, // in 32-bit words
+    #[deku(update = "self.sources.len()")]
+    pub num_sources: u16,
+    pub multicast_address: Ipv4Addr,
+    #[deku(count = "num_sources")]
+    pub sources: Vec<Ipv4Addr>,
+    #[deku(count = "usize::from(*aux_data_len) * 4")]
+    pub aux_data: Vec<u8>,
+}
+
+// update the counts of each group record
+fn update_records(records: &[IgmpGroupRecord]) -> Result<Vec<IgmpGroupRecord>, DekuError> {
+    let mut records = records.to_vec();
+    for record in records.iter_mut() {
+        record.update()?;
+    }
+
+    Ok(records)
+}
+
+/// IGMP message body, determined by the message type
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "igmp_type: u8", id = "igmp_type", endian = "big")]
+pub enum IgmpMessage {
+    #[deku(id = "0x22")]
+    V3Report {
+        reserved: u16,
+        #[deku(update = "records.len()")]
+        num_records: u16,
+        #[deku(count = "num_records", update = "update_records(records)?")]
+        records: Vec<IgmpGroupRecord>,
+    },
+    /// Query, IGMPv1/v2 report and leave group. The IGMPv3 query fields are kept as bytes.
+    #[deku(id_pat = "_")]
+    Group {
+        group: Ipv4Addr,
+        #[deku(reader = "read_data(deku::rest)")]
+        data: Vec<u8>,
+    },
+}
+
+/**
+IGMP Message
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|      Type     | Max Resp Time |           Checksum            |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                            Message                            |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Igmp {
+    pub igmp_type: u8,
+    pub max_resp_time: u8, // Reserved in an IGMPv3 report
+    #[deku(update = "self.update_checksum()?")]
+    pub checksum: u16,
+    #[deku(ctx = "*igmp_type", update = "self.update_message()?")]
+    pub message: IgmpMessage,
+}
+
+impl Igmp {
+    fn update_message(&self) -> Result<IgmpMessage, DekuError> {
+        let mut message = self.message.clone();
+        message.update()?;
+        Ok(message)
+    }
+
+    fn update_checksum(&self) -> Result<u16, DekuError> {
+        // the message is updated after the checksum field
+        let mut igmp = self.clone();
+        igmp.message = self.update_message()?;
+        igmp.checksum = 0;
+
+        checksum(&igmp.to_bytes()?).map_err(|e| DekuError::InvalidParam(e.to_string()))
+    }
+
+    /// Returns the checksum of the layer as written, without updating it
+    ///
+    /// Unlike `update`, the counts of the message are not updated first
+    pub fn compute_checksum(&self) -> Result<u16, LayerError> {
+        let mut igmp = self.clone();
+        igmp.checksum = 0;
+
+        checksum(&igmp.to_bytes()?)
+    }
+
+    /// Returns the group records of an IGMPv3 report
+    pub fn group_records(&self) -> &[IgmpGroupRecord] {
+        match &self.message {
+            IgmpMessage::V3Report { records, .. } => records,
+            _ => &[],
+        }
+    }
+}
+
+impl Default for Igmp {
+    fn default() -> Self {
+        Igmp {
+            igmp_type: IGMP_V2_MEMBERSHIP_REPORT,
+            max_resp_time: 0,
+            checksum: 0xe9ff,
+            message: IgmpMessage::Group {
+                group: Ipv4Addr::UNSPECIFIED,
+                data: vec![],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::v2_report(
+            &hex!("160009e9e0000016"),
+            Igmp {
+                igmp_type: IGMP_V2_MEMBERSHIP_REPORT,
+                max_resp_time: 0,
+                checksum: 0x09e9,
+                message: IgmpMessage::Group {
+                    group: Ipv4Addr::new(224, 0, 0, 22),
+                    data: vec![],
+                },
+            },
+        ),
+        case::v3_report(
+            &hex!("22008a9d00000002 01000002e1010101c0a80001c0a80002 02000001e10202020a000001"),
+            Igmp {
+                igmp_type: IGMP_V3_MEMBERSHIP_REPORT,
+                max_resp_time: 0,
+                checksum: 0x8a9d,
+                message: IgmpMessage::V3Report {
+                    reserved: 0,
+                    num_records: 2,
+                    records: vec![
+                        IgmpGroupRecord {
+                            record_type: 1,
+                            aux_data_len: 0,
+                            num_sources: 2,
+                            multicast_address: Ipv4Addr::new(225, 1, 1, 1),
+                            sources: vec![
+                                Ipv4Addr::new(192, 168, 0, 1),
+                                Ipv4Addr::new(192, 168, 0, 2),
+                            ],
+                            aux_data: vec![],
+                        },
+                        IgmpGroupRecord {
+                            record_type: 2,
+                            aux_data_len: 0,
+                            num_sources: 1,
+                            multicast_address: Ipv4Addr::new(225, 2, 2, 2),
+                            sources: vec![Ipv4Addr::new(10, 0, 0, 1)],
+                            aux_data: vec![],
+                        },
+                    ],
+                },
+            },
+        ),
+    )]
+    fn test_igmp(input: &[u8], expected: Igmp) {
+        let ret_read = Igmp::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_igmp_group_records() {
+        let igmp = Igmp::try_from(
+            hex!("22008a9d00000002 01000002e1010101c0a80001c0a80002 02000001e10202020a000001")
+                .as_ref(),
+        )
+        .unwrap();
+
+        let records = igmp.group_records();
+        assert_eq!(2, records.len());
+        assert_eq!(Ipv4Addr::new(225, 1, 1, 1), records[0].multicast_address);
+        assert_eq!(Ipv4Addr::new(192, 168, 0, 2), records[0].sources[1]);
+        assert_eq!(Ipv4Addr::new(225, 2, 2, 2), records[1].multicast_address);
+        assert_eq!(vec![Ipv4Addr::new(10, 0, 0, 1)], records[1].sources);
+    }
+
+    #[test]
+    fn test_igmp_update() {
+        let mut igmp = Igmp {
+            igmp_type: IGMP_V3_MEMBERSHIP_REPORT,
+            max_resp_time: 0,
+            checksum: 0,
+            message: IgmpMessage::V3Report {
+                reserved: 0,
+                num_records: 0,
+                records: vec![
+                    IgmpGroupRecord {
+                        record_type: 1,
+                        aux_data_len: 0,
+                        num_sources: 0,
+                        multicast_address: Ipv4Addr::new(225, 1, 1, 1),
+                        sources: vec![
+                            Ipv4Addr::new(192, 168, 0, 1),
+                            Ipv4Addr::new(192, 168, 0, 2),
+                        ],
+                        aux_data: vec![],
+                    },
+                    IgmpGroupRecord {
+                        record_type: 2,
+                        aux_data_len: 0,
+                        num_sources: 0,
+                        multicast_address: Ipv4Addr::new(225, 2, 2, 2),
+                        sources: vec![Ipv4Addr::new(10, 0, 0, 1)],
+                        aux_data: vec![],
+                    },
+                ],
+            },
+        };
+
+        igmp.update().unwrap();
+
+        assert_eq!(0x8a9d, igmp.checksum);
+        assert_eq!(
+            hex!("22008a9d00000002 01000002e1010101c0a80001c0a80002 02000001e10202020a000001")
+                .to_vec(),
+            igmp.to_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_igmp_default() {
+        let mut igmp = Igmp::default();
+        igmp.update().unwrap();
+
+        assert_eq!(Igmp::default(), igmp);
+    }
+}
@@ -1,7 +1,7 @@
 use super::checksum;
 use super::IpProtocol;
 use crate::layer::{Layer, LayerError, LayerValidate, ValidationError};
-use deku::bitvec::{BitSlice, Msb0};
+use deku::bitvec::{BitSlice, BitVec, BitView, Msb0};
 use deku::prelude::*;
 use std::convert::TryFrom;
 use std::net::Ipv4Addr;
@@ -111,6 +111,8 @@ pub struct Ipv4 {
     pub dst: Ipv4Addr,       // Destination IP Address
     #[deku(reader = "Ipv4::read_options(*ihl, deku::rest)")]
     pub options: Vec<Ipv4Option>,
+    #[deku(reader = "Ipv4::read_padding(*ihl, options, deku::rest)")]
+    pub padding: Vec<u8>, // Padding following an End of Option List
 }
 
 impl Ipv4 {
@@ -124,13 +126,136 @@ impl Ipv4 {
         checksum(&ipv4).map_err(|e| DekuError::InvalidParam(e.to_string()))
     }
 
+    /// Returns the checksum of the header, without updating it
+    pub fn compute_checksum(&self) -> Result<u16, LayerError> {
+        Ok(self.update_checksum()?)
+    }
+
+    /// Returns the header length in bytes
+    pub fn header_len(&self) -> usize {
+        usize::from(self.ihl) * 4
+    }
+
+    /// Returns true if the headers are equal, ignoring the `checksum` and `length` fields
+    pub fn structurally_equal(&self, other: &Ipv4) -> bool {
+        let normalize = |ipv4: &Ipv4| Ipv4 {
+            checksum: 0,
+            length: 0,
+            ..ipv4.clone()
+        };
+
+        normalize(self) == normalize(other)
+    }
+
+    /// Pad the options to a 32-bit boundary and set the ihl to include them
+    ///
+    /// An End of Option List is appended when padding is needed, followed by zero padding
+    pub fn pad_options(&mut self) -> Result<(), LayerError> {
+        let mut options_bits: BitVec<Msb0, u8> = BitVec::new();
+        for option in &self.options {
+            option.write(&mut options_bits, deku::ctx::Endian::Big)?;
+        }
+        let options_len = options_bits.len() / 8;
+
+        let padding_len = (4 - options_len % 4) % 4;
+        let words = (options_len + padding_len) / 4;
+        if words > 10 {
+            return Err(LayerError::Unexpected(format!(
+                "ipv4 options of {} bytes exceed 40 bytes",
+                options_len
+            )));
+        }
+
+        let is_eool = |v: &Ipv4Option| v.option == Ipv4OptionType::EOOL;
+        self.padding = if padding_len == 0 || self.options.last().map_or(false, is_eool) {
+            vec![0x00; padding_len]
+        } else {
+            self.options.push(Ipv4Option {
+                copied: 0,
+                class: Ipv4OptionClass::Control,
+                option: Ipv4OptionType::EOOL,
+            });
+            vec![0x00; padding_len - 1]
+        };
+        self.ihl = 5 + words as u8;
+
+        Ok(())
+    }
+
+    /// Read an Ipv4 header, reading as many complete options as fit when the ihl exceeds the
+    /// available data
+    ///
+    /// Returns the remaining bytes, the header and a note describing the truncated options, if
+    /// any. The ihl is kept as read, other parse errors are returned as-is.
+    pub fn from_bytes_lenient(input: &[u8]) -> Result<(&[u8], Ipv4, Option<String>), LayerError> {
+        let err = match Ipv4::from_bytes((input, 0)) {
+            Ok(((rest, _), ipv4)) => return Ok((rest, ipv4, None)),
+            Err(e) => e,
+        };
+
+        let ihl = match input.first() {
+            Some(v) => v & 0x0f,
+            None => return Err(err.into()),
+        };
+        let options_len = usize::from(ihl).saturating_sub(5) * 4;
+        if options_len == 0 || 20 + options_len <= input.len() {
+            return Err(err.into());
+        }
+
+        // Read the fixed header as if it had no options
+        let mut header = input[..20].to_vec();
+        header[0] = (header[0] & 0xf0) | 5;
+        let (_rest, mut ipv4) = Ipv4::from_bytes((header.as_ref(), 0))?;
+        ipv4.ihl = ihl;
+
+        let mut option_rest = input[20..].view_bits::<Msb0>();
+        while let Ok((option_rest_new, ipv4_option)) =
+            Ipv4Option::read(option_rest, deku::ctx::Endian::Big)
+        {
+            let is_eool = ipv4_option.option == Ipv4OptionType::EOOL;
+
+            ipv4.options.push(ipv4_option);
+            option_rest = option_rest_new;
+
+            // End of Option List, the remaining bytes are padding
+            if is_eool {
+                ipv4.padding = option_rest.as_raw_slice().to_vec();
+                option_rest = &option_rest[option_rest.len()..];
+                break;
+            }
+        }
+
+        let rest = &input[input.len() - option_rest.len() / 8..];
+        let note = format!(
+            "ipv4 ihl {} exceeds the available data, read {} of {} option bytes",
+            ihl,
+            input.len() - 20 - rest.len(),
+            options_len
+        );
+
+        Ok((rest, ipv4, Some(note)))
+    }
+
+    /// Increment the identification, wrapping around at `u16::MAX`, and return it
+    ///
+    /// A series is seeded by setting `identification` to the value preceding the first one
+    pub fn next_identification(&mut self) -> u16 {
+        self.identification = self.identification.wrapping_add(1);
+        self.identification
+    }
+
     pub fn update_length(&mut self, data: &[Layer]) -> Result<(), LayerError> {
-        let header = self.to_bytes()?;
         let mut data_buf = Vec::new();
         for layer in data {
             data_buf.extend(layer.to_bytes()?)
         }
 
+        self.update_length_payload(&data_buf)
+    }
+
+    /// Update the length from the written bytes of the following layers
+    pub fn update_length_payload(&mut self, data_buf: &[u8]) -> Result<(), LayerError> {
+        let header = self.to_bytes()?;
         self.length = u16::try_from(header.len() + data_buf.len())?;
 
         Ok(())
@@ -153,23 +278,59 @@ impl Ipv4 {
                 ));
             }
 
-            let (mut option_rest, rest) = rest.split_at(bits);
+            let (mut option_rest, _rest) = rest.split_at(bits);
 
             let mut ipv4_options = Vec::with_capacity(1); // at-least 1
             while !option_rest.is_empty() {
-                let (option_rest_new, tcp_option) =
+                let (option_rest_new, ipv4_option) =
                     Ipv4Option::read(option_rest, deku::ctx::Endian::Big)?;
 
-                ipv4_options.push(tcp_option);
+                let is_eool = ipv4_option.option == Ipv4OptionType::EOOL;
+
+                ipv4_options.push(ipv4_option);
 
                 option_rest = option_rest_new;
+
+                // End of Option List, the remaining bytes are padding
+                if is_eool {
+                    break;
+                }
             }
 
+            // padding is left to be read by `read_padding`
+            let (_options, rest) = rest.split_at(bits - option_rest.len());
+
             Ok((rest, ipv4_options))
         } else {
             Ok((rest, vec![]))
         }
     }
+
+    fn read_padding<'a>(
+        ihl: u8, // number of 32 bit words
+        options: &[Ipv4Option],
+        rest: &'a BitSlice<Msb0, u8>,
+    ) -> Result<(&'a BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+        let mut options_bits: BitVec<Msb0, u8> = BitVec::new();
+        for option in options {
+            option.write(&mut options_bits, deku::ctx::Endian::Big)?;
+        }
+
+        let bits = (usize::from(ihl).saturating_sub(5) * 32)
+            .checked_sub(options_bits.len())
+            .ok_or_else(|| DekuError::Parse("invalid ipv4 options length".to_string()))?;
+
+        // Check split_at precondition
+        if bits > rest.len() {
+            return Err(DekuError::Parse(
+                "not enough data to read ipv4 padding".to_string(),
+            ));
+        }
+
+        let (padding, rest) = rest.split_at(bits);
+
+        Ok((rest, padding.as_raw_slice().to_vec()))
+    }
 }
 
 impl LayerValidate for Ipv4 {
@@ -182,6 +343,15 @@ impl LayerValidate for Ipv4 {
             ret.push(ValidationError::Checksum)
         }
 
+        if self.version != 4 {
+            ret.push(ValidationError::InvalidVersion)
+        }
+
+        // header is at least 5 words
+        if self.ihl < 5 {
+            ret.push(ValidationError::InvalidIhl)
+        }
+
         Ok(ret)
     }
 }
@@ -203,10 +373,21 @@ impl Default for Ipv4 {
             src: Ipv4Addr::new(127, 0, 0, 1),
             dst: Ipv4Addr::new(127, 0, 0, 1),
             options: vec![],
+            padding: vec![],
         }
     }
 }
 
+impl std::fmt::Display for Ipv4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "IPv4 {} > {} proto={:?} len={}",
+            self.src, self.dst, self.protocol, self.length
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,6 +413,7 @@ mod tests {
                 src: Ipv4Addr::new(145,254,160,237),
                 dst: Ipv4Addr::new(145,253,2,203),
                 options: vec![],
+                padding: vec![],
             },
         ),
 
@@ -258,6 +440,38 @@ mod tests {
                         option: Ipv4OptionType::Unknown { type_: 6, length: 40, value: vec![0, 0, 0, 1, 1, 34, 0, 1, 174, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1] }
                     }
                 ],
+                padding: vec![],
+            },
+        ),
+        case::with_eool_padding(
+            &hex!("46000018000040004001fd307f0000017f00000101000000"),
+            Ipv4 {
+                version: 4,
+                ihl: 6,
+                ecn: 0,
+                dscp: 0,
+                length: 24,
+                identification: 0,
+                flags: 2,
+                offset: 0,
+                ttl: 64,
+                protocol: IpProtocol::ICMP,
+                checksum: 0xfd30,
+                src: Ipv4Addr::new(127,0,0,1),
+                dst: Ipv4Addr::new(127,0,0,1),
+                options: vec![
+                    Ipv4Option {
+                        copied: 0,
+                        class: Ipv4OptionClass::Control,
+                        option: Ipv4OptionType::NOP,
+                    },
+                    Ipv4Option {
+                        copied: 0,
+                        class: Ipv4OptionClass::Control,
+                        option: Ipv4OptionType::EOOL,
+                    },
+                ],
+                padding: vec![0x00, 0x00],
             },
         ),
     )]
@@ -287,11 +501,204 @@ mod tests {
                 src: Ipv4Addr::new(127, 0, 0, 1),
                 dst: Ipv4Addr::new(127, 0, 0, 1),
                 options: vec![],
+                padding: vec![],
             },
             Ipv4::default()
         );
     }
 
+    #[rstest(input, expected_options, expected_padding, expected_rest, expected_note,
+        case::valid(
+            &hex!("450000140001000040060000 7f000001 7f000001"),
+            vec![],
+            vec![],
+            &[],
+            None,
+        ),
+        case::ihl_too_large(
+            &hex!("480000200001000040060000 7f000001 7f000001 0101070704"),
+            vec![
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::NOP,
+                };
+                2
+            ],
+            vec![],
+            &hex!("070704"),
+            Some("ipv4 ihl 8 exceeds the available data, read 2 of 12 option bytes"),
+        ),
+        case::ihl_too_large_eool(
+            &hex!("480000200001000040060000 7f000001 7f000001 01000000"),
+            vec![
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::NOP,
+                },
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::EOOL,
+                },
+            ],
+            vec![0x00, 0x00],
+            &[],
+            Some("ipv4 ihl 8 exceeds the available data, read 4 of 12 option bytes"),
+        ),
+    )]
+    fn test_ipv4_from_bytes_lenient(
+        input: &[u8],
+        expected_options: Vec<Ipv4Option>,
+        expected_padding: Vec<u8>,
+        expected_rest: &[u8],
+        expected_note: Option<&str>,
+    ) {
+        let (rest, ipv4, note) = Ipv4::from_bytes_lenient(input).unwrap();
+
+        assert_eq!(input[0] & 0x0f, ipv4.ihl);
+        assert_eq!(expected_options, ipv4.options);
+        assert_eq!(expected_padding, ipv4.padding);
+        assert_eq!(expected_rest, rest);
+        assert_eq!(expected_note, note.as_deref());
+    }
+
+    #[rstest(options, expected_options, expected_padding, expected_ihl,
+        case::none(vec![], vec![], vec![], 5),
+        case::record_route(
+            vec![Ipv4Option {
+                copied: 0,
+                class: Ipv4OptionClass::Control,
+                option: Ipv4OptionType::Unknown { type_: 7, length: 3, value: vec![4] },
+            }],
+            vec![
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::Unknown { type_: 7, length: 3, value: vec![4] },
+                },
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::EOOL,
+                },
+            ],
+            vec![],
+            6,
+        ),
+        case::nop(
+            vec![Ipv4Option {
+                copied: 0,
+                class: Ipv4OptionClass::Control,
+                option: Ipv4OptionType::NOP,
+            }],
+            vec![
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::NOP,
+                },
+                Ipv4Option {
+                    copied: 0,
+                    class: Ipv4OptionClass::Control,
+                    option: Ipv4OptionType::EOOL,
+                },
+            ],
+            vec![0x00, 0x00],
+            6,
+        ),
+        case::eool(
+            vec![Ipv4Option {
+                copied: 0,
+                class: Ipv4OptionClass::Control,
+                option: Ipv4OptionType::EOOL,
+            }],
+            vec![Ipv4Option {
+                copied: 0,
+                class: Ipv4OptionClass::Control,
+                option: Ipv4OptionType::EOOL,
+            }],
+            vec![0x00, 0x00, 0x00],
+            6,
+        ),
+        #[should_panic(expected = "ipv4 options of 41 bytes exceed 40 bytes")]
+        case::too_long(
+            vec![Ipv4Option {
+                copied: 0,
+                class: Ipv4OptionClass::Control,
+                option: Ipv4OptionType::NOP,
+            }; 41],
+            vec![],
+            vec![],
+            0,
+        ),
+    )]
+    fn test_ipv4_pad_options(
+        options: Vec<Ipv4Option>,
+        expected_options: Vec<Ipv4Option>,
+        expected_padding: Vec<u8>,
+        expected_ihl: u8,
+    ) {
+        let mut ipv4 = Ipv4 {
+            ihl: 5,
+            options,
+            ..Ipv4::default()
+        };
+
+        ipv4.pad_options().unwrap();
+
+        assert_eq!(expected_options, ipv4.options);
+        assert_eq!(expected_padding, ipv4.padding);
+        assert_eq!(expected_ihl, ipv4.ihl);
+        assert_eq!(ipv4.header_len(), ipv4.to_bytes().unwrap().len());
+
+        // Read back
+        let ret_read = Ipv4::try_from(ipv4.to_bytes().unwrap().as_ref()).unwrap();
+        assert_eq!(ipv4, ret_read);
+    }
+
+    #[test]
+    fn test_ipv4_header_len() {
+        let ipv4 = Ipv4::try_from(hex!("4500004b0f490000801163a591fea0ed91fd02cb").as_ref()).unwrap();
+        assert_eq!(20, ipv4.header_len());
+        assert_eq!(ipv4.to_bytes().unwrap().len(), ipv4.header_len());
+    }
+
+    #[test]
+    fn test_ipv4_structurally_equal() {
+        let ipv4 = Ipv4::try_from(hex!("450002070f4540008006901091fea0ed41d0e4df").as_ref()).unwrap();
+
+        let other = Ipv4 {
+            checksum: 0xaabb,
+            length: 20,
+            ..ipv4.clone()
+        };
+        assert!(ipv4.structurally_equal(&other));
+        assert_ne!(ipv4, other);
+
+        let other = Ipv4 {
+            ttl: 64,
+            ..ipv4.clone()
+        };
+        assert!(!ipv4.structurally_equal(&other));
+    }
+
+    #[rstest(seed, expected,
+        case(0x0000, 0x0001),
+        case(0x1234, 0x1235),
+        case(0xFFFF, 0x0000),
+    )]
+    fn test_ipv4_next_identification(seed: u16, expected: u16) {
+        let mut ipv4 = Ipv4 {
+            identification: seed,
+            ..Ipv4::default()
+        };
+
+        assert_eq!(expected, ipv4.next_identification());
+        assert_eq!(expected, ipv4.identification);
+    }
+
     #[test]
     fn test_ipv4_checksum_update() {
         let expected_checksum = 0x9010;
@@ -308,7 +715,10 @@ mod tests {
     #[rstest(input, expected,
         case::valid(&hex!("450002070f4540008006901091fea0ed41d0e4df"), vec![]),
         case::modify_chksum(&hex!("450002070f4540008006FF1091fea0ed41d0e4df"), vec![ValidationError::Checksum]),
-        case::modify_version(&hex!("550002070f4540008006901091fea0ed41d0e4df"), vec![ValidationError::Checksum]),
+        case::modify_version(&hex!("550002070f4540008006901091fea0ed41d0e4df"), vec![ValidationError::Checksum, ValidationError::InvalidVersion]),
+        case::modify_ihl(&hex!("440002070f4540008006901091fea0ed41d0e4df"), vec![ValidationError::Checksum, ValidationError::InvalidIhl]),
+        case::version_5(&hex!("550002070f4540008006801091fea0ed41d0e4df"), vec![ValidationError::InvalidVersion]),
+        case::ihl_4(&hex!("440002070f4540008006911091fea0ed41d0e4df"), vec![ValidationError::InvalidIhl]),
     )]
     fn test_ipv4_checksum_validate(input: &[u8], expected: Vec<ValidationError>) {
         let ipv4 = Ipv4::try_from(input).unwrap();
@@ -316,4 +726,16 @@ mod tests {
         // validate
         assert_eq!(expected, ipv4.validate().unwrap());
     }
+
+    #[test]
+    fn test_ipv4_display() {
+        let ipv4 = Ipv4 {
+            protocol: IpProtocol::UDP,
+            length: 39,
+            dst: Ipv4Addr::new(127, 0, 0, 2),
+            ..Ipv4::default()
+        };
+
+        assert_eq!("IPv4 127.0.0.1 > 127.0.0.2 proto=UDP len=39", ipv4.to_string());
+    }
 }
@@ -1,4 +1,4 @@
-use super::IpProtocol;
+use super::{IpProtocol, Ipv6Ext};
 use crate::layer::{Layer, LayerError};
 use deku::prelude::*;
 use std::convert::TryFrom;
@@ -51,17 +51,128 @@ pub struct Ipv6 {
     pub dst: Ipv6Addr,           // Destination IP Address
 }
 
+/// Length of the fixed Ipv6 header
+pub const IPV6_HEADER_LEN: usize = 40;
+
 impl Ipv6 {
+    /// Returns the length of the fixed header in bytes, extension headers are separate layers
+    pub fn header_len(&self) -> usize {
+        IPV6_HEADER_LEN
+    }
+
+    /// Returns true if the headers are equal, ignoring the `length` field
+    pub fn structurally_equal(&self, other: &Ipv6) -> bool {
+        let normalize = |ipv6: &Ipv6| Ipv6 {
+            length: 0,
+            ..ipv6.clone()
+        };
+
+        normalize(self) == normalize(other)
+    }
+
     pub fn update_length(&mut self, data: &[Layer]) -> Result<(), LayerError> {
         let mut data_buf = Vec::new();
         for layer in data {
             data_buf.extend(layer.to_bytes()?)
         }
 
+        self.update_length_payload(&data_buf)
+    }
+
+    /// Update the payload length from the written bytes of the following layers
+    pub fn update_length_payload(&mut self, data_buf: &[u8]) -> Result<(), LayerError> {
         self.length = u16::try_from(data_buf.len())?;
 
         Ok(())
     }
+
+    /// Insert an extension header of type `protocol` at `index` in `layers`
+    ///
+    /// The layer preceding `index` must be an Ipv6 or Ipv6Ext layer. The `next_header`
+    /// chain is relinked through the new header and the Ipv6 payload length is updated.
+    pub fn insert_ext_header(
+        layers: &mut Vec<Layer>,
+        index: usize,
+        protocol: IpProtocol,
+        mut ext: Ipv6Ext,
+    ) -> Result<(), LayerError> {
+        if index > layers.len() {
+            return Err(LayerError::Unexpected(
+                "extension header index out of range".to_string(),
+            ));
+        }
+
+        let prev_next_header = index
+            .checked_sub(1)
+            .and_then(|i| Ipv6::next_header_mut(&mut layers[i]))
+            .ok_or_else(|| {
+                LayerError::Unexpected(
+                    "extension header must follow an ipv6 header".to_string(),
+                )
+            })?;
+
+        ext.next_header = std::mem::replace(prev_next_header, protocol);
+        layers.insert(index, Layer::Ipv6Ext(ext));
+
+        Ipv6::update_ipv6_length(layers, index)
+    }
+
+    /// Remove the extension header at `index` in `layers`
+    ///
+    /// The `next_header` of the preceding header is relinked to the header which
+    /// followed the removed one and the Ipv6 payload length is updated.
+    pub fn remove_ext_header(layers: &mut Vec<Layer>, index: usize) -> Result<Ipv6Ext, LayerError> {
+        if !matches!(layers.get(index), Some(Layer::Ipv6Ext(_))) {
+            return Err(LayerError::Unexpected(
+                "no extension header at index".to_string(),
+            ));
+        }
+
+        let prev_index = index
+            .checked_sub(1)
+            .filter(|i| matches!(layers[*i], Layer::Ipv6(_) | Layer::Ipv6Ext(_)))
+            .ok_or_else(|| {
+                LayerError::Unexpected(
+                    "extension header must follow an ipv6 header".to_string(),
+                )
+            })?;
+
+        let ext = match layers.remove(index) {
+            Layer::Ipv6Ext(ext) => ext,
+            _ => unreachable!("layer type checked above"),
+        };
+
+        if let Some(prev_next_header) = Ipv6::next_header_mut(&mut layers[prev_index]) {
+            *prev_next_header = ext.next_header.clone();
+        }
+
+        Ipv6::update_ipv6_length(layers, index)?;
+
+        Ok(ext)
+    }
+
+    fn next_header_mut(layer: &mut Layer) -> Option<&mut IpProtocol> {
+        match layer {
+            Layer::Ipv6(ipv6) => Some(&mut ipv6.next_header),
+            Layer::Ipv6Ext(ext) => Some(&mut ext.next_header),
+            _ => None,
+        }
+    }
+
+    // update the payload length of the closest Ipv6 layer before `index`
+    fn update_ipv6_length(layers: &mut [Layer], index: usize) -> Result<(), LayerError> {
+        if let Some(i) = layers[..index]
+            .iter()
+            .rposition(|v| matches!(v, Layer::Ipv6(_)))
+        {
+            let (head, tail) = layers.split_at_mut(i + 1);
+            if let Layer::Ipv6(ipv6) = &mut head[i] {
+                ipv6.update_length(tail)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for Ipv6 {
@@ -80,9 +191,20 @@ impl Default for Ipv6 {
     }
 }
 
+impl std::fmt::Display for Ipv6 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "IPv6 {} > {} nh={:?} len={}",
+            self.src, self.dst, self.next_header, self.length
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::layer::udp::Udp;
     use hex_literal::hex;
     use rstest::*;
     use std::convert::TryFrom;
@@ -108,6 +230,96 @@ mod tests {
         assert_eq!(expected, ipv6);
     }
 
+    #[test]
+    fn test_ipv6_header_len() {
+        let ipv6 = Ipv6::default();
+        assert_eq!(40, ipv6.header_len());
+        assert_eq!(ipv6.to_bytes().unwrap().len(), ipv6.header_len());
+    }
+
+    #[test]
+    fn test_ipv6_structurally_equal() {
+        let ipv6 = Ipv6::default();
+
+        let other = Ipv6 {
+            length: 20,
+            ..Ipv6::default()
+        };
+        assert!(ipv6.structurally_equal(&other));
+
+        let other = Ipv6 {
+            hop_limit: 1,
+            ..Ipv6::default()
+        };
+        assert!(!ipv6.structurally_equal(&other));
+    }
+
+    #[test]
+    fn test_ipv6_remove_ext_header() {
+        // IPv6 / Hop-by-Hop / UDP
+        let mut layers = vec![
+            Layer::Ipv6(Ipv6 {
+                length: 16,
+                next_header: IpProtocol::HOPOPT,
+                ..Ipv6::default()
+            }),
+            Layer::Ipv6Ext(Ipv6Ext {
+                next_header: IpProtocol::UDP,
+                ..Ipv6Ext::default()
+            }),
+            Layer::Udp(Udp::default()),
+        ];
+
+        let ext = Ipv6::remove_ext_header(&mut layers, 1).unwrap();
+        assert_eq!(IpProtocol::UDP, ext.next_header);
+
+        assert_eq!(2, layers.len());
+        if let Layer::Ipv6(ipv6) = &layers[0] {
+            assert_eq!(IpProtocol::UDP, ipv6.next_header);
+            assert_eq!(8, ipv6.length);
+        } else {
+            panic!("expected ipv6 layer");
+        }
+
+        // Not an extension header
+        assert!(Ipv6::remove_ext_header(&mut layers, 1).is_err());
+    }
+
+    #[test]
+    fn test_ipv6_insert_ext_header() {
+        // IPv6 / UDP
+        let mut layers = vec![
+            Layer::Ipv6(Ipv6 {
+                length: 8,
+                next_header: IpProtocol::UDP,
+                ..Ipv6::default()
+            }),
+            Layer::Udp(Udp::default()),
+        ];
+
+        Ipv6::insert_ext_header(&mut layers, 1, IpProtocol::IPV6OPTS, Ipv6Ext::default())
+            .unwrap();
+
+        assert_eq!(3, layers.len());
+        if let Layer::Ipv6(ipv6) = &layers[0] {
+            assert_eq!(IpProtocol::IPV6OPTS, ipv6.next_header);
+            assert_eq!(16, ipv6.length);
+        } else {
+            panic!("expected ipv6 layer");
+        }
+        if let Layer::Ipv6Ext(ext) = &layers[1] {
+            assert_eq!(IpProtocol::UDP, ext.next_header);
+        } else {
+            panic!("expected ipv6 extension layer");
+        }
+
+        // Must follow an ipv6 header
+        assert!(
+            Ipv6::insert_ext_header(&mut layers, 3, IpProtocol::HOPOPT, Ipv6Ext::default())
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_ipv6_default() {
         assert_eq!(
@@ -125,4 +337,17 @@ mod tests {
             Ipv6::default(),
         );
     }
+
+    #[test]
+    fn test_ipv6_display() {
+        let ipv6 = Ipv6 {
+            next_header: IpProtocol::TCP,
+            length: 20,
+            src: "fe80::1".parse().unwrap(),
+            dst: "fe80::2".parse().unwrap(),
+            ..Ipv6::default()
+        };
+
+        assert_eq!("IPv6 fe80::1 > fe80::2 nh=TCP len=20", ipv6.to_string());
+    }
 }
@@ -0,0 +1,109 @@
+use super::IpProtocol;
+use deku::prelude::*;
+use std::convert::TryFrom;
+
+/**
+IPv6 Extension Header
+
+Generic representation of the Hop-by-Hop Options, Routing, Fragment and
+Destination Options extension headers. The header specific data is kept as bytes.
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  Next Header  |  Hdr Ext Len  |                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+|                                                               |
+.                                                               .
+.                  Header Specific Data                         .
+.                                                               .
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Ipv6Ext {
+    pub next_header: IpProtocol, // Next Header
+    #[deku(update = "self.update_length()?")]
+    pub length: u8, // Hdr Ext Len, in 8-octet units not including the first 8 octets
+    #[deku(count = "usize::from(*length) * 8 + 6")]
+    pub data: Vec<u8>, // Header Specific Data
+}
+
+impl Ipv6Ext {
+    fn update_length(&self) -> Result<u8, DekuError> {
+        // next header and length are part of the first 8 octets
+        let len = (self.data.len() + 2).saturating_sub(8) / 8;
+        Ok(u8::try_from(len)?)
+    }
+}
+
+impl Default for Ipv6Ext {
+    fn default() -> Self {
+        Ipv6Ext {
+            next_header: IpProtocol::IPV6NONXT,
+            length: 0,
+            data: vec![0; 6],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::hop_by_hop(
+            &hex!("3a00050200000100"),
+            Ipv6Ext {
+                next_header: IpProtocol::IPV6ICMP,
+                length: 0,
+                data: vec![0x05, 0x02, 0x00, 0x00, 0x01, 0x00],
+            }
+        ),
+        case::routing(
+            &hex!("1102000100000000fe800000000000000000000000000001"),
+            Ipv6Ext {
+                next_header: IpProtocol::UDP,
+                length: 2,
+                data: hex!("000100000000fe800000000000000000000000000001").to_vec(),
+            }
+        ),
+    )]
+    fn test_ipv6ext(input: &[u8], expected: Ipv6Ext) {
+        let ret_read = Ipv6Ext::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_ipv6ext_update_length() {
+        let mut ext = Ipv6Ext {
+            data: vec![0; 22],
+            ..Ipv6Ext::default()
+        };
+
+        ext.update().unwrap();
+
+        assert_eq!(2, ext.length);
+    }
+
+    #[test]
+    fn test_ipv6ext_default() {
+        assert_eq!(
+            Ipv6Ext {
+                next_header: IpProtocol::IPV6NONXT,
+                length: 0,
+                data: vec![0; 6],
+            },
+            Ipv6Ext::default(),
+        );
+    }
+}
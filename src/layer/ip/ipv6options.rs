@@ -0,0 +1,324 @@
+use super::{IpProtocol, Ipv6Ext};
+use crate::layer::LayerError;
+use deku::bitvec::{BitSlice, BitVec, Msb0};
+use deku::prelude::*;
+use std::convert::TryFrom;
+
+/**
+IPv6 Option of the Hop-by-Hop and Destination Options headers
+
+```text
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+- - - - - - - - -
+|  Option Type  |  Opt Data Len |  Option Data
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+- - - - - - - - -
+```
+
+Pad1 is a single byte, without length nor data
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(type = "u8", ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub enum Ipv6Option {
+    /// One octet of padding
+    #[deku(id = "0x00")]
+    Pad1,
+    /// Two or more octets of padding
+    #[deku(id = "0x01")]
+    PadN {
+        #[deku(update = "data.len()")]
+        length: u8,
+        #[deku(count = "length")]
+        data: Vec<u8>,
+    },
+    /// Router Alert (RFC 2711)
+    #[deku(id = "0x05")]
+    RouterAlert {
+        #[deku(assert_eq = "2")]
+        length: u8,
+        value: u16, // 0 for MLD, 2 for RSVP
+    },
+    /// Jumbo Payload (RFC 2675)
+    #[deku(id = "0xc2")]
+    JumboPayload {
+        #[deku(assert_eq = "4")]
+        length: u8,
+        payload_length: u32, // Length of the packet excluding the Ipv6 header
+    },
+    /// Unknown
+    #[deku(id_pat = "_")]
+    Unknown {
+        option_type: u8,
+        #[deku(update = "data.len()")]
+        length: u8,
+        #[deku(count = "length")]
+        data: Vec<u8>,
+    },
+}
+
+/**
+IPv6 Hop-by-Hop Options and Destination Options Header
+
+Typed view of an [Ipv6Ext](struct.Ipv6Ext.html) following a `HOPOPT` or `IPV6OPTS` next
+header. The padding options aligning the options and the header are kept in the option list,
+the header written back is the header read. An update appends a Pad1 or PadN option to
+unaligned options.
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  Next Header  |  Hdr Ext Len  |                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+|                                                               |
+.                                                               .
+.                            Options                            .
+.                                                               .
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Ipv6OptionsHeader {
+    pub next_header: IpProtocol, // Next Header
+    #[deku(update = "self.update_length()?")]
+    pub length: u8, // Hdr Ext Len, in 8-octet units not including the first 8 octets
+    #[deku(
+        reader = "Ipv6OptionsHeader::read_options(*length, deku::rest)",
+        update = "self.update_options()?"
+    )]
+    pub options: Vec<Ipv6Option>,
+}
+
+impl Ipv6OptionsHeader {
+    // written length of `options` in bytes
+    fn options_len(options: &[Ipv6Option]) -> Result<usize, DekuError> {
+        let mut bits = BitVec::<Msb0, u8>::new();
+        for option in options.iter() {
+            option.write(&mut bits, deku::ctx::Endian::Big)?;
+        }
+
+        Ok(bits.len() / 8)
+    }
+
+    fn update_length(&self) -> Result<u8, DekuError> {
+        let options_len = Ipv6OptionsHeader::options_len(&self.update_options()?)?;
+
+        // next header and length are part of the first 8 octets
+        let len = (options_len + 2).saturating_sub(8) / 8;
+        Ok(u8::try_from(len)?)
+    }
+
+    fn update_options(&self) -> Result<Vec<Ipv6Option>, DekuError> {
+        let mut options = self.options.clone();
+        for option in options.iter_mut() {
+            option.update()?;
+        }
+
+        // pad the header to a multiple of 8 octets
+        let padding = (8 - (Ipv6OptionsHeader::options_len(&options)? + 2) % 8) % 8;
+        match padding {
+            0 => {}
+            1 => options.push(Ipv6Option::Pad1),
+            _ => options.push(Ipv6Option::PadN {
+                length: u8::try_from(padding - 2)?,
+                data: vec![0; padding - 2],
+            }),
+        }
+
+        Ok(options)
+    }
+
+    fn read_options(
+        length: u8, // Hdr Ext Len
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<Ipv6Option>), DekuError> {
+        // slice off length of options
+        let bits = (usize::from(length) * 8 + 6) * 8;
+
+        // Check split_at precondition
+        if bits > rest.len() {
+            return Err(DekuError::Parse(
+                "not enough data to read ipv6 options".to_string(),
+            ));
+        }
+
+        let (mut option_rest, rest) = rest.split_at(bits);
+
+        let mut options = Vec::new();
+        while !option_rest.is_empty() {
+            let (option_rest_new, option) =
+                Ipv6Option::read(option_rest, deku::ctx::Endian::Big)?;
+
+            options.push(option);
+
+            option_rest = option_rest_new;
+        }
+
+        Ok((rest, options))
+    }
+
+    /// Returns the value of the Router Alert option
+    pub fn router_alert(&self) -> Option<u16> {
+        self.options.iter().find_map(|v| match v {
+            Ipv6Option::RouterAlert { value, .. } => Some(*value),
+            _ => None,
+        })
+    }
+
+    /// Returns the payload length of the Jumbo Payload option
+    pub fn jumbo_payload_length(&self) -> Option<u32> {
+        self.options.iter().find_map(|v| match v {
+            Ipv6Option::JumboPayload { payload_length, .. } => Some(*payload_length),
+            _ => None,
+        })
+    }
+}
+
+impl TryFrom<&Ipv6Ext> for Ipv6OptionsHeader {
+    type Error = LayerError;
+
+    fn try_from(ext: &Ipv6Ext) -> Result<Self, Self::Error> {
+        let ext = ext.to_bytes()?;
+        Ok(Ipv6OptionsHeader::try_from(ext.as_slice())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::router_alert(
+            &hex!("3a00 05020000 0100"),
+            Ipv6OptionsHeader {
+                next_header: IpProtocol::IPV6ICMP,
+                length: 0,
+                options: vec![
+                    Ipv6Option::RouterAlert { length: 2, value: 0 },
+                    Ipv6Option::PadN { length: 0, data: vec![] },
+                ],
+            }
+        ),
+        case::jumbo_payload(
+            &hex!("1100 c20400010000"),
+            Ipv6OptionsHeader {
+                next_header: IpProtocol::UDP,
+                length: 0,
+                options: vec![Ipv6Option::JumboPayload {
+                    length: 4,
+                    payload_length: 0x10000,
+                }],
+            }
+        ),
+        case::pad1_unknown(
+            &hex!("0601 00 3e0aabcdef0123456789abcd 00"),
+            Ipv6OptionsHeader {
+                next_header: IpProtocol::TCP,
+                length: 1,
+                options: vec![
+                    Ipv6Option::Pad1,
+                    Ipv6Option::Unknown {
+                        option_type: 0x3e,
+                        length: 10,
+                        data: hex!("abcdef0123456789abcd").to_vec(),
+                    },
+                    Ipv6Option::Pad1,
+                ],
+            }
+        ),
+        #[should_panic(expected = "not enough data to read ipv6 options")]
+        case::short_options(
+            &hex!("3a01 05020000 0100"),
+            Ipv6OptionsHeader {
+                next_header: IpProtocol::IPV6ICMP,
+                length: 1,
+                options: vec![],
+            }
+        ),
+        #[should_panic(expected = "Incomplete")]
+        case::option_past_header(
+            &hex!("3a00 0502000001 01"),
+            Ipv6OptionsHeader {
+                next_header: IpProtocol::IPV6ICMP,
+                length: 0,
+                options: vec![],
+            }
+        ),
+    )]
+    fn test_ipv6options(input: &[u8], expected: Ipv6OptionsHeader) {
+        let ret_read = Ipv6OptionsHeader::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_ipv6options_from_ipv6ext() {
+        // Hop-by-Hop header of an MLD report
+        let ext = Ipv6Ext::try_from(hex!("3a00 05020000 0100").as_ref()).unwrap();
+
+        let hop_by_hop = Ipv6OptionsHeader::try_from(&ext).unwrap();
+
+        assert_eq!(Some(0), hop_by_hop.router_alert());
+        assert_eq!(None, hop_by_hop.jumbo_payload_length());
+    }
+
+    #[test]
+    fn test_ipv6options_update_length() {
+        let mut header = Ipv6OptionsHeader {
+            next_header: IpProtocol::IPV6NONXT,
+            length: 0,
+            options: vec![
+                Ipv6Option::RouterAlert { length: 2, value: 2 },
+                Ipv6Option::PadN {
+                    length: 0,
+                    data: vec![0; 8],
+                },
+            ],
+        };
+
+        header.update().unwrap();
+
+        assert_eq!(1, header.length);
+        assert_eq!(
+            Ipv6Option::PadN {
+                length: 8,
+                data: vec![0; 8]
+            },
+            header.options[1]
+        );
+    }
+
+    #[rstest(options, expected_padding, expected,
+        case::pad_n(
+            vec![Ipv6Option::RouterAlert { length: 2, value: 0 }],
+            Ipv6Option::PadN { length: 0, data: vec![] },
+            &hex!("3a00 05020000 0100"),
+        ),
+        case::pad1(
+            vec![Ipv6Option::Unknown { option_type: 0x3e, length: 0, data: vec![0xab; 3] }],
+            Ipv6Option::Pad1,
+            &hex!("3a00 3e03ababab 00"),
+        ),
+    )]
+    fn test_ipv6options_update_padding(
+        options: Vec<Ipv6Option>,
+        expected_padding: Ipv6Option,
+        expected: &[u8],
+    ) {
+        let mut header = Ipv6OptionsHeader {
+            next_header: IpProtocol::IPV6ICMP,
+            length: 0,
+            options,
+        };
+
+        header.update().unwrap();
+
+        assert_eq!(0, header.length);
+        assert_eq!(Some(&expected_padding), header.options.last());
+        assert_eq!(expected.to_vec(), header.to_bytes().unwrap());
+    }
+}
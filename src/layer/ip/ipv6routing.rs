@@ -0,0 +1,168 @@
+use super::{IpProtocol, Ipv6Ext};
+use crate::layer::LayerError;
+use deku::prelude::*;
+use std::convert::TryFrom;
+use std::net::Ipv6Addr;
+
+/// Routing type of the (deprecated) type 0 routing header
+pub const IPV6_ROUTING_TYPE_SOURCE: u8 = 0;
+
+/// Routing type of the segment routing header (SRv6)
+pub const IPV6_ROUTING_TYPE_SEGMENT: u8 = 4;
+
+/**
+IPv6 Routing Header with an address list, type 0 and type 4 (SRH)
+
+Typed view of an [Ipv6Ext](struct.Ipv6Ext.html) following a `IPV6ROUTE` next header. The
+segment list fills the header, SRH TLVs are not supported.
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  Next Header  |  Hdr Ext Len  | Routing Type  | Segments Left |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                      Type Specific Data                       |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                                                               |
++                                                               +
+|                     Segment List[0] ... [n]                   |
++                                                               +
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Ipv6RoutingHeader {
+    pub next_header: IpProtocol, // Next Header
+    #[deku(update = "self.update_length()?")]
+    pub length: u8, // Hdr Ext Len, in 8-octet units not including the first 8 octets
+    pub routing_type: u8, // Routing Type
+    pub segments_left: u8, // Segments Left
+    pub type_data: u32, // Reserved for type 0, Last Entry, Flags and Tag for type 4
+    #[deku(count = "Ipv6RoutingHeader::segments_len(*length)?")]
+    pub segments: Vec<Ipv6Addr>, // Segment List
+}
+
+impl Ipv6RoutingHeader {
+    // each segment is 2 units of 8 octets, an odd length would leave a partial segment
+    fn segments_len(length: u8) -> Result<usize, DekuError> {
+        if length % 2 != 0 {
+            return Err(DekuError::Parse(format!(
+                "odd ipv6 routing header length: {}",
+                length
+            )));
+        }
+
+        Ok(usize::from(length) / 2)
+    }
+
+    fn update_length(&self) -> Result<u8, DekuError> {
+        Ok(u8::try_from(self.segments.len() * 2)?)
+    }
+
+    /// Returns the segment list, the last segment of the path first for an SRH
+    pub fn segments(&self) -> &[Ipv6Addr] {
+        &self.segments
+    }
+}
+
+impl TryFrom<&Ipv6Ext> for Ipv6RoutingHeader {
+    type Error = LayerError;
+
+    fn try_from(ext: &Ipv6Ext) -> Result<Self, Self::Error> {
+        let ext = ext.to_bytes()?;
+        Ok(Ipv6RoutingHeader::try_from(ext.as_slice())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::segment_routing(
+            &hex!("0604040101000000 20010db8000000000000000000000002 20010db8000000000000000000000001"),
+            Ipv6RoutingHeader {
+                next_header: IpProtocol::TCP,
+                length: 4,
+                routing_type: IPV6_ROUTING_TYPE_SEGMENT,
+                segments_left: 1,
+                type_data: 0x01000000,
+                segments: vec![
+                    "2001:db8::2".parse().unwrap(),
+                    "2001:db8::1".parse().unwrap(),
+                ],
+            }
+        ),
+        #[should_panic(expected = "Incomplete")]
+        case::missing_segment(
+            &hex!("0604040101000000 20010db8000000000000000000000002"),
+            Ipv6RoutingHeader {
+                next_header: IpProtocol::TCP,
+                length: 4,
+                routing_type: IPV6_ROUTING_TYPE_SEGMENT,
+                segments_left: 1,
+                type_data: 0x01000000,
+                segments: vec![],
+            }
+        ),
+        #[should_panic(expected = "Parse(\"odd ipv6 routing header length: 3\")")]
+        case::odd_length(
+            &hex!("0603040101000000 20010db8000000000000000000000002 0000000000000000"),
+            Ipv6RoutingHeader {
+                next_header: IpProtocol::TCP,
+                length: 3,
+                routing_type: IPV6_ROUTING_TYPE_SEGMENT,
+                segments_left: 1,
+                type_data: 0x01000000,
+                segments: vec![],
+            }
+        ),
+    )]
+    fn test_ipv6routing(input: &[u8], expected: Ipv6RoutingHeader) {
+        let ret_read = Ipv6RoutingHeader::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_ipv6routing_from_ipv6ext() {
+        let ext = Ipv6Ext::try_from(
+            hex!("0604040101000000 20010db8000000000000000000000002 20010db8000000000000000000000001").as_ref(),
+        )
+        .unwrap();
+
+        let srh = Ipv6RoutingHeader::try_from(&ext).unwrap();
+
+        assert_eq!(
+            &[
+                "2001:db8::2".parse::<Ipv6Addr>().unwrap(),
+                "2001:db8::1".parse::<Ipv6Addr>().unwrap(),
+            ],
+            srh.segments()
+        );
+        assert_eq!(1, srh.segments_left);
+    }
+
+    #[test]
+    fn test_ipv6routing_update_length() {
+        let mut srh = Ipv6RoutingHeader {
+            next_header: IpProtocol::IPV6NONXT,
+            length: 0,
+            routing_type: IPV6_ROUTING_TYPE_SEGMENT,
+            segments_left: 0,
+            type_data: 0,
+            segments: vec![Ipv6Addr::LOCALHOST; 3],
+        };
+
+        srh.update().unwrap();
+
+        assert_eq!(6, srh.length);
+    }
+}
@@ -5,16 +5,33 @@ Ipv4 and Ipv6 layer
 #[macro_use]
 pub mod ipv4;
 pub mod ipv6;
+pub mod ipv6ext;
+pub mod ipv6options;
+pub mod ipv6routing;
+pub mod net;
 pub mod protocols;
 
 pub use ipv4::Ipv4;
 pub use ipv6::Ipv6;
+pub use ipv6ext::Ipv6Ext;
+pub use ipv6options::{Ipv6Option, Ipv6OptionsHeader};
+pub use ipv6routing::Ipv6RoutingHeader;
+pub use net::{
+    ipv6_6to4_ipv4, ipv6_solicited_node, ipv6_teredo_ipv4, is_link_local, is_multicast,
+};
 pub use protocols::IpProtocol;
 
 use crate::layer::LayerError;
 use std::convert::TryInto;
 
 pub fn checksum(input: &[u8]) -> Result<u16, LayerError> {
+    Ok(!ones_complement_sum(input))
+}
+
+/// One's complement sum of the 16-bit words of `input`, the checksum before inversion
+///
+/// An odd trailing byte is padded with zero
+pub fn ones_complement_sum(input: &[u8]) -> u16 {
     let mut sum = 0x00;
     let mut chunks_iter = input.chunks_exact(2);
     while let Some(chunk) = chunks_iter.next() {
@@ -28,9 +45,7 @@ pub fn checksum(input: &[u8]) -> Result<u16, LayerError> {
     }
 
     let carry_add = (sum & 0xffff) + (sum >> 16);
-    let chksum = !(((carry_add & 0xffff) + (carry_add >> 16)) as u16);
-
-    Ok(chksum)
+    ((carry_add & 0xffff) + (carry_add >> 16)) as u16
 }
 
 #[cfg(test)]
@@ -0,0 +1,109 @@
+/*!
+Ipv6 address helpers
+*/
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// Returns the solicited-node multicast address of `addr`, `ff02::1:ffXX:XXXX`
+///
+/// The low 24 bits of `addr` are appended to the `ff02::1:ff00:0/104` prefix
+pub fn ipv6_solicited_node(addr: Ipv6Addr) -> Ipv6Addr {
+    let octets = addr.octets();
+    Ipv6Addr::new(
+        0xff02,
+        0,
+        0,
+        0,
+        0,
+        0x0001,
+        0xff00 | u16::from(octets[13]),
+        u16::from_be_bytes([octets[14], octets[15]]),
+    )
+}
+
+/// Returns true if `addr` is a unicast link-local address, in `fe80::/10`
+pub fn is_link_local(addr: Ipv6Addr) -> bool {
+    (addr.segments()[0] & 0xffc0) == 0xfe80
+}
+
+/// Returns true if `addr` is a multicast address, in `ff00::/8`
+pub fn is_multicast(addr: Ipv6Addr) -> bool {
+    addr.octets()[0] == 0xff
+}
+
+/// Returns the client Ipv4 address and port of a Teredo address, in `2001::/32`
+///
+/// The client address and port are stored obfuscated, with all their bits inverted
+pub fn ipv6_teredo_ipv4(addr: Ipv6Addr) -> Option<(Ipv4Addr, u16)> {
+    let segments = addr.segments();
+    if segments[0] != 0x2001 || segments[1] != 0x0000 {
+        return None;
+    }
+
+    let octets = addr.octets();
+    let client = Ipv4Addr::new(!octets[12], !octets[13], !octets[14], !octets[15]);
+    Some((client, !segments[5]))
+}
+
+/// Returns the Ipv4 address embedded in a 6to4 address, in `2002::/16`
+pub fn ipv6_6to4_ipv4(addr: Ipv6Addr) -> Option<Ipv4Addr> {
+    if addr.segments()[0] != 0x2002 {
+        return None;
+    }
+
+    let octets = addr.octets();
+    Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest(addr, expected,
+        case("2001:db8::1:2:3", "ff02::1:ff02:3"),
+        case("fe80::2aa:ff:fe28:9c5a", "ff02::1:ff28:9c5a"),
+        case("::", "ff02::1:ff00:0"),
+    )]
+    fn test_ipv6_solicited_node(addr: &str, expected: &str) {
+        assert_eq!(
+            expected.parse::<Ipv6Addr>().unwrap(),
+            ipv6_solicited_node(addr.parse().unwrap())
+        );
+    }
+
+    #[rstest(addr, expected_link_local, expected_multicast,
+        case("fe80::1", true, false),
+        case("febf:ffff::1", true, false),
+        case("fec0::1", false, false),
+        case("2001:db8::1", false, false),
+        case("ff02::1:ff28:9c5a", false, true),
+        case("::1", false, false),
+    )]
+    fn test_ipv6_addr_class(addr: &str, expected_link_local: bool, expected_multicast: bool) {
+        let addr: Ipv6Addr = addr.parse().unwrap();
+
+        assert_eq!(expected_link_local, is_link_local(addr));
+        assert_eq!(expected_multicast, is_multicast(addr));
+    }
+
+    #[rstest(addr, expected,
+        case("2002:c000:0204::1", Some(Ipv4Addr::new(192, 0, 2, 4))),
+        case("2002:0a01:02ff:1::", Some(Ipv4Addr::new(10, 1, 2, 255))),
+        case("2001:db8::1", None),
+    )]
+    fn test_ipv6_6to4_ipv4(addr: &str, expected: Option<Ipv4Addr>) {
+        assert_eq!(expected, ipv6_6to4_ipv4(addr.parse().unwrap()));
+    }
+
+    #[rstest(addr, expected,
+        case(
+            "2001:0000:4136:e378:8000:63bf:3fff:fdd2",
+            Some((Ipv4Addr::new(192, 0, 2, 45), 40000)),
+        ),
+        case("2001:db8::1", None),
+        case("2002:c000:0204::1", None),
+    )]
+    fn test_ipv6_teredo_ipv4(addr: &str, expected: Option<(Ipv4Addr, u16)>) {
+        assert_eq!(expected, ipv6_teredo_ipv4(addr.parse().unwrap()));
+    }
+}
@@ -1,6 +1,6 @@
 use deku::prelude::*;
 
-#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, DekuRead, DekuWrite)]
 #[deku(
     type = "u8",
     ctx = "endian: deku::ctx::Endian",
@@ -172,6 +172,68 @@ pub enum IpProtocol {
     ROHC,
 }
 
+impl IpProtocol {
+    /// Returns all the known protocols, by increasing number
+    pub fn all() -> &'static [IpProtocol] {
+        &[
+            IpProtocol::HOPOPT,
+            IpProtocol::ICMP,
+            IpProtocol::IGMP,
+            IpProtocol::GGP,
+            IpProtocol::IPENCAP,
+            IpProtocol::ST,
+            IpProtocol::TCP,
+            IpProtocol::EGP,
+            IpProtocol::IGP,
+            IpProtocol::PUP,
+            IpProtocol::UDP,
+            IpProtocol::HMP,
+            IpProtocol::XNSIDP,
+            IpProtocol::RDP,
+            IpProtocol::ISOTP4,
+            IpProtocol::DCCP,
+            IpProtocol::XTP,
+            IpProtocol::DDP,
+            IpProtocol::IDPRCMTP,
+            IpProtocol::IPV6,
+            IpProtocol::IPV6ROUTE,
+            IpProtocol::IPV6FRAG,
+            IpProtocol::IDRP,
+            IpProtocol::RSVP,
+            IpProtocol::GRE,
+            IpProtocol::ESP,
+            IpProtocol::AH,
+            IpProtocol::SKIP,
+            IpProtocol::IPV6ICMP,
+            IpProtocol::IPV6NONXT,
+            IpProtocol::IPV6OPTS,
+            IpProtocol::RSPF,
+            IpProtocol::VMTP,
+            IpProtocol::EIGRP,
+            IpProtocol::OSPF,
+            IpProtocol::AX25,
+            IpProtocol::IPIP,
+            IpProtocol::ETHERIP,
+            IpProtocol::ENCAP,
+            IpProtocol::PIM,
+            IpProtocol::IPCOMP,
+            IpProtocol::VRRP,
+            IpProtocol::L2TP,
+            IpProtocol::ISIS,
+            IpProtocol::SCTP,
+            IpProtocol::FC,
+            IpProtocol::MOBILITYHEADER,
+            IpProtocol::UDPLITE,
+            IpProtocol::MPLSINIP,
+            IpProtocol::MANET,
+            IpProtocol::HIP,
+            IpProtocol::SHIM6,
+            IpProtocol::WESP,
+            IpProtocol::ROHC,
+        ]
+    }
+}
+
 impl Default for IpProtocol {
     fn default() -> Self {
         IpProtocol::TCP
@@ -193,6 +255,18 @@ mod tests {
         assert_eq!(test_data, ret_write);
     }
 
+    #[test]
+    fn test_ipprotocol_all() {
+        let all = IpProtocol::all();
+        assert_eq!(54, all.len());
+
+        for protocol in all {
+            let ret_write = protocol.to_bytes().unwrap();
+            let (_rest, ret_read) = IpProtocol::from_bytes((&ret_write, 0)).unwrap();
+            assert_eq!(protocol, &ret_read);
+        }
+    }
+
     #[test]
     fn test_ipprotocol_default() {
         assert_eq!(IpProtocol::TCP, IpProtocol::default())
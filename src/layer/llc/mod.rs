@@ -0,0 +1,80 @@
+/*!
+IEEE 802.2 LLC layer
+
+The LLC header follows an IEEE 802.3 length field in place of an ether type
+*/
+use deku::prelude::*;
+
+/// Service access point of the Spanning Tree Protocol
+pub const LLC_SAP_STP: u8 = 0x42;
+
+/// Unnumbered Information control field
+pub const LLC_UI: u8 = 0x03;
+
+/**
+LLC Header
+
+```text
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|     DSAP      |     SSAP      |    Control    |   Control*    |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+
+The control field is 2 bytes for Information and Supervisory frames, 1 byte for Unnumbered
+frames
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Llc {
+    pub dsap: u8, // Destination Service Access Point
+    pub ssap: u8, // Source Service Access Point
+    pub control: u8,
+    #[deku(cond = "*control & 0b11 != 0b11")]
+    pub control_ext: Option<u8>, // Second control byte of I and S frames
+}
+
+impl Default for Llc {
+    fn default() -> Self {
+        Llc {
+            dsap: 0,
+            ssap: 0,
+            control: LLC_UI,
+            control_ext: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::unnumbered(
+            &hex!("424203"),
+            Llc {
+                dsap: LLC_SAP_STP,
+                ssap: LLC_SAP_STP,
+                control: LLC_UI,
+                control_ext: None,
+            },
+        ),
+        case::information(
+            &hex!("f0f00002"),
+            Llc {
+                dsap: 0xf0,
+                ssap: 0xf0,
+                control: 0x00,
+                control_ext: Some(0x02),
+            },
+        ),
+    )]
+    fn test_llc(input: &[u8], expected: Llc) {
+        let (_rest, ret_read) = Llc::from_bytes((input, 0)).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+}
@@ -0,0 +1,224 @@
+/*!
+LLDP layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+use std::convert::TryInto;
+
+/// End of LLDPDU TLV type
+pub const LLDP_TLV_END: u8 = 0;
+
+/// Chassis ID TLV type
+pub const LLDP_TLV_CHASSIS_ID: u8 = 1;
+
+/// Port ID TLV type
+pub const LLDP_TLV_PORT_ID: u8 = 2;
+
+/// Time To Live TLV type
+pub const LLDP_TLV_TTL: u8 = 3;
+
+/**
+LLDP TLV
+
+```text
+ 0                   1                   2
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  TLV Type   |   TLV Length    |  Value ...    |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct LldpTlv {
+    #[deku(bits = "7")]
+    pub tlv_type: u8,
+    #[deku(bits = "9", update = "self.value.len()")]
+    pub length: u16,
+    #[deku(count = "length")]
+    pub value: Vec<u8>,
+}
+
+/**
+LLDP Data Unit
+
+A sequence of TLVs, terminated by the End of LLDPDU TLV
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Lldp {
+    #[deku(
+        reader = "Lldp::read_tlvs(deku::rest)",
+        update = "self.update_tlvs()?"
+    )]
+    pub tlvs: Vec<LldpTlv>,
+}
+
+impl Lldp {
+    fn update_tlvs(&self) -> Result<Vec<LldpTlv>, DekuError> {
+        let mut tlvs = self.tlvs.clone();
+        for tlv in tlvs.iter_mut() {
+            tlv.update()?;
+        }
+
+        Ok(tlvs)
+    }
+
+    fn read_tlvs(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<LldpTlv>), DekuError> {
+        let mut rest = rest;
+        let mut tlvs = Vec::new();
+
+        // read up to and including the end TLV, frame padding may follow
+        while !rest.is_empty() {
+            let (new_rest, tlv) = LldpTlv::read(rest, deku::ctx::Endian::Big)?;
+            rest = new_rest;
+
+            let end = tlv.tlv_type == LLDP_TLV_END;
+            tlvs.push(tlv);
+            if end {
+                break;
+            }
+        }
+
+        Ok((rest, tlvs))
+    }
+
+    /// Returns the value of the first TLV of type `tlv_type`
+    pub fn tlv(&self, tlv_type: u8) -> Option<&[u8]> {
+        self.tlvs
+            .iter()
+            .find(|v| v.tlv_type == tlv_type)
+            .map(|v| v.value.as_slice())
+    }
+
+    /// Returns the chassis ID, without its subtype
+    pub fn chassis_id(&self) -> Option<&[u8]> {
+        self.tlv(LLDP_TLV_CHASSIS_ID).and_then(|v| v.get(1..))
+    }
+
+    /// Returns the port ID, without its subtype
+    pub fn port_id(&self) -> Option<&[u8]> {
+        self.tlv(LLDP_TLV_PORT_ID).and_then(|v| v.get(1..))
+    }
+
+    /// Returns the time to live in seconds
+    pub fn ttl(&self) -> Option<u16> {
+        self.tlv(LLDP_TLV_TTL)
+            .and_then(|v| v.try_into().ok())
+            .map(u16::from_be_bytes)
+    }
+}
+
+impl Default for Lldp {
+    fn default() -> Self {
+        Lldp {
+            tlvs: vec![LldpTlv {
+                tlv_type: LLDP_TLV_END,
+                length: 0,
+                value: vec![],
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::mandatory(
+            &hex!("0207 04001122334455 0405 0565746830 0602 0078 0000"),
+            Lldp {
+                tlvs: vec![
+                    LldpTlv {
+                        tlv_type: LLDP_TLV_CHASSIS_ID,
+                        length: 7,
+                        value: hex!("04001122334455").to_vec(),
+                    },
+                    LldpTlv {
+                        tlv_type: LLDP_TLV_PORT_ID,
+                        length: 5,
+                        value: hex!("0565746830").to_vec(),
+                    },
+                    LldpTlv {
+                        tlv_type: LLDP_TLV_TTL,
+                        length: 2,
+                        value: vec![0x00, 0x78],
+                    },
+                    LldpTlv {
+                        tlv_type: LLDP_TLV_END,
+                        length: 0,
+                        value: vec![],
+                    },
+                ],
+            },
+        ),
+        case::no_end(
+            &hex!("0602 0078"),
+            Lldp {
+                tlvs: vec![LldpTlv {
+                    tlv_type: LLDP_TLV_TTL,
+                    length: 2,
+                    value: vec![0x00, 0x78],
+                }],
+            },
+        ),
+        #[should_panic(expected = "Incomplete")]
+        case::short_value(
+            &hex!("0605 0078"),
+            Lldp::default(),
+        ),
+    )]
+    fn test_lldp(input: &[u8], expected: Lldp) {
+        let ret_read = Lldp::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_lldp_ids() {
+        let lldp =
+            Lldp::try_from(hex!("0207 04001122334455 0405 0565746830 0602 0078 0000").as_ref())
+                .unwrap();
+
+        assert_eq!(Some(hex!("001122334455").as_ref()), lldp.chassis_id());
+        assert_eq!(Some(b"eth0".as_ref()), lldp.port_id());
+        assert_eq!(Some(120), lldp.ttl());
+    }
+
+    #[test]
+    fn test_lldp_update_length() {
+        let mut lldp = Lldp {
+            tlvs: vec![LldpTlv {
+                tlv_type: LLDP_TLV_TTL,
+                length: 0,
+                value: vec![0x00, 0x78],
+            }],
+        };
+
+        lldp.update().unwrap();
+
+        assert_eq!(2, lldp.tlvs[0].length);
+    }
+
+    #[test]
+    fn test_lldp_default() {
+        assert_eq!(
+            Lldp {
+                tlvs: vec![LldpTlv {
+                    tlv_type: 0,
+                    length: 0,
+                    value: vec![],
+                }],
+            },
+            Lldp::default()
+        )
+    }
+}
@@ -0,0 +1,151 @@
+/*!
+MACsec layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/**
+MACsec (IEEE 802.1AE) SecTAG
+
+The secured data, including the ICV, is kept opaque
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|V|E|S|S|E|C| AN|R R|    SL     |        Packet Number          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|        Packet Number          |                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+|                    SCI (present if SC is set)                 |
++                               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                               |         Secured Data          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct MacSec {
+    #[deku(bits = "1")]
+    pub version: u8, // Version Number
+    #[deku(bits = "1")]
+    pub es: u8, // End Station
+    #[deku(bits = "1", update = "u8::from(self.sci.is_some())")]
+    pub sc: u8, // Secure Channel, SCI present
+    #[deku(bits = "1")]
+    pub scb: u8, // Single Copy Broadcast
+    #[deku(bits = "1")]
+    pub encrypted: u8, // Encryption
+    #[deku(bits = "1")]
+    pub changed: u8, // Changed Text
+    #[deku(bits = "2")]
+    pub an: u8, // Association Number
+    #[deku(bits = "2")]
+    pub reserved: u8,
+    #[deku(bits = "6")]
+    pub short_length: u8, // Length of short secured data, 0 if 48 bytes or more
+    pub packet_number: u32,
+    #[deku(cond = "*sc == 1")]
+    pub sci: Option<u64>, // Secure Channel Identifier
+    #[deku(reader = "MacSec::read_data(deku::rest)")]
+    pub data: Vec<u8>, // Secured Data and ICV
+}
+
+impl MacSec {
+    fn read_data(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+        let ret = rest.as_raw_slice().to_vec();
+        let (empty, _rest) = rest.split_at(0);
+        Ok((empty, ret))
+    }
+}
+
+impl Default for MacSec {
+    fn default() -> Self {
+        MacSec {
+            version: 0,
+            es: 0,
+            sc: 1,
+            scb: 0,
+            encrypted: 0,
+            changed: 0,
+            an: 0,
+            reserved: 0,
+            short_length: 0,
+            packet_number: 1,
+            sci: Some(0),
+            data: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::with_sci(
+            &hex!("2c 00 0000002a 0011223344550001 aabbccdd"),
+            MacSec {
+                version: 0,
+                es: 0,
+                sc: 1,
+                scb: 0,
+                encrypted: 1,
+                changed: 1,
+                an: 0,
+                reserved: 0,
+                short_length: 0,
+                packet_number: 42,
+                sci: Some(0x0011223344550001),
+                data: vec![0xaa, 0xbb, 0xcc, 0xdd],
+            },
+        ),
+        case::without_sci(
+            &hex!("0d 04 0000002a aabbccdd"),
+            MacSec {
+                version: 0,
+                es: 0,
+                sc: 0,
+                scb: 0,
+                encrypted: 1,
+                changed: 1,
+                an: 1,
+                reserved: 0,
+                short_length: 4,
+                packet_number: 42,
+                sci: None,
+                data: vec![0xaa, 0xbb, 0xcc, 0xdd],
+            },
+        ),
+        #[should_panic(expected = "Incomplete")]
+        case::short_sci(&hex!("2c 00 0000002a 00112233"), MacSec::default()),
+    )]
+    fn test_macsec(input: &[u8], expected: MacSec) {
+        let ret_read = MacSec::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[rstest(sci, expected_sc,
+        case::some(Some(0x0011223344550001), 1),
+        case::none(None, 0),
+    )]
+    fn test_macsec_update_sc(sci: Option<u64>, expected_sc: u8) {
+        let mut macsec = MacSec {
+            sc: 1 - expected_sc,
+            sci,
+            ..MacSec::default()
+        };
+
+        macsec.update().unwrap();
+
+        assert_eq!(expected_sc, macsec.sc);
+    }
+}
@@ -4,19 +4,67 @@ Collection of network layer types
 A layer is a type representing a network header found in a packet, such as Ether, Ipv4, etc.
 */
 
+pub mod arp;
+pub mod avtp;
+pub mod bgp;
+pub mod bpdu;
+pub mod bytes;
+pub mod coap;
+pub mod dhcpv6;
+pub mod dns;
 pub mod error;
 pub mod ether;
+pub mod gtpu;
+pub mod icmp;
+pub mod igmp;
 pub mod ip;
+pub mod llc;
+pub mod lldp;
+pub mod macsec;
+pub mod netflow;
+pub mod null;
+pub mod ospf;
+pub mod ptp;
+pub mod radiotap;
+pub mod radius;
 pub mod raw;
+pub mod sll;
+pub mod smb;
 pub mod tcp;
 pub mod udp;
+pub mod vlan;
+pub mod vxlan;
+pub mod wireguard;
 
+pub use arp::Arp;
+pub use avtp::Avtp;
+pub use bgp::Bgp;
+pub use bpdu::Bpdu;
+pub use coap::Coap;
+pub use dhcpv6::Dhcpv6;
 pub use error::LayerError;
 pub use ether::Ether;
-pub use ip::{Ipv4, Ipv6};
+pub use gtpu::Gtpu;
+pub use icmp::Icmp;
+pub use igmp::Igmp;
+pub use ip::{Ipv4, Ipv6, Ipv6Ext};
+pub use llc::Llc;
+pub use lldp::Lldp;
+pub use macsec::MacSec;
+pub use netflow::NetflowV5;
+pub use null::Null;
+pub use ospf::Ospf;
+pub use ptp::Ptp;
+pub use radiotap::Radiotap;
+pub use radius::Radius;
 pub use raw::Raw;
+pub use sll::LinuxSll;
+pub use smb::Smb;
 pub use tcp::Tcp;
 pub use udp::Udp;
+pub use vlan::Vlan;
+pub use vxlan::Vxlan;
+pub use wireguard::WireGuard;
 
 use deku::prelude::*;
 use ip::IpProtocol;
@@ -24,6 +72,27 @@ use ip::IpProtocol;
 #[derive(Debug, PartialEq)]
 pub enum ValidationError {
     Checksum,
+    Reserved,
+    InvalidVersion,
+    InvalidIhl,
+    Fcs,
+}
+
+/// How a packet is read past an ether type with no layer to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnknownHandling {
+    /// The remaining bytes are read as a Raw layer
+    Raw,
+    /// The remaining bytes are not read, the packet ends with the layer carrying the ether type
+    Stop,
+    /// The packet is not read
+    Error,
+}
+
+impl Default for UnknownHandling {
+    fn default() -> Self {
+        UnknownHandling::Raw
+    }
 }
 
 pub trait LayerValidate {
@@ -41,8 +110,22 @@ macro_rules! do_layer {
     }};
 }
 
+// A layer selected by a port: a payload which cannot be read as that layer is read as Raw
+macro_rules! do_port_layer {
+    ($layer:ident, $input:ident, $layers:ident, $unknown:ident, $max_payload:ident) => {{
+        match $layer::from_bytes($input) {
+            Ok((rest, layer)) => {
+                $layers.push(Layer::$layer(layer));
+
+                rest
+            }
+            Err(_) => return Layer::consume_layer($input, $layers, 0, $unknown, $max_payload),
+        }
+    }};
+}
+
 macro_rules! gen_layer_types {
-    ($($types:ident,)*) => {
+    ($($types:ident => $names:literal,)*) => {
         /// Layer wrapper type
         #[derive(Debug, PartialEq, Clone)]
         pub enum Layer {
@@ -59,15 +142,33 @@ macro_rules! gen_layer_types {
                 }
             }
 
+            /// Returns the protocol name of the layer
+            pub fn name(&self) -> &'static str {
+                self.layer_type().name()
+            }
+
             // Recursive function to consume layers from a stream of bytes
-            fn consume_layer<'a>(rest: (&'a [u8], usize), layers: &mut Vec<Layer>, max_depth: usize) -> Result<(), LayerError> {
+            fn consume_layer<'a>(rest: (&'a [u8], usize), layers: &mut Vec<Layer>, max_depth: usize, unknown: UnknownHandling, max_payload: usize) -> Result<(), LayerError> {
                 if max_depth == 0 {
+                    if rest.0.len() > max_payload {
+                        return Err(LayerError::Parse(format!(
+                            "raw payload of {} bytes exceeds the maximum of {}",
+                            rest.0.len(),
+                            max_payload
+                        )));
+                    }
+
                     if !rest.0.is_empty() {
                         let rest = {
                             do_layer!(Raw, rest, layers)
                         };
 
-                        assert!(rest.0.is_empty(), "dev error: rest should always be empty here");
+                        if !rest.0.is_empty() {
+                            return Err(LayerError::Unexpected(format!(
+                                "{} bytes left after raw layer",
+                                rest.0.len()
+                            )));
+                        }
                     }
 
                     return Ok(())
@@ -76,17 +177,47 @@ macro_rules! gen_layer_types {
                 // # Layer: How the layer is consumed
                 let new_rest = if let Some(previous_layer) = layers.iter().last() {
                     match previous_layer {
-                        Layer::Ether(eth) => {
-                            match eth.ether_type {
+                        Layer::Ether(Ether { ether_type, .. })
+                        | Layer::LinuxSll(LinuxSll { protocol: ether_type, .. })
+                        | Layer::Vlan(Vlan { ether_type, .. }) => {
+                            match ether_type {
+                                ether::EtherType::VLAN | ether::EtherType::QINQ => {
+                                    do_layer!(Vlan, rest, layers)
+                                },
                                 ether::EtherType::IPv4 => {
                                     do_layer!(Ipv4, rest, layers)
                                 },
                                 ether::EtherType::IPv6 => {
                                     do_layer!(Ipv6, rest, layers)
                                 },
+                                ether::EtherType::ARP => {
+                                    do_layer!(Arp, rest, layers)
+                                },
+                                ether::EtherType::PTP => {
+                                    do_layer!(Ptp, rest, layers)
+                                },
+                                ether::EtherType::LLDP => {
+                                    do_layer!(Lldp, rest, layers)
+                                },
+                                ether::EtherType::AVTP => {
+                                    do_layer!(Avtp, rest, layers)
+                                },
+                                ether::EtherType::MACSEC => {
+                                    do_layer!(MacSec, rest, layers)
+                                },
+                                ether::EtherType::Length(_) => {
+                                    do_layer!(Llc, rest, layers)
+                                },
                                 _ => {
                                     // eth type not supported
-                                    return Layer::consume_layer(rest, layers, 0);
+                                    return match unknown {
+                                        UnknownHandling::Raw => Layer::consume_layer(rest, layers, 0, unknown, max_payload),
+                                        UnknownHandling::Stop => Ok(()),
+                                        UnknownHandling::Error => Err(LayerError::Parse(format!(
+                                            "unsupported ether type {:?}",
+                                            ether_type
+                                        ))),
+                                    };
                                 }
                             }
                         },
@@ -98,14 +229,30 @@ macro_rules! gen_layer_types {
                                 IpProtocol::UDP => {
                                     do_layer!(Udp, rest, layers)
                                 },
+                                IpProtocol::ICMP => {
+                                    do_layer!(Icmp, rest, layers)
+                                },
+                                IpProtocol::IGMP => {
+                                    do_layer!(Igmp, rest, layers)
+                                },
+                                IpProtocol::OSPF => {
+                                    do_layer!(Ospf, rest, layers)
+                                },
                                 _ => {
                                     // ip protocol not supported
-                                    return Layer::consume_layer(rest, layers, 0);
+                                    return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
                                 }
                             }
                         },
-                        Layer::Ipv6(ipv6) => {
-                            match ipv6.next_header {
+                        Layer::Ipv6(Ipv6 { next_header, .. })
+                        | Layer::Ipv6Ext(Ipv6Ext { next_header, .. }) => {
+                            match next_header {
+                                IpProtocol::HOPOPT
+                                | IpProtocol::IPV6ROUTE
+                                | IpProtocol::IPV6FRAG
+                                | IpProtocol::IPV6OPTS => {
+                                    do_layer!(Ipv6Ext, rest, layers)
+                                },
                                 IpProtocol::TCP => {
                                     do_layer!(Tcp, rest, layers)
                                 },
@@ -114,38 +261,183 @@ macro_rules! gen_layer_types {
                                 },
                                 _ => {
                                     // ip protocol not supported
-                                    return Layer::consume_layer(rest, layers, 0);
+                                    return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
+                                }
+                            }
+                        }
+                        Layer::Tcp(tcp) if !rest.0.is_empty() => {
+                            match (tcp.sport, tcp.dport) {
+                                (bgp::BGP_PORT, _) | (_, bgp::BGP_PORT) => {
+                                    do_port_layer!(Bgp, rest, layers, unknown, max_payload)
+                                },
+                                _ => {
+                                    // tcp port not supported
+                                    return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
+                                }
+                            }
+                        }
+                        Layer::Udp(udp) if !rest.0.is_empty() => {
+                            match (udp.sport, udp.dport) {
+                                (_, vxlan::VXLAN_PORT) => {
+                                    do_port_layer!(Vxlan, rest, layers, unknown, max_payload)
+                                },
+                                (_, ptp::PTP_EVENT_PORT) | (_, ptp::PTP_GENERAL_PORT) => {
+                                    do_port_layer!(Ptp, rest, layers, unknown, max_payload)
+                                },
+                                (_, netflow::NETFLOW_PORT) => {
+                                    do_port_layer!(NetflowV5, rest, layers, unknown, max_payload)
+                                },
+                                (_, gtpu::GTPU_PORT) => {
+                                    do_port_layer!(Gtpu, rest, layers, unknown, max_payload)
+                                },
+                                (_, dhcpv6::DHCPV6_CLIENT_PORT) | (_, dhcpv6::DHCPV6_SERVER_PORT) => {
+                                    do_port_layer!(Dhcpv6, rest, layers, unknown, max_payload)
+                                },
+                                (coap::COAP_PORT, _) | (_, coap::COAP_PORT) => {
+                                    do_port_layer!(Coap, rest, layers, unknown, max_payload)
+                                },
+                                (radius::RADIUS_AUTH_PORT, _)
+                                | (_, radius::RADIUS_AUTH_PORT)
+                                | (radius::RADIUS_ACCT_PORT, _)
+                                | (_, radius::RADIUS_ACCT_PORT) => {
+                                    do_port_layer!(Radius, rest, layers, unknown, max_payload)
+                                },
+                                _ => {
+                                    // udp port not supported
+                                    return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
+                                }
+                            }
+                        }
+                        Layer::Vxlan(_) => {
+                            do_layer!(Ether, rest, layers)
+                        }
+                        Layer::Null(loopback) => {
+                            match loopback.address_family() {
+                                null::NULL_AF_INET => {
+                                    do_layer!(Ipv4, rest, layers)
+                                },
+                                family if null::NULL_AF_INET6.contains(&family) => {
+                                    do_layer!(Ipv6, rest, layers)
+                                },
+                                _ => {
+                                    // address family not supported
+                                    return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
+                                }
+                            }
+                        }
+                        Layer::Llc(Llc { dsap: llc::LLC_SAP_STP, ssap: llc::LLC_SAP_STP, .. }) => {
+                            do_layer!(Bpdu, rest, layers)
+                        }
+                        Layer::Gtpu(Gtpu { message_type, .. }) if *message_type == gtpu::GTPU_G_PDU => {
+                            // the user packet is a bare ip packet
+                            match rest.0.first().map(|v| v >> 4) {
+                                Some(4) => {
+                                    do_layer!(Ipv4, rest, layers)
+                                },
+                                Some(6) => {
+                                    do_layer!(Ipv6, rest, layers)
+                                },
+                                _ => {
+                                    return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
                                 }
                             }
                         }
                         _ => {
                             // nothing to consume next, create raw layer with rest
-                            return Layer::consume_layer(rest, layers, 0);
+                            return Layer::consume_layer(rest, layers, 0, unknown, max_payload);
                         }
                     }
 
                 } else {
-                    unreachable!("dev error: no previous layer available from caller")
+                    return Err(LayerError::Unexpected(
+                        "no previous layer to consume from".to_string(),
+                    ));
                 };
 
-                Layer::consume_layer(new_rest, layers, max_depth-1)
+                Layer::consume_layer(new_rest, layers, max_depth-1, unknown, max_payload)
             }
 
             /// Returns a vector of `Layer` consumed from the byte stream
             /// This will consume the next-layer in accordance to the protocol
             pub fn from_bytes_multi_layer(input: &[u8], max_depth: usize) -> Result<Vec<Layer>, LayerError> {
-                let mut layers = Vec::new();
-                let mut rest = (input, 0);
+                Layer::from_bytes_multi_layer_as(input, LayerType::Ether, max_depth)
+            }
 
-                rest = {
-                    do_layer!(Ether, rest, layers)
-                };
+            /// Returns a vector of `Layer` consumed from the byte stream, starting with a `layer_type` layer
+            /// This will consume the next-layer in accordance to the protocol
+            pub fn from_bytes_multi_layer_as(input: &[u8], layer_type: LayerType, max_depth: usize) -> Result<Vec<Layer>, LayerError> {
+                Layer::from_bytes_multi_layer_with(input, layer_type, max_depth, UnknownHandling::Raw)
+            }
+
+            /// Returns a vector of `Layer` consumed from the byte stream, starting with a `layer_type` layer
+            /// The bytes following an unsupported ether type are handled according to `unknown`
+            pub fn from_bytes_multi_layer_with(input: &[u8], layer_type: LayerType, max_depth: usize, unknown: UnknownHandling) -> Result<Vec<Layer>, LayerError> {
+                Layer::from_bytes_multi_layer_bounded(input, layer_type, max_depth, unknown, usize::MAX)
+            }
+
+            /// Returns a vector of `Layer` consumed from the byte stream, starting with a `layer_type` layer
+            /// The bytes following an unsupported ether type are handled according to `unknown`,
+            /// a Raw layer of more than `max_payload` bytes is an error and is not read
+            pub fn from_bytes_multi_layer_bounded(input: &[u8], layer_type: LayerType, max_depth: usize, unknown: UnknownHandling, max_payload: usize) -> Result<Vec<Layer>, LayerError> {
+                if layer_type == LayerType::Raw && input.len() > max_payload {
+                    return Err(LayerError::Parse(format!(
+                        "raw payload of {} bytes exceeds the maximum of {}",
+                        input.len(),
+                        max_payload
+                    )));
+                }
+
+                let (rest, layer) = Layer::parse_as(input, layer_type)?;
+                let mut layers = vec![layer];
 
-                Layer::consume_layer(rest, &mut layers, max_depth)?;
+                Layer::consume_layer((rest, 0), &mut layers, max_depth, unknown, max_payload)?;
 
                 Ok(layers)
             }
 
+            /// Reads a single layer of type `layer_type` from bytes
+            /// Returns the remaining bytes and the layer
+            pub fn parse_as(input: &[u8], layer_type: LayerType) -> Result<(&[u8], Layer), LayerError> {
+                let ((rest, _), layer) = match layer_type {
+                    $(
+                        LayerType::$types => {
+                            let (rest, layer) = $types::from_bytes((input, 0))?;
+                            (rest, Layer::$types(layer))
+                        }
+                    ),*
+                };
+
+                Ok((rest, layer))
+            }
+
+            /// Replaces the layer with a layer of the same type read from `bytes`
+            /// All of `bytes` must be read, the layer is left unchanged on error
+            pub fn set_raw_bytes(&mut self, bytes: &[u8]) -> Result<(), LayerError> {
+                *self = Layer::from_tagged_bytes(self.layer_type(), bytes)?;
+
+                Ok(())
+            }
+
+            /// Writes the layer, along with its layer type
+            pub fn to_tagged_bytes(&self) -> Result<(LayerType, Vec<u8>), LayerError> {
+                Ok((self.layer_type(), self.to_bytes()?))
+            }
+
+            /// Reads a layer of type `layer_type` from bytes written by `to_tagged_bytes`
+            /// All of `bytes` must be read
+            pub fn from_tagged_bytes(layer_type: LayerType, bytes: &[u8]) -> Result<Layer, LayerError> {
+                let (rest, layer) = Layer::parse_as(bytes, layer_type)?;
+                if !rest.is_empty() {
+                    return Err(LayerError::Parse(format!(
+                        "{} bytes left after {} layer",
+                        rest.len(),
+                        layer.name()
+                    )));
+                }
+
+                Ok(layer)
+            }
+
             /// Writes the layer
             pub fn to_bytes(&self) -> Result<Vec<u8>, LayerError> {
                 let ret = match self {
@@ -174,11 +466,67 @@ macro_rules! gen_layer_types {
         pub enum LayerType {
             $($types,)*
         }
+
+        impl LayerType {
+            /// Returns the protocol name of the layer type
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(
+                        LayerType::$types => $names
+                    ),*
+                }
+            }
+        }
     };
 }
 
 // # LAYER: Add type to Layer enum
-gen_layer_types!(Raw, Ether, Ipv4, Ipv6, Tcp, Udp,);
+gen_layer_types!(
+    Raw => "Raw",
+    Ether => "Ether",
+    LinuxSll => "Linux SLL",
+    Vlan => "VLAN",
+    Ipv4 => "IPv4",
+    Ipv6 => "IPv6",
+    Ipv6Ext => "IPv6 Extension",
+    Tcp => "TCP",
+    Udp => "UDP",
+    Vxlan => "VXLAN",
+    Bgp => "BGP",
+    Smb => "SMB",
+    Ptp => "PTP",
+    WireGuard => "WireGuard",
+    Igmp => "IGMP",
+    Lldp => "LLDP",
+    Avtp => "AVTP",
+    NetflowV5 => "NetFlow v5",
+    MacSec => "MACsec",
+    Arp => "ARP",
+    Gtpu => "GTP-U",
+    Ospf => "OSPF",
+    Radiotap => "Radiotap",
+    Icmp => "ICMP",
+    Llc => "LLC",
+    Bpdu => "BPDU",
+    Dhcpv6 => "DHCPv6",
+    Coap => "CoAP",
+    Null => "Null/Loopback",
+    Radius => "RADIUS",
+);
+
+impl std::fmt::Display for Layer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layer::Raw(v) => write!(f, "{}", v),
+            Layer::Ether(v) => write!(f, "{}", v),
+            Layer::Ipv4(v) => write!(f, "{}", v),
+            Layer::Ipv6(v) => write!(f, "{}", v),
+            Layer::Tcp(v) => write!(f, "{}", v),
+            Layer::Udp(v) => write!(f, "{}", v),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
 
 /// Internal macro used to expand layer macros, not for public use
 #[doc(hidden)]
@@ -349,3 +697,566 @@ macro_rules! udp {
         $crate::__builder_impl!(Udp, $($field_ident : $field),*)
     );
 }
+
+/**
+Create a [Vxlan](layer/vxlan/struct.Vxlan.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Vxlan(Vxlan), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = vxlan! {
+    vni: 42
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! vxlan {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Vxlan, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Bgp](layer/bgp/struct.Bgp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Bgp(Bgp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+# use rust_packet::layer::bgp::BgpMessage;
+let layer = bgp! {
+    message: BgpMessage::Keepalive
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! bgp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Bgp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Ipv6Ext](layer/ip/ipv6ext/struct.Ipv6Ext.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Ipv6Ext(Ipv6Ext), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+# use rust_packet::layer::ip::IpProtocol;
+let layer = ipv6ext! {
+    next_header: IpProtocol::UDP
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! ipv6ext {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Ipv6Ext, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Vlan](layer/vlan/struct.Vlan.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Vlan(Vlan), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = vlan! {
+    vid: 100
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! vlan {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Vlan, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Smb](layer/smb/struct.Smb.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Smb(Smb), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+# use rust_packet::layer::smb::Smb2Header;
+let layer = smb! {
+    smb2: Some(Smb2Header {
+        command: 5,
+        ..Smb2Header::default()
+    })
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! smb {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Smb, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [LinuxSll](layer/sll/struct.LinuxSll.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::LinuxSll(LinuxSll), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = linux_sll! {
+    packet_type: 4
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! linux_sll {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(LinuxSll, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Ptp](layer/ptp/struct.Ptp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Ptp(Ptp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = ptp! {
+    sequence_id: 1
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! ptp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Ptp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [WireGuard](layer/wireguard/struct.WireGuard.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::WireGuard(WireGuard), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = wireguard! {
+    reserved: [0; 3]
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! wireguard {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(WireGuard, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Igmp](layer/igmp/struct.Igmp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Igmp(Igmp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = igmp! {
+    max_resp_time: 100
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! igmp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Igmp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Lldp](layer/lldp/struct.Lldp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Lldp(Lldp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = lldp! {}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! lldp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Lldp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Avtp](layer/avtp/struct.Avtp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Avtp(Avtp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = avtp! {
+    stream_id: [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x00, 0x01]
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! avtp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Avtp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [NetflowV5](layer/netflow/struct.NetflowV5.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::NetflowV5(NetflowV5), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = netflow_v5! {
+    flow_sequence: 42
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! netflow_v5 {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(NetflowV5, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [MacSec](layer/macsec/struct.MacSec.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::MacSec(MacSec), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = macsec! {
+    packet_number: 42,
+    sci: Some(0x0011223344550001),
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! macsec {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(MacSec, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Arp](layer/arp/struct.Arp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Arp(Arp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = arp! {
+    sender_ip: "10.0.0.1".parse().unwrap(),
+    target_ip: "10.0.0.2".parse().unwrap(),
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! arp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Arp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Gtpu](layer/gtpu/struct.Gtpu.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Gtpu(Gtpu), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = gtpu! {
+    teid: 0x12345678
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! gtpu {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Gtpu, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Ospf](layer/ospf/struct.Ospf.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Ospf(Ospf), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = ospf! {
+    router_id: "10.0.0.1".parse().unwrap(),
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! ospf {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Ospf, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Radiotap](layer/radiotap/struct.Radiotap.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Radiotap(Radiotap), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = radiotap! {
+    present: vec![0x20],
+    fields: vec![0xd6],
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! radiotap {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Radiotap, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Icmp](layer/icmp/struct.Icmp.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Icmp(Icmp), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = icmp! {
+    rest_of_header: 0x00010001,
+    data: b"hello".to_vec(),
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! icmp {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Icmp, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Llc](layer/llc/struct.Llc.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Llc(Llc), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = llc! {
+    dsap: 0x42,
+    ssap: 0x42,
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! llc {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Llc, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Bpdu](layer/bpdu/struct.Bpdu.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Bpdu(Bpdu), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = bpdu! {
+    bpdu_type: 0x80,
+    config: None,
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! bpdu {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Bpdu, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Dhcpv6](layer/dhcpv6/struct.Dhcpv6.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Dhcpv6(Dhcpv6), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = dhcpv6! {
+    transaction_id: 0x100874,
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! dhcpv6 {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Dhcpv6, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Coap](layer/coap/struct.Coap.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Coap(Coap), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = coap! {
+    message_id: 0x04d2,
+    token: vec![0xab, 0xcd],
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! coap {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Coap, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Null](layer/null/struct.Null.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Null(Null), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = null! {
+    family: 30,
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! null {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Null, $($field_ident : $field),*)
+    );
+}
+
+/**
+Create a [Radius](layer/radius/struct.Radius.html) layer
+
+Fields which are not provided are defaulted.
+
+Returns `Result<Layer::Radius(Radius), LayerError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let layer = radius! {
+    identifier: 42,
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! radius {
+    ($($field_ident:ident : $field:expr),* $(,)?)=> (
+        $crate::__builder_impl!(Radius, $($field_ident : $field),*)
+    );
+}
@@ -0,0 +1,174 @@
+/*!
+NetFlow layer
+*/
+use deku::prelude::*;
+use std::net::Ipv4Addr;
+
+/// Well-known UDP port for NetFlow
+pub const NETFLOW_PORT: u16 = 2055;
+
+/// Length of the NetFlow v5 header
+pub const NETFLOW_V5_HEADER_LEN: usize = 24;
+
+/// Length of a NetFlow v5 flow record
+pub const NETFLOW_V5_RECORD_LEN: usize = 48;
+
+/// NetFlow v5 Flow Record
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct NetflowV5Record {
+    pub src: Ipv4Addr,     // Source IP Address
+    pub dst: Ipv4Addr,     // Destination IP Address
+    pub nexthop: Ipv4Addr, // Next Hop Router IP Address
+    pub input: u16,        // SNMP Index of Input Interface
+    pub output: u16,       // SNMP Index of Output Interface
+    pub packets: u32,      // Packets in the Flow
+    pub octets: u32,       // Total Layer 3 Bytes in the Flow
+    pub first: u32,        // SysUptime at Start of Flow
+    pub last: u32,         // SysUptime at Last Packet of the Flow
+    pub sport: u16,        // Source Port
+    pub dport: u16,        // Destination Port
+    pub pad1: u8,
+    pub tcp_flags: u8, // Cumulative OR of TCP Flags
+    pub protocol: u8,  // IP Protocol
+    pub tos: u8,       // IP Type of Service
+    pub src_as: u16,   // Source Autonomous System Number
+    pub dst_as: u16,   // Destination Autonomous System Number
+    pub src_mask: u8,  // Source Address Prefix Mask Bits
+    pub dst_mask: u8,  // Destination Address Prefix Mask Bits
+    pub pad2: u16,
+}
+
+/// NetFlow v5 Header and Flow Records
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct NetflowV5 {
+    pub version: u16, // NetFlow Export Format Version
+    #[deku(update = "self.records.len()")]
+    pub count: u16, // Number of Flow Records
+    pub sys_uptime: u32, // Milliseconds Since the Export Device Booted
+    pub unix_secs: u32, // Seconds Since the Epoch
+    pub unix_nsecs: u32, // Residual Nanoseconds Since the Epoch
+    pub flow_sequence: u32, // Sequence Counter of Total Flows Seen
+    pub engine_type: u8, // Type of Flow-Switching Engine
+    pub engine_id: u8, // Slot Number of the Flow-Switching Engine
+    pub sampling_interval: u16, // Sampling Mode and Interval
+    #[deku(count = "count")]
+    pub records: Vec<NetflowV5Record>,
+}
+
+impl Default for NetflowV5 {
+    fn default() -> Self {
+        NetflowV5 {
+            version: 5,
+            count: 0,
+            sys_uptime: 0,
+            unix_secs: 0,
+            unix_nsecs: 0,
+            flow_sequence: 0,
+            engine_type: 0,
+            engine_id: 0,
+            sampling_interval: 0,
+            records: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case(
+            &hex!("00050001000010005f000000000000000000000100000000 0a0000010a00010100000000000100020000000a000003e8000001000000020003e80050001806000000000018180000"),
+            NetflowV5 {
+                version: 5,
+                count: 1,
+                sys_uptime: 0x1000,
+                unix_secs: 0x5f000000,
+                unix_nsecs: 0,
+                flow_sequence: 1,
+                engine_type: 0,
+                engine_id: 0,
+                sampling_interval: 0,
+                records: vec![NetflowV5Record {
+                    src: Ipv4Addr::new(10, 0, 0, 1),
+                    dst: Ipv4Addr::new(10, 0, 1, 1),
+                    nexthop: Ipv4Addr::new(0, 0, 0, 0),
+                    input: 1,
+                    output: 2,
+                    packets: 10,
+                    octets: 1000,
+                    first: 0x100,
+                    last: 0x200,
+                    sport: 1000,
+                    dport: 80,
+                    pad1: 0,
+                    tcp_flags: 0x18,
+                    protocol: 6,
+                    tos: 0,
+                    src_as: 0,
+                    dst_as: 0,
+                    src_mask: 24,
+                    dst_mask: 24,
+                    pad2: 0,
+                }],
+            },
+        ),
+        #[should_panic(expected = "Incomplete")]
+        case::missing_record(
+            &hex!("00050002000010005f000000000000000000000100000000 0a0000010a00010100000000000100020000000a000003e8000001000000020003e80050001806000000000018180000"),
+            NetflowV5::default(),
+        ),
+    )]
+    fn test_netflow_v5(input: &[u8], expected: NetflowV5) {
+        let ret_read = NetflowV5::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_netflow_v5_update_count() {
+        let mut netflow = NetflowV5 {
+            records: vec![
+                NetflowV5Record {
+                    src: Ipv4Addr::new(10, 0, 0, 1),
+                    dst: Ipv4Addr::new(10, 0, 1, 1),
+                    nexthop: Ipv4Addr::new(0, 0, 0, 0),
+                    input: 0,
+                    output: 0,
+                    packets: 0,
+                    octets: 0,
+                    first: 0,
+                    last: 0,
+                    sport: 0,
+                    dport: 0,
+                    pad1: 0,
+                    tcp_flags: 0,
+                    protocol: 0,
+                    tos: 0,
+                    src_as: 0,
+                    dst_as: 0,
+                    src_mask: 0,
+                    dst_mask: 0,
+                    pad2: 0,
+                };
+                2
+            ],
+            ..NetflowV5::default()
+        };
+
+        netflow.update().unwrap();
+
+        assert_eq!(2, netflow.count);
+        assert_eq!(
+            NETFLOW_V5_HEADER_LEN + 2 * NETFLOW_V5_RECORD_LEN,
+            netflow.to_bytes().unwrap().len()
+        );
+    }
+}
@@ -0,0 +1,76 @@
+/*!
+BSD loopback layer
+*/
+use deku::prelude::*;
+
+/// Address family of IPv4
+pub const NULL_AF_INET: u32 = 2;
+
+/// Address families of IPv6, which differ between BSDs
+pub const NULL_AF_INET6: [u32; 3] = [24, 28, 30];
+
+/**
+BSD Loopback Header
+
+Pseudo link-layer header of captures on BSD loopback devices (LINKTYPE_NULL and LINKTYPE_LOOP)
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                        Address Family                         |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+
+The family is in the byte order of the capturing host for LINKTYPE_NULL and big-endian for
+LINKTYPE_LOOP. It is read as little-endian, see
+[address_family](#method.address_family) for the family in either byte order.
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "little")]
+pub struct Null {
+    pub family: u32,
+}
+
+impl Null {
+    /// Returns the address family, whichever the byte order it was written in
+    ///
+    /// Families are small values, a family with its low 16 bits unset was written big-endian
+    pub fn address_family(&self) -> u32 {
+        if self.family & 0xffff == 0 {
+            self.family.swap_bytes()
+        } else {
+            self.family
+        }
+    }
+}
+
+impl Default for Null {
+    fn default() -> Self {
+        Null {
+            family: NULL_AF_INET,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected, family,
+        case::little_endian(&hex!("02000000"), Null { family: 2 }, NULL_AF_INET),
+        case::big_endian(&hex!("00000002"), Null { family: 0x02000000 }, NULL_AF_INET),
+        case::ipv6(&hex!("1e000000"), Null { family: 30 }, 30),
+    )]
+    fn test_null(input: &[u8], expected: Null, family: u32) {
+        let ret_read = Null::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+        assert_eq!(family, ret_read.address_family());
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+}
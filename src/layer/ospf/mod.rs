@@ -0,0 +1,143 @@
+/*!
+OSPFv2 layer
+*/
+use crate::layer::ip::checksum;
+use crate::layer::LayerError;
+use deku::prelude::*;
+use std::net::Ipv4Addr;
+
+/// Length of the OSPF common header
+pub const OSPF_HEADER_LEN: usize = 24;
+
+/// OSPF Hello packet type
+pub const OSPF_HELLO: u8 = 1;
+
+/// Cryptographic authentication type, the checksum is not calculated
+pub const OSPF_AUTH_CRYPTOGRAPHIC: u16 = 2;
+
+/**
+OSPF Header
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|   Version #   |     Type      |         Packet length         |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                          Router ID                            |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                           Area ID                             |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|           Checksum            |             AuType            |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                       Authentication                          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                       Authentication                          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Ospf {
+    pub version: u8,
+    pub ospf_type: u8,
+    #[deku(update = "OSPF_HEADER_LEN + self.body.len()")]
+    pub length: u16, // Length of the packet, including the header
+    pub router_id: Ipv4Addr,
+    pub area_id: Ipv4Addr,
+    #[deku(update = "self.update_checksum()?")]
+    pub checksum: u16,
+    pub auth_type: u16,
+    pub auth_data: [u8; 8],
+    /// Type-specific body, kept as bytes
+    #[deku(
+        count = "usize::from(*length).checked_sub(OSPF_HEADER_LEN).ok_or_else(|| DekuError::Parse(\"invalid ospf length\".to_string()))?"
+    )]
+    pub body: Vec<u8>,
+}
+
+impl Ospf {
+    fn update_checksum(&self) -> Result<u16, DekuError> {
+        if self.auth_type == OSPF_AUTH_CRYPTOGRAPHIC {
+            return Ok(self.checksum);
+        }
+
+        // the authentication field is excluded from the checksum
+        let mut ospf = self.clone();
+        ospf.checksum = 0;
+        ospf.auth_data = [0; 8];
+
+        checksum(&ospf.to_bytes()?).map_err(|e| DekuError::InvalidParam(e.to_string()))
+    }
+
+    /// Returns the checksum of the layer, without updating it
+    pub fn compute_checksum(&self) -> Result<u16, LayerError> {
+        Ok(self.update_checksum()?)
+    }
+}
+
+impl Default for Ospf {
+    fn default() -> Self {
+        Ospf {
+            version: 2,
+            ospf_type: OSPF_HELLO,
+            length: OSPF_HEADER_LEN as u16,
+            router_id: Ipv4Addr::UNSPECIFIED,
+            area_id: Ipv4Addr::UNSPECIFIED,
+            checksum: 0xfde6,
+            auth_type: 0,
+            auth_data: [0; 8],
+            body: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::hello(
+            &hex!("0201002c0a0000010000000130f300000000000000000000 ffffff00000a020100000028c0a8010100000000"),
+            Ospf {
+                length: 44,
+                router_id: Ipv4Addr::new(10, 0, 0, 1),
+                area_id: Ipv4Addr::new(0, 0, 0, 1),
+                checksum: 0x30f3,
+                body: hex!("ffffff00000a020100000028c0a8010100000000").to_vec(),
+                ..Ospf::default()
+            },
+        ),
+        #[should_panic(expected = "invalid ospf length")]
+        case::length_too_short(&hex!("020100100a0000010000000130f300000000000000000000"), Ospf::default()),
+    )]
+    fn test_ospf(input: &[u8], expected: Ospf) {
+        let (_rest, ret_read) = Ospf::from_bytes((input, 0)).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[rstest(auth_type, expected,
+        case(0, 0x30f3),
+        case(OSPF_AUTH_CRYPTOGRAPHIC, 0x0000),
+    )]
+    fn test_ospf_update_checksum(auth_type: u16, expected: u16) {
+        let mut ospf = Ospf {
+            router_id: Ipv4Addr::new(10, 0, 0, 1),
+            area_id: Ipv4Addr::new(0, 0, 0, 1),
+            checksum: 0,
+            auth_type,
+            auth_data: *b"password",
+            body: hex!("ffffff00000a020100000028c0a8010100000000").to_vec(),
+            ..Ospf::default()
+        };
+
+        ospf.update().unwrap();
+        assert_eq!(44, ospf.length);
+        assert_eq!(expected, ospf.checksum);
+    }
+}
@@ -0,0 +1,206 @@
+/*!
+PTP layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+use std::convert::TryFrom;
+
+/// Well-known UDP port for PTP event messages
+pub const PTP_EVENT_PORT: u16 = 319;
+
+/// Well-known UDP port for PTP general messages
+pub const PTP_GENERAL_PORT: u16 = 320;
+
+/// Length of the PTP common header
+pub const PTP_HEADER_LEN: u16 = 34;
+
+/**
+PTP (IEEE 1588) Common Header
+
+The message specific body is kept as bytes
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|SdoId  |MsgType|Minor  |Version|         Message Length        |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+| Domain Number | Minor SdoId   |          Flag Field           |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                                                               |
++                       Correction Field                        +
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                    Message Type Specific                      |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                                                               |
++                       Clock Identity                          +
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|          Port Number          |          Sequence Id          |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+| Control Field | Log Msg Intvl |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Ptp {
+    #[deku(bits = "4")]
+    pub major_sdo_id: u8, // Major SdoId (transportSpecific)
+    #[deku(bits = "4")]
+    pub message_type: u8, // Message Type
+    #[deku(bits = "4")]
+    pub minor_version: u8, // Minor Version PTP
+    #[deku(bits = "4")]
+    pub version: u8, // Version PTP
+    #[deku(update = "self.update_length()?")]
+    pub length: u16, // Message Length, including the header
+    pub domain: u8, // Domain Number
+    pub minor_sdo_id: u8, // Minor SdoId
+    pub flags: u16, // Flag Field
+    pub correction: i64, // Correction Field
+    pub message_type_specific: u32, // Message Type Specific
+    pub clock_identity: [u8; 8], // Source Port Identity: Clock Identity
+    pub port_number: u16, // Source Port Identity: Port Number
+    pub sequence_id: u16, // Sequence Id
+    pub control: u8, // Control Field
+    pub log_message_interval: i8, // Log Message Interval
+    #[deku(reader = "Ptp::read_body(*length, deku::rest)")]
+    pub body: Vec<u8>, // Message specific body
+}
+
+impl Ptp {
+    fn update_length(&self) -> Result<u16, DekuError> {
+        let ptp = self.to_bytes()?;
+        Ok(u16::try_from(ptp.len())?)
+    }
+
+    fn read_body(
+        length: u16, // length of the message, including the header
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+        let body_len = length
+            .checked_sub(PTP_HEADER_LEN)
+            .ok_or_else(|| DekuError::Parse("error: invalid ptp length".to_string()))?;
+
+        // slice off length of body
+        let bits = usize::from(body_len) * 8;
+
+        // Check split_at precondition
+        if bits > rest.len() {
+            return Err(DekuError::Parse(
+                "not enough data to read ptp body".to_string(),
+            ));
+        }
+
+        let (body, rest) = rest.split_at(bits);
+
+        Ok((rest, body.as_raw_slice().to_vec()))
+    }
+}
+
+impl Default for Ptp {
+    fn default() -> Self {
+        Ptp {
+            major_sdo_id: 0,
+            message_type: 0,
+            minor_version: 0,
+            version: 2,
+            length: PTP_HEADER_LEN,
+            domain: 0,
+            minor_sdo_id: 0,
+            flags: 0,
+            correction: 0,
+            message_type_specific: 0,
+            clock_identity: [0; 8],
+            port_number: 0,
+            sequence_id: 0,
+            control: 0,
+            log_message_interval: 0,
+            body: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::sync(
+            &hex!("0002002c00000200000000000000000000000000001b19fffe00000100011234 00fd 000060a1b2c31a2b3c4d"),
+            Ptp {
+                major_sdo_id: 0,
+                message_type: 0,
+                minor_version: 0,
+                version: 2,
+                length: 44,
+                domain: 0,
+                minor_sdo_id: 0,
+                flags: 0x0200,
+                correction: 0,
+                message_type_specific: 0,
+                clock_identity: [0x00, 0x1b, 0x19, 0xff, 0xfe, 0x00, 0x00, 0x01],
+                port_number: 1,
+                sequence_id: 0x1234,
+                control: 0,
+                log_message_interval: -3,
+                body: vec![0x00, 0x00, 0x60, 0xa1, 0xb2, 0xc3, 0x1a, 0x2b, 0x3c, 0x4d],
+            },
+        ),
+        #[should_panic(expected = "Parse(\"error: invalid ptp length\")")]
+        case::invalid_length(
+            &hex!("0002002000000200000000000000000000000000001b19fffe00000100011234 00fd"),
+            Ptp::default(),
+        ),
+    )]
+    fn test_ptp(input: &[u8], expected: Ptp) {
+        let ret_read = Ptp::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_ptp_update_length() {
+        let mut ptp = Ptp {
+            length: 0,
+            body: vec![0; 10],
+            ..Ptp::default()
+        };
+
+        ptp.update().unwrap();
+
+        assert_eq!(44, ptp.length);
+    }
+
+    #[test]
+    fn test_ptp_default() {
+        assert_eq!(
+            Ptp {
+                major_sdo_id: 0,
+                message_type: 0,
+                minor_version: 0,
+                version: 2,
+                length: 34,
+                domain: 0,
+                minor_sdo_id: 0,
+                flags: 0,
+                correction: 0,
+                message_type_specific: 0,
+                clock_identity: [0; 8],
+                port_number: 0,
+                sequence_id: 0,
+                control: 0,
+                log_message_interval: 0,
+                body: vec![],
+            },
+            Ptp::default()
+        )
+    }
+}
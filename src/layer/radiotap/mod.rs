@@ -0,0 +1,205 @@
+/*!
+Radiotap layer
+
+Capture metadata prepended to 802.11 frames on wireless captures (LINKTYPE_IEEE802_11_RADIOTAP)
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+use std::convert::TryInto;
+
+/// Length of the radiotap header up to the first present bitmap
+pub const RADIOTAP_HEADER_LEN: usize = 4;
+
+/// Present bit of the channel field
+pub const RADIOTAP_CHANNEL: usize = 3;
+
+/// Present bit of the antenna signal field, in dBm
+pub const RADIOTAP_ANTENNA_SIGNAL: usize = 5;
+
+// Present bit of an extended present bitmap
+const RADIOTAP_EXT: u32 = 1 << 31;
+
+// (alignment, size) of the fields of the first present bitmap, indexed by present bit
+const RADIOTAP_FIELDS: [(usize, usize); 15] = [
+    (8, 8), // TSFT
+    (1, 1), // Flags
+    (1, 1), // Rate
+    (2, 4), // Channel
+    (2, 2), // FHSS
+    (1, 1), // Antenna signal, dBm
+    (1, 1), // Antenna noise, dBm
+    (2, 2), // Lock quality
+    (2, 2), // TX attenuation
+    (2, 2), // TX attenuation, dB
+    (1, 1), // TX power, dBm
+    (1, 1), // Antenna
+    (1, 1), // Antenna signal, dB
+    (1, 1), // Antenna noise, dB
+    (2, 2), // RX flags
+];
+
+/**
+Radiotap Header
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|    Version    |      Pad      |            Length             |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                     Present Bitmap [1..N]                     |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                         Aligned Fields                        |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+
+The fields are little-endian, each aligned to its natural size from the start of the header.
+They are kept as bytes and read through the accessors.
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "little")]
+pub struct Radiotap {
+    pub version: u8,
+    pub pad: u8,
+    #[deku(update = "RADIOTAP_HEADER_LEN + self.present.len() * 4 + self.fields.len()")]
+    pub length: u16, // Length of the whole header
+    #[deku(reader = "Radiotap::read_present(deku::rest)")]
+    pub present: Vec<u32>,
+    #[deku(
+        count = "usize::from(*length).checked_sub(RADIOTAP_HEADER_LEN + present.len() * 4).ok_or_else(|| DekuError::Parse(\"invalid radiotap length\".to_string()))?"
+    )]
+    pub fields: Vec<u8>,
+}
+
+impl Radiotap {
+    // read the present bitmaps, each one with the ext bit set is followed by another
+    fn read_present(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<u32>), DekuError> {
+        let mut rest = rest;
+        let mut present = Vec::new();
+        loop {
+            let (new_rest, bitmap) = u32::read(rest, deku::ctx::Endian::Little)?;
+            present.push(bitmap);
+            rest = new_rest;
+
+            if bitmap & RADIOTAP_EXT == 0 {
+                break;
+            }
+        }
+
+        Ok((rest, present))
+    }
+
+    /// Returns the bytes of the field of present bit `bit` of the first present bitmap
+    ///
+    /// Only the fields up to the RX flags (bit 14) are known, `None` is returned for the
+    /// others or if the field is not present
+    pub fn field(&self, bit: usize) -> Option<&[u8]> {
+        let present = *self.present.first()?;
+        if bit >= RADIOTAP_FIELDS.len() || present & (1 << bit) == 0 {
+            return None;
+        }
+
+        let fields_start = RADIOTAP_HEADER_LEN + self.present.len() * 4;
+        let mut offset = fields_start;
+        for (i, (align, size)) in RADIOTAP_FIELDS.iter().enumerate().take(bit + 1) {
+            if present & (1 << i) == 0 {
+                continue;
+            }
+
+            offset = (offset + align - 1) / align * align;
+            if i == bit {
+                return self.fields.get(offset - fields_start..offset - fields_start + size);
+            }
+            offset += size;
+        }
+
+        None
+    }
+
+    /// Returns the channel frequency in MHz
+    pub fn channel_frequency(&self) -> Option<u16> {
+        let channel = self.field(RADIOTAP_CHANNEL)?;
+        Some(u16::from_le_bytes(channel[..2].try_into().ok()?))
+    }
+
+    /// Returns the antenna signal strength in dBm
+    pub fn antenna_signal(&self) -> Option<i8> {
+        self.field(RADIOTAP_ANTENNA_SIGNAL).map(|signal| i8::from_le_bytes([signal[0]]))
+    }
+}
+
+impl Default for Radiotap {
+    fn default() -> Self {
+        Radiotap {
+            version: 0,
+            pad: 0,
+            length: 8,
+            present: vec![0],
+            fields: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::LayerType;
+    use crate::packet::Packet;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected, frequency, signal,
+        case::channel(
+            &hex!("00000f00 2e000000 00 02 8509a000 d6"),
+            Radiotap {
+                length: 15,
+                present: vec![0x2e],
+                fields: hex!("00028509a000d6").to_vec(),
+                ..Radiotap::default()
+            },
+            Some(2437),
+            Some(-42),
+        ),
+        case::tsft_ext(
+            &hex!("00001f00 2b000080 00000000 00000000 0102030405060708 10 00 8509a000 d6"),
+            Radiotap {
+                length: 31,
+                present: vec![0x8000002b, 0],
+                fields: hex!("00000000 0102030405060708 10 00 8509a000 d6").to_vec(),
+                ..Radiotap::default()
+            },
+            Some(2437),
+            Some(-42),
+        ),
+        case::none(&hex!("00000800 00000000"), Radiotap::default(), None, None),
+        #[should_panic(expected = "invalid radiotap length")]
+        case::length_too_short(&hex!("00000400 00000000"), Radiotap::default(), None, None),
+    )]
+    fn test_radiotap(
+        input: &[u8],
+        expected: Radiotap,
+        frequency: Option<u16>,
+        signal: Option<i8>,
+    ) {
+        let (_rest, ret_read) = Radiotap::from_bytes((input, 0)).unwrap();
+        assert_eq!(expected, ret_read);
+        assert_eq!(frequency, ret_read.channel_frequency());
+        assert_eq!(signal, ret_read.antenna_signal());
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_radiotap_packet() {
+        // Radiotap / 802.11 beacon header, the 802.11 frame is read as Raw
+        let test_data = hex!("00000f00 2e000000 00 02 8509a000 d6 80000000ffffffffffff");
+
+        let pkt = Packet::from_bytes_as(test_data.as_ref(), LayerType::Radiotap).unwrap();
+        assert_eq!(vec![LayerType::Radiotap, LayerType::Raw], pkt.protocol_stack());
+        assert_eq!(Some(2437), pkt.radiotap().unwrap().channel_frequency());
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+}
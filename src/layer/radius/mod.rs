@@ -0,0 +1,236 @@
+/*!
+RADIUS layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/// UDP port of RADIUS authentication
+pub const RADIUS_AUTH_PORT: u16 = 1812;
+
+/// UDP port of RADIUS accounting
+pub const RADIUS_ACCT_PORT: u16 = 1813;
+
+/// Length of the RADIUS header, without attributes
+pub const RADIUS_HEADER_LEN: u16 = 20;
+
+/// Access-Request code
+pub const RADIUS_ACCESS_REQUEST: u8 = 1;
+
+/// Access-Accept code
+pub const RADIUS_ACCESS_ACCEPT: u8 = 2;
+
+/// Access-Reject code
+pub const RADIUS_ACCESS_REJECT: u8 = 3;
+
+/// Accounting-Request code
+pub const RADIUS_ACCOUNTING_REQUEST: u8 = 4;
+
+/// Accounting-Response code
+pub const RADIUS_ACCOUNTING_RESPONSE: u8 = 5;
+
+/// Access-Challenge code
+pub const RADIUS_ACCESS_CHALLENGE: u8 = 11;
+
+/// User-Name attribute type
+pub const RADIUS_ATTR_USER_NAME: u8 = 1;
+
+/// User-Password attribute type
+pub const RADIUS_ATTR_USER_PASSWORD: u8 = 2;
+
+/// NAS-IP-Address attribute type
+pub const RADIUS_ATTR_NAS_IP_ADDRESS: u8 = 4;
+
+/// NAS-Port attribute type
+pub const RADIUS_ATTR_NAS_PORT: u8 = 5;
+
+/**
+RADIUS Attribute
+
+```text
+ 0                   1                   2
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-
+|     Type      |    Length     |  Value ...
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-
+```
+
+The length includes the type and length fields
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct RadiusAttribute {
+    pub attr_type: u8,
+    #[deku(update = "self.value.len() + 2")]
+    pub length: u8,
+    #[deku(
+        count = "length.checked_sub(2).ok_or_else(|| DekuError::Parse(\"overflow when parsing RADIUS attribute value\".to_string()))?"
+    )]
+    pub value: Vec<u8>,
+}
+
+/**
+RADIUS Packet
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|     Code      |  Identifier   |            Length             |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                                                               |
+|                         Authenticator                         |
+|                                                               |
+|                                                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|  Attributes ...
++-+-+-+-+-+-+-+-+-+-+-+-+-
+```
+
+The attributes run until the length, bytes following the length are padding
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Radius {
+    pub code: u8,
+    pub identifier: u8,
+    #[deku(update = "self.update_length()?")]
+    pub length: u16,
+    pub authenticator: [u8; 16],
+    #[deku(
+        reader = "Radius::read_attributes(*length, deku::rest)",
+        update = "self.update_attributes()?"
+    )]
+    pub attributes: Vec<RadiusAttribute>,
+}
+
+impl Radius {
+    fn update_length(&self) -> Result<usize, DekuError> {
+        let mut length = usize::from(RADIUS_HEADER_LEN);
+        for attribute in self.update_attributes()? {
+            length += usize::from(attribute.length);
+        }
+
+        Ok(length)
+    }
+
+    fn update_attributes(&self) -> Result<Vec<RadiusAttribute>, DekuError> {
+        let mut attributes = self.attributes.clone();
+        for attribute in attributes.iter_mut() {
+            attribute.update()?;
+        }
+
+        Ok(attributes)
+    }
+
+    fn read_attributes(
+        length: u16, // radius length header field
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, Vec<RadiusAttribute>), DekuError> {
+        let attributes_len = length
+            .checked_sub(RADIUS_HEADER_LEN)
+            .ok_or_else(|| DekuError::Parse("error: invalid radius length".to_string()))?;
+
+        // slice off the attributes from rest
+        let bits = usize::from(attributes_len) * 8;
+        if bits > rest.len() {
+            return Err(DekuError::Parse(
+                "not enough data to read radius attributes".to_string(),
+            ));
+        }
+
+        let (mut attribute_rest, rest) = rest.split_at(bits);
+
+        let mut attributes = Vec::new();
+        while !attribute_rest.is_empty() {
+            let (new_rest, attribute) =
+                RadiusAttribute::read(attribute_rest, deku::ctx::Endian::Big)?;
+            attributes.push(attribute);
+            attribute_rest = new_rest;
+        }
+
+        Ok((rest, attributes))
+    }
+
+    /// Returns the value of the first attribute of type `attr_type`
+    pub fn attribute(&self, attr_type: u8) -> Option<&[u8]> {
+        self.attributes
+            .iter()
+            .find(|v| v.attr_type == attr_type)
+            .map(|v| v.value.as_slice())
+    }
+}
+
+impl Default for Radius {
+    fn default() -> Self {
+        Radius {
+            code: RADIUS_ACCESS_REQUEST,
+            identifier: 0,
+            length: RADIUS_HEADER_LEN,
+            authenticator: [0; 16],
+            attributes: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::access_request(
+            &hex!("012a0021 0f403f9473978057bd83d5cb98f4227a 01077374657665 0406c0a80001"),
+            Radius {
+                code: RADIUS_ACCESS_REQUEST,
+                identifier: 0x2a,
+                length: 33,
+                authenticator: hex!("0f403f9473978057bd83d5cb98f4227a"),
+                attributes: vec![
+                    RadiusAttribute {
+                        attr_type: RADIUS_ATTR_USER_NAME,
+                        length: 7,
+                        value: b"steve".to_vec(),
+                    },
+                    RadiusAttribute {
+                        attr_type: RADIUS_ATTR_NAS_IP_ADDRESS,
+                        length: 6,
+                        value: vec![192, 168, 0, 1],
+                    },
+                ],
+            },
+        ),
+        case::no_attributes(&hex!("01000014 00000000000000000000000000000000"), Radius::default()),
+        #[should_panic(expected = "invalid radius length")]
+        case::length_too_small(&hex!("01000013 00000000000000000000000000000000"), Radius::default()),
+        #[should_panic(expected = "not enough data to read radius attributes")]
+        case::length_too_large(&hex!("01000016 00000000000000000000000000000000 01"), Radius::default()),
+        #[should_panic(expected = "overflow when parsing RADIUS attribute value")]
+        case::attribute_length_underflow(&hex!("01000016 00000000000000000000000000000000 0101"), Radius::default()),
+    )]
+    fn test_radius(input: &[u8], expected: Radius) {
+        let ret_read = Radius::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_radius_update_length() {
+        let mut radius = Radius {
+            attributes: vec![RadiusAttribute {
+                attr_type: RADIUS_ATTR_USER_NAME,
+                length: 0,
+                value: b"steve".to_vec(),
+            }],
+            ..Radius::default()
+        };
+
+        radius.update().unwrap();
+        assert_eq!(7, radius.attributes[0].length);
+        assert_eq!(27, radius.length);
+        assert_eq!(Some(b"steve".as_ref()), radius.attribute(RADIUS_ATTR_USER_NAME));
+    }
+}
@@ -3,13 +3,19 @@ Raw layer
 
 A Raw layer represents un-parsed data or application data such as a UDP payload
 */
-use deku::bitvec::{BitSlice, Msb0};
+use crate::layer::{Layer, LayerError};
+use crate::packet::Packet;
+use deku::bitvec::{BitSlice, BitView, Msb0};
 use deku::prelude::*;
 
 #[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
 pub struct Raw {
     #[deku(reader = "Raw::reader(deku::rest)")]
     pub data: Vec<u8>,
+    /// Bit position within the last byte of the preceding layer where `data` starts
+    ///
+    /// Only honored when writing a packet, the bits of the preceding layer from this position
+    /// are replaced by `data`
     #[deku(skip)]
     pub bit_offset: usize,
 }
@@ -21,6 +27,83 @@ impl Raw {
         let (empty, _rest) = rest.split_at(0);
         Ok((empty, ret))
     }
+
+    // Check the bit range is within `data` and fits in a u64
+    fn check_bit_range(&self, bit_offset: usize, bit_len: usize) -> Result<(), LayerError> {
+        if bit_len > 64 {
+            return Err(LayerError::Unexpected(format!(
+                "bit length {} exceeds 64 bits",
+                bit_len
+            )));
+        }
+
+        match bit_offset.checked_add(bit_len) {
+            Some(end) if end <= self.data.len() * 8 => Ok(()),
+            _ => Err(LayerError::Unexpected(format!(
+                "bit range {}+{} out of bounds",
+                bit_offset, bit_len
+            ))),
+        }
+    }
+
+    /// Read `bit_len` bits of `data` at `bit_offset` as a big-endian value
+    pub fn get_bits(&self, bit_offset: usize, bit_len: usize) -> Result<u64, LayerError> {
+        self.check_bit_range(bit_offset, bit_len)?;
+
+        let bits = &self.data.view_bits::<Msb0>()[bit_offset..bit_offset + bit_len];
+        let value = bits
+            .iter()
+            .fold(0u64, |acc, bit| (acc << 1) | u64::from(*bit));
+
+        Ok(value)
+    }
+
+    /// Write `value` to `bit_len` bits of `data` at `bit_offset`, other bits are unchanged
+    pub fn set_bits(
+        &mut self,
+        bit_offset: usize,
+        bit_len: usize,
+        value: u64,
+    ) -> Result<(), LayerError> {
+        self.check_bit_range(bit_offset, bit_len)?;
+
+        if bit_len < 64 && value >> bit_len != 0 {
+            return Err(LayerError::Unexpected(format!(
+                "value {} does not fit in {} bits",
+                value, bit_len
+            )));
+        }
+
+        let bits = &mut self.data.view_bits_mut::<Msb0>()[bit_offset..bit_offset + bit_len];
+        for i in 0..bit_len {
+            bits.set(i, (value >> (bit_len - 1 - i)) & 1 == 1);
+        }
+
+        Ok(())
+    }
+
+    /// Read `data` as an encapsulated packet, for payloads of unrecognized tunnels
+    ///
+    /// `data` is read as an IP packet if it starts with an IP version, then as an Ethernet
+    /// frame. A packet is returned if it parses cleanly: at least two layers are recognized,
+    /// the packet validates and it is written back identically.
+    pub fn try_parse_nested(&self) -> Option<Packet> {
+        let mut candidates = Vec::with_capacity(2);
+        if let Some(4) | Some(6) = self.data.first().map(|v| v >> 4) {
+            candidates.push(Packet::from_ip_bytes(&self.data));
+        }
+        candidates.push(Packet::from_bytes(&self.data));
+
+        candidates.into_iter().flatten().find(|pkt| {
+            pkt.layers
+                .iter()
+                .filter(|layer| !matches!(layer, Layer::Raw(_)))
+                .count()
+                >= 2
+                && matches!(pkt.validate(), Ok(errors) if errors.is_empty())
+                && matches!(pkt.to_bytes(), Ok(data) if data == self.data)
+        })
+    }
 }
 
 impl Default for Raw {
@@ -32,9 +115,17 @@ impl Default for Raw {
     }
 }
 
+impl std::fmt::Display for Raw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Raw len={}", self.data.len())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hex_literal::hex;
+    use rstest::*;
 
     #[test]
     fn test_raw_write() {
@@ -63,6 +154,35 @@ mod tests {
         assert_eq!((0, 0), (rest.0.len(), rest.1));
     }
 
+    #[test]
+    fn test_raw_bits() {
+        let mut layer = Raw {
+            data: vec![0xAA, 0xBB, 0x0C, 0xDD],
+            bit_offset: 0,
+        };
+
+        // 4 bit field at byte 2
+        layer.set_bits(16, 4, 0xF).unwrap();
+        assert_eq!(0xF, layer.get_bits(16, 4).unwrap());
+        assert_eq!(vec![0xAA, 0xBB, 0xFC, 0xDD], layer.data);
+
+        assert_eq!(0xAABBFCDD, layer.get_bits(0, 32).unwrap());
+        assert_eq!(0, layer.get_bits(8, 0).unwrap());
+    }
+
+    #[test]
+    fn test_raw_bits_invalid() {
+        let mut layer = Raw {
+            data: vec![0xAA, 0xBB],
+            bit_offset: 0,
+        };
+
+        assert!(layer.get_bits(12, 5).is_err());
+        assert!(layer.get_bits(0, 65).is_err());
+        assert!(layer.set_bits(0, 4, 0x10).is_err());
+        assert_eq!(vec![0xAA, 0xBB], layer.data);
+    }
+
     #[test]
     fn test_raw_default() {
         assert_eq!(
@@ -73,4 +193,52 @@ mod tests {
             Raw::default()
         )
     }
+
+    #[test]
+    fn test_raw_try_parse_nested() {
+        // IP / TCP / "hello world"
+        let ip = hex!("450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+        let raw = Raw {
+            data: ip.to_vec(),
+            bit_offset: 0,
+        };
+
+        let pkt = raw.try_parse_nested().unwrap();
+        assert_eq!(80, pkt.tcp().unwrap().dport);
+        assert_eq!(b"hello world".to_vec(), pkt.raw().unwrap().data);
+
+        // Ether / IP / TCP / "hello world"
+        let mut data = hex!("ffffffffffff0000000000000800").to_vec();
+        data.extend(ip.as_ref());
+        let raw = Raw { data, bit_offset: 0 };
+
+        let pkt = raw.try_parse_nested().unwrap();
+        assert!(pkt.ether().is_some());
+        assert_eq!(80, pkt.tcp().unwrap().dport);
+    }
+
+    #[rstest(input,
+        case::text(b"hello world"),
+        case::empty(b""),
+        // IP / TCP with an invalid ip checksum
+        case::checksum(&hex!("450000330001000040060000 7f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64")),
+    )]
+    fn test_raw_try_parse_nested_none(input: &[u8]) {
+        let raw = Raw {
+            data: input.to_vec(),
+            bit_offset: 0,
+        };
+
+        assert_eq!(None, raw.try_parse_nested());
+    }
+
+    #[test]
+    fn test_raw_display() {
+        let raw = Raw {
+            data: b"hello world!".to_vec(),
+            bit_offset: 0,
+        };
+
+        assert_eq!("Raw len=12", raw.to_string());
+    }
 }
@@ -0,0 +1,85 @@
+/*!
+Linux cooked capture layer
+*/
+use crate::layer::ether::EtherType;
+use deku::prelude::*;
+
+/**
+Linux Cooked Capture (SLL) Header
+
+Pseudo link-layer header of captures taken on the Linux "any" device (LINKTYPE_LINUX_SLL)
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|          Packet Type          |         ARPHRD Type           |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|    Link-layer Address Length  |                               |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+                               +
+|                 Link-layer Address (8 bytes)                  |
++                               +-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                               |         Protocol Type         |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct LinuxSll {
+    pub packet_type: u16,
+    pub arphrd_type: u16,
+    pub addr_len: u16,
+    pub addr: [u8; 8],
+    pub protocol: EtherType,
+}
+
+impl Default for LinuxSll {
+    fn default() -> Self {
+        LinuxSll {
+            packet_type: 0,
+            arphrd_type: 1, // ARPHRD_ETHER
+            addr_len: 6,
+            addr: [0; 8],
+            protocol: EtherType::IPv4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case(&hex!("000000010006000c29a1b2c300000800"), LinuxSll {
+            packet_type: 0,
+            arphrd_type: 1,
+            addr_len: 6,
+            addr: [0x00, 0x0c, 0x29, 0xa1, 0xb2, 0xc3, 0x00, 0x00],
+            protocol: EtherType::IPv4,
+        }),
+    )]
+    fn test_linux_sll(input: &[u8], expected: LinuxSll) {
+        let ret_read = LinuxSll::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_linux_sll_default() {
+        assert_eq!(
+            LinuxSll {
+                packet_type: 0,
+                arphrd_type: 1,
+                addr_len: 6,
+                addr: [0; 8],
+                protocol: EtherType::IPv4,
+            },
+            LinuxSll::default()
+        )
+    }
+}
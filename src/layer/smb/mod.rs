@@ -0,0 +1,206 @@
+/*!
+SMB layer
+
+SMB structures are little-endian, unlike the surrounding network headers.
+
+On TCP port 445 the SMB header is preceded by a 4 byte NetBIOS session header,
+the SMB layer can be read from the remaining bytes with
+[Layer::parse_as](../enum.Layer.html#method.parse_as).
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/// Well-known TCP port for SMB (direct hosting)
+pub const SMB_PORT: u16 = 445;
+
+/// Protocol identifier of an SMB1 header
+pub const SMB1_PROTOCOL: [u8; 4] = [0xff, b'S', b'M', b'B'];
+
+/// Protocol identifier of an SMB2 header
+pub const SMB2_PROTOCOL: [u8; 4] = [0xfe, b'S', b'M', b'B'];
+
+/// SMB1 Header, following the protocol identifier
+#[derive(Debug, PartialEq, Clone, Default, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct Smb1Header {
+    pub command: u8,
+    pub status: u32,
+    pub flags: u8,
+    pub flags2: u16,
+    pub pid_high: u16,
+    pub security_features: [u8; 8],
+    pub reserved: u16,
+    pub tid: u16,
+    pub pid_low: u16,
+    pub uid: u16,
+    pub mid: u16,
+}
+
+/// SMB2 Header, following the protocol identifier
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "endian: deku::ctx::Endian", endian = "endian")]
+pub struct Smb2Header {
+    pub structure_size: u16,
+    pub credit_charge: u16,
+    pub status: u32,
+    pub command: u16,
+    pub credits: u16,
+    pub flags: u32,
+    pub next_command: u32,
+    pub message_id: u64,
+    pub process_id: u32,
+    pub tree_id: u32,
+    pub session_id: u64,
+    pub signature: [u8; 16],
+}
+
+impl Default for Smb2Header {
+    fn default() -> Self {
+        Smb2Header {
+            structure_size: 64,
+            credit_charge: 0,
+            status: 0,
+            command: 0,
+            credits: 0,
+            flags: 0,
+            next_command: 0,
+            message_id: 0,
+            process_id: 0,
+            tree_id: 0,
+            session_id: 0,
+            signature: [0; 16],
+        }
+    }
+}
+
+/**
+SMB Header
+
+The protocol identifier selects between the SMB1 and SMB2 header
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "little")]
+pub struct Smb {
+    #[deku(reader = "Smb::read_protocol(deku::rest)")]
+    pub protocol: [u8; 4],
+    #[deku(cond = "*protocol == SMB1_PROTOCOL")]
+    pub smb1: Option<Smb1Header>,
+    #[deku(cond = "*protocol == SMB2_PROTOCOL")]
+    pub smb2: Option<Smb2Header>,
+}
+
+impl Smb {
+    fn read_protocol(
+        rest: &BitSlice<Msb0, u8>,
+    ) -> Result<(&BitSlice<Msb0, u8>, [u8; 4]), DekuError> {
+        let (rest, protocol) = <[u8; 4]>::read(rest, deku::ctx::Endian::Little)?;
+
+        if protocol != SMB1_PROTOCOL && protocol != SMB2_PROTOCOL {
+            return Err(DekuError::Parse(format!(
+                "invalid smb protocol identifier: {:02x?}",
+                protocol
+            )));
+        }
+
+        Ok((rest, protocol))
+    }
+}
+
+impl Default for Smb {
+    fn default() -> Self {
+        Smb {
+            protocol: SMB2_PROTOCOL,
+            smb1: None,
+            smb2: Some(Smb2Header::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::{Layer, LayerType};
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::smb1(
+            &hex!("ff534d4272000000001853c8000000000000000000000000fffffeff00000000"),
+            Smb {
+                protocol: SMB1_PROTOCOL,
+                smb1: Some(Smb1Header {
+                    command: 0x72,
+                    status: 0,
+                    flags: 0x18,
+                    flags2: 0xc853,
+                    pid_high: 0,
+                    security_features: [0; 8],
+                    reserved: 0,
+                    tid: 0xffff,
+                    pid_low: 0xfffe,
+                    uid: 0,
+                    mid: 0,
+                }),
+                smb2: None,
+            },
+        ),
+        case::smb2(
+            &hex!("fe534d4240000100000000000500010000000000000000000400000000000000fffe000001000000110000000000000000000000000000000000000000000000"),
+            Smb {
+                protocol: SMB2_PROTOCOL,
+                smb1: None,
+                smb2: Some(Smb2Header {
+                    structure_size: 64,
+                    credit_charge: 1,
+                    status: 0,
+                    command: 5,
+                    credits: 1,
+                    flags: 0,
+                    next_command: 0,
+                    message_id: 4,
+                    process_id: 0xfeff,
+                    tree_id: 1,
+                    session_id: 0x11,
+                    signature: [0; 16],
+                }),
+            },
+        ),
+        #[should_panic(expected = "Parse(\"invalid smb protocol identifier: [00, 53, 4d, 42]\")")]
+        case::invalid_protocol(&hex!("00534d42"), Smb::default()),
+    )]
+    fn test_smb(input: &[u8], expected: Smb) {
+        let ret_read = Smb::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_smb_parse_as() {
+        // SMB2 CREATE request header followed by the request body
+        let input = hex!("fe534d4240000100000000000500010000000000000000000400000000000000fffe000001000000110000000000000000000000000000000000000000000000 3900");
+
+        let (rest, layer) = Layer::parse_as(input.as_ref(), LayerType::Smb).unwrap();
+        assert_eq!(hex!("3900").to_vec(), rest.to_vec());
+
+        if let Layer::Smb(smb) = layer {
+            assert_eq!(5, smb.smb2.unwrap().command);
+        } else {
+            panic!("expected smb layer");
+        }
+    }
+
+    #[test]
+    fn test_smb_default() {
+        assert_eq!(
+            Smb {
+                protocol: SMB2_PROTOCOL,
+                smb1: None,
+                smb2: Some(Smb2Header::default()),
+            },
+            Smb::default()
+        )
+    }
+}
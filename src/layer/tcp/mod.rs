@@ -1,15 +1,18 @@
 /*!
 TCP layer
 */
-use super::{Layer, LayerError};
+use super::{Layer, LayerError, LayerValidate, ValidationError};
 use crate::layer::{ip::checksum, Ipv4, Ipv6};
-use deku::bitvec::{BitSlice, BitVec, Msb0};
+use deku::bitvec::{BitSlice, BitVec, BitView, Msb0};
 use deku::prelude::*;
 use std::convert::TryFrom;
 
 mod options;
 pub use options::{SAckData, TcpOption, TimestampData};
 
+/// Maximum number of blocks in a SACK option
+pub const MAX_SACK_BLOCKS: usize = 4;
+
 #[derive(Debug, Clone, PartialEq, DekuRead, DekuWrite)]
 #[deku(
     endian = "endian",
@@ -116,19 +119,109 @@ pub struct Tcp {
 
 impl Tcp {
     pub fn update_checksum_ipv4(&mut self, ipv4: &Ipv4, data: &[Layer]) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv4(ipv4, data)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum of the layer followed by `data`, without updating it
+    pub fn compute_checksum_ipv4(&self, ipv4: &Ipv4, data: &[Layer]) -> Result<u16, LayerError> {
+        let mut data_buf = Vec::new();
+        for layer in data {
+            data_buf.extend(layer.to_bytes()?)
+        }
+
+        self.compute_checksum_ipv4_payload(ipv4, &data_buf)
+    }
+
+    /// Update the checksum from the written bytes of the following layers
+    pub fn update_checksum_ipv4_payload(
+        &mut self,
+        ipv4: &Ipv4,
+        data_buf: &[u8],
+    ) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv4_payload(ipv4, data_buf)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum from the written bytes of the following layers, without updating it
+    pub fn compute_checksum_ipv4_payload(
+        &self,
+        ipv4: &Ipv4,
+        data_buf: &[u8],
+    ) -> Result<u16, LayerError> {
+        let mut tcp = self.to_bytes()?;
+        // Bytes 16, 17 are the checksum. Clear them for calculation.
+        tcp[16] = 0x00;
+        tcp[17] = 0x00;
+
+        let mut buf = Tcp::pseudo_header_ipv4(ipv4, tcp.len() + data_buf.len())?;
+        buf.reserve(tcp.len() + data_buf.len());
+
+        // Write tcp header
+        buf.extend(tcp);
+
+        // Write remaining data
+        buf.extend_from_slice(data_buf);
+
+        checksum(&buf)
+    }
+
+    pub fn update_checksum_ipv6(&mut self, ipv6: &Ipv6, data: &[Layer]) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv6(ipv6, data)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum of the layer followed by `data`, without updating it
+    pub fn compute_checksum_ipv6(&self, ipv6: &Ipv6, data: &[Layer]) -> Result<u16, LayerError> {
         let mut data_buf = Vec::new();
         for layer in data {
             data_buf.extend(layer.to_bytes()?)
         }
 
+        self.compute_checksum_ipv6_payload(ipv6, &data_buf)
+    }
+
+    /// Update the checksum from the written bytes of the following layers
+    pub fn update_checksum_ipv6_payload(
+        &mut self,
+        ipv6: &Ipv6,
+        data_buf: &[u8],
+    ) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv6_payload(ipv6, data_buf)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum from the written bytes of the following layers, without updating it
+    pub fn compute_checksum_ipv6_payload(
+        &self,
+        ipv6: &Ipv6,
+        data_buf: &[u8],
+    ) -> Result<u16, LayerError> {
         let mut tcp = self.to_bytes()?;
         // Bytes 16, 17 are the checksum. Clear them for calculation.
         tcp[16] = 0x00;
         tcp[17] = 0x00;
 
-        let mut buf = Vec::with_capacity(12 + tcp.len() + data_buf.len());
+        let mut buf = Tcp::pseudo_header_ipv6(ipv6, tcp.len() + data_buf.len())?;
+        buf.reserve(tcp.len() + data_buf.len());
+
+        // Write tcp header
+        buf.extend(tcp);
+
+        // Write remaining data
+        buf.extend_from_slice(data_buf);
+
+        checksum(&buf)
+    }
+
+    /// Write the Ipv4 pseudo header of a tcp segment of `tcp_len` bytes, header included
+    pub fn pseudo_header_ipv4(ipv4: &Ipv4, tcp_len: usize) -> Result<Vec<u8>, LayerError> {
+        let mut buf = Vec::with_capacity(12);
 
-        // Write pseudo header
         let mut ipv4_src = BitVec::<Msb0, u8>::new();
         ipv4.src.write(&mut ipv4_src, deku::ctx::Endian::Big)?;
         buf.extend(ipv4_src.into_vec());
@@ -144,37 +237,18 @@ impl Tcp {
             .write(&mut ipv4_protocol, deku::ctx::Endian::Big)?;
         buf.extend(ipv4_protocol.into_vec());
 
-        let len_sum = (u16::try_from(data_buf.len())?.checked_add(u16::try_from(tcp.len())?))
-            .ok_or_else(|| LayerError::IntError("overflow occurred".to_string()))?;
+        let len_sum = u16::try_from(tcp_len)?;
         let mut len_sum_res = BitVec::<Msb0, u8>::new();
         len_sum.write(&mut len_sum_res, deku::ctx::Endian::Big)?;
         buf.extend(len_sum_res.into_vec());
 
-        // Write tcp header
-        buf.extend(tcp);
-
-        // Write remaining data
-        buf.extend(data_buf);
-
-        self.checksum = checksum(&buf)?;
-
-        Ok(())
+        Ok(buf)
     }
 
-    pub fn update_checksum_ipv6(&mut self, ipv6: &Ipv6, data: &[Layer]) -> Result<(), LayerError> {
-        let mut data_buf = Vec::new();
-        for layer in data {
-            data_buf.extend(layer.to_bytes()?)
-        }
+    /// Write the Ipv6 pseudo header of a tcp segment of `tcp_len` bytes, header included
+    pub fn pseudo_header_ipv6(ipv6: &Ipv6, tcp_len: usize) -> Result<Vec<u8>, LayerError> {
+        let mut buf = Vec::with_capacity(40);
 
-        let mut tcp = self.to_bytes()?;
-        // Bytes 16, 17 are the checksum. Clear them for calculation.
-        tcp[16] = 0x00;
-        tcp[17] = 0x00;
-
-        let mut buf = Vec::with_capacity(40 + tcp.len() + data_buf.len());
-
-        // Write pseudo header
         let mut ipv6_src = BitVec::<Msb0, u8>::new();
         ipv6.src.write(&mut ipv6_src, deku::ctx::Endian::Big)?;
         buf.extend(ipv6_src.into_vec());
@@ -183,8 +257,7 @@ impl Tcp {
         ipv6.dst.write(&mut ipv6_dst, deku::ctx::Endian::Big)?;
         buf.extend(ipv6_dst.into_vec());
 
-        let len_sum = (u16::try_from(data_buf.len())?.checked_add(u16::try_from(tcp.len())?))
-            .ok_or_else(|| LayerError::IntError("overflow occurred".to_string()))?;
+        let len_sum = u16::try_from(tcp_len)?;
         let mut len_sum_res = BitVec::<Msb0, u8>::new();
         len_sum.write(&mut len_sum_res, deku::ctx::Endian::Big)?;
         buf.extend(len_sum_res.into_vec());
@@ -198,15 +271,7 @@ impl Tcp {
             .write(&mut ipv6_next_header, deku::ctx::Endian::Big)?;
         buf.extend(ipv6_next_header.into_vec());
 
-        // Write tcp header
-        buf.extend(tcp);
-
-        // Write remaining data
-        buf.extend(data_buf);
-
-        self.checksum = checksum(&buf)?;
-
-        Ok(())
+        Ok(buf)
     }
 
     fn read_options(
@@ -248,6 +313,224 @@ impl Tcp {
     }
 }
 
+impl Tcp {
+    /// Returns the header length in bytes, including options
+    pub fn header_len(&self) -> usize {
+        usize::from(self.offset) * 4
+    }
+
+    /// Read a Tcp header, reading as many complete options as fit when the offset exceeds the
+    /// available data
+    ///
+    /// Returns the remaining bytes, the header and a note describing the truncated options, if
+    /// any. The offset is kept as read, other parse errors are returned as-is.
+    pub fn from_bytes_lenient(input: &[u8]) -> Result<(&[u8], Tcp, Option<String>), LayerError> {
+        let err = match Tcp::from_bytes((input, 0)) {
+            Ok(((rest, _), tcp)) => return Ok((rest, tcp, None)),
+            Err(e) => e,
+        };
+
+        let offset = match input.get(12) {
+            Some(v) => v >> 4,
+            None => return Err(err.into()),
+        };
+        let options_len = usize::from(offset).saturating_sub(5) * 4;
+        if options_len == 0 || 20 + options_len <= input.len() {
+            return Err(err.into());
+        }
+
+        // Read the fixed header as if it had no options
+        let mut header = input[..20].to_vec();
+        header[12] = (header[12] & 0x0f) | (5 << 4);
+        let (_rest, mut tcp) = Tcp::from_bytes((header.as_ref(), 0))?;
+        tcp.offset = offset;
+
+        let mut option_rest = input[20..].view_bits::<Msb0>();
+        while let Ok((option_rest_new, tcp_option)) =
+            TcpOption::read(option_rest, deku::ctx::Endian::Big)
+        {
+            tcp.options.push(tcp_option);
+            option_rest = option_rest_new;
+        }
+
+        let rest = &input[input.len() - option_rest.len() / 8..];
+        let note = format!(
+            "tcp offset {} exceeds the available data, read {} of {} option bytes",
+            offset,
+            input.len() - 20 - rest.len(),
+            options_len
+        );
+
+        Ok((rest, tcp, Some(note)))
+    }
+
+    /// Returns true if the offset exceeds the written length of the header, as for a header
+    /// cut off in its options read by `Tcp::from_bytes_lenient`
+    pub fn options_truncated(&self) -> Result<bool, LayerError> {
+        Ok(self.to_bytes()?.len() < usize::from(self.offset) * 4)
+    }
+
+    /// Returns an iterator reading the options of the written header `raw_header` one at a time
+    ///
+    /// Options are read lazily, without reading the whole header. The options end at the
+    /// offset or at the end of `raw_header`, whichever comes first. The iterator ends after
+    /// the first error.
+    pub fn options_iter(
+        raw_header: &[u8],
+    ) -> impl Iterator<Item = Result<TcpOption, LayerError>> + '_ {
+        let options = match raw_header.get(12) {
+            Some(v) => {
+                let end = (usize::from(v >> 4) * 4).min(raw_header.len());
+                Ok(raw_header.get(20..end).unwrap_or(&[]))
+            }
+            None => Err(LayerError::Parse("not enough data to read tcp header".to_string())),
+        };
+
+        let mut state = Some(options.map(|v| v.view_bits::<Msb0>()));
+        std::iter::from_fn(move || match state.take()? {
+            Ok(rest) if rest.is_empty() => None,
+            Ok(rest) => match TcpOption::read(rest, deku::ctx::Endian::Big) {
+                Ok((rest, option)) => {
+                    state = Some(Ok(rest));
+                    Some(Ok(option))
+                }
+                Err(e) => Some(Err(e.into())),
+            },
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Returns the cookie of the TCP Fast Open option, if present
+    ///
+    /// An empty cookie is a Fast Open cookie request
+    pub fn tfo_cookie(&self) -> Option<&[u8]> {
+        self.options.iter().find_map(|v| match v {
+            TcpOption::FastOpen { cookie, .. } => Some(cookie.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Add a block to the SACK option, adding the option if there is none
+    ///
+    /// A new option is preceded by two NOPs to keep the options 4-byte aligned. The option
+    /// length and the offset are updated, a SACK option holds at most 4 blocks. The options
+    /// are left unchanged on error.
+    pub fn add_sack_block(&mut self, begin: u32, end: u32) -> Result<(), LayerError> {
+        let options = self.options.clone();
+        let sack = self.options.iter_mut().find_map(|v| match v {
+            TcpOption::SAck { value, .. } => Some(value),
+            _ => None,
+        });
+
+        match sack {
+            Some(value) if value.len() >= MAX_SACK_BLOCKS => {
+                return Err(LayerError::Unexpected(format!(
+                    "sack option already holds {} blocks",
+                    MAX_SACK_BLOCKS
+                )));
+            }
+            Some(value) => value.push(SAckData { begin, end }),
+            None => {
+                // insert before the end of option list, if any
+                let index = self
+                    .options
+                    .iter()
+                    .position(|v| *v == TcpOption::EOL)
+                    .unwrap_or_else(|| self.options.len());
+
+                self.options.splice(
+                    index..index,
+                    vec![
+                        TcpOption::NOP,
+                        TcpOption::NOP,
+                        TcpOption::SAck {
+                            length: 0,
+                            value: vec![SAckData { begin, end }],
+                        },
+                    ],
+                );
+            }
+        }
+
+        self.update_options_offset().map_err(|e| {
+            self.options = options;
+            e
+        })
+    }
+
+    /// Remove the SACK option and the NOPs preceding it
+    ///
+    /// The offset is updated
+    pub fn clear_sack_blocks(&mut self) -> Result<(), LayerError> {
+        while let Some(index) = self
+            .options
+            .iter()
+            .position(|v| matches!(v, TcpOption::SAck { .. }))
+        {
+            let start = self.options[..index]
+                .iter()
+                .rev()
+                .take(2)
+                .take_while(|v| **v == TcpOption::NOP)
+                .count();
+
+            self.options.drain(index - start..=index);
+        }
+
+        self.update_options_offset()
+    }
+
+    // Update the option lengths and the offset from the length of the options
+    //
+    // The options are padded with EOL to a multiple of 4 bytes, replacing the previous padding
+    fn update_options_offset(&mut self) -> Result<(), LayerError> {
+        while self.options.last() == Some(&TcpOption::EOL) {
+            self.options.pop();
+        }
+
+        let mut options_len = 0;
+        for option in self.options.iter_mut() {
+            option.update()?;
+            options_len += option.to_bytes()?.len();
+        }
+
+        let padding = (4 - options_len % 4) % 4;
+        let offset = 5 + (options_len + padding) / 4;
+        if offset > 15 {
+            return Err(LayerError::Unexpected(format!(
+                "tcp options of {} bytes exceed the maximum offset",
+                options_len
+            )));
+        }
+
+        self.options
+            .resize(self.options.len() + padding, TcpOption::EOL);
+        self.offset = u8::try_from(offset)?;
+
+        Ok(())
+    }
+
+    /// Verify the reserved bits of the header are zero
+    ///
+    /// The nonce bit was reclaimed as reserved by RFC 8311, `include_nonce` also requires it
+    /// to be zero.
+    pub fn validate_reserved(&self, include_nonce: bool) -> Vec<ValidationError> {
+        let mut ret = Vec::new();
+
+        if self.flags.reserved != 0 || (include_nonce && self.flags.nonce != 0) {
+            ret.push(ValidationError::Reserved)
+        }
+
+        ret
+    }
+}
+
+impl LayerValidate for Tcp {
+    fn validate(&self) -> Result<Vec<ValidationError>, LayerError> {
+        Ok(self.validate_reserved(false))
+    }
+}
+
 impl Default for Tcp {
     fn default() -> Self {
         Tcp {
@@ -265,6 +548,16 @@ impl Default for Tcp {
     }
 }
 
+impl std::fmt::Display for Tcp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TCP {} > {} flags={} seq={} ack={} win={}",
+            self.sport, self.dport, self.flags, self.seq, self.ack, self.window
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -356,6 +649,67 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_tcp_header_len() {
+        let tcp = Tcp::try_from(hex!("0d2c005038affe14114c618c501825bca9580000").as_ref()).unwrap();
+        assert_eq!(20, tcp.header_len());
+        assert_eq!(tcp.to_bytes().unwrap().len(), tcp.header_len());
+    }
+
+    #[rstest(input, expected_options, expected_rest, expected_note,
+        case::valid(
+            &hex!("0014005000000000000000006002200000000000020405b4"),
+            vec![TcpOption::MSS { length: 4, value: 0x05b4 }],
+            &[],
+            None,
+        ),
+        case::offset_too_large(
+            &hex!("0014005000000000000000008002200000000000020405b4010303"),
+            vec![TcpOption::MSS { length: 4, value: 0x05b4 }, TcpOption::NOP],
+            &hex!("0303"),
+            Some("tcp offset 8 exceeds the available data, read 5 of 12 option bytes"),
+        ),
+        #[should_panic(expected = "invalid tcp offset")]
+        case::offset_too_small(
+            &hex!("0014005000000000000000004002200000000000"),
+            vec![],
+            &[],
+            None,
+        ),
+    )]
+    fn test_tcp_from_bytes_lenient(
+        input: &[u8],
+        expected_options: Vec<TcpOption>,
+        expected_rest: &[u8],
+        expected_note: Option<&str>,
+    ) {
+        let (rest, tcp, note) = Tcp::from_bytes_lenient(input).unwrap();
+
+        assert_eq!(input[12] >> 4, tcp.offset);
+        assert_eq!(expected_options, tcp.options);
+        assert_eq!(expected_rest, rest);
+        assert_eq!(expected_note, note.as_deref());
+        assert_eq!(expected_note.is_some(), tcp.options_truncated().unwrap());
+    }
+
+    #[test]
+    fn test_tcp_tfo_cookie() {
+        // SYN with MSS and a Fast Open option carrying an 8 byte cookie
+        let tcp = Tcp::try_from(
+            hex!("c213005086eebc64000000009002000000000000020405b4220a01020304050607080101").as_ref(),
+        )
+        .unwrap();
+
+        assert_eq!(Some(hex!("0102030405060708").as_ref()), tcp.tfo_cookie());
+        assert_eq!(
+            TcpOption::FastOpen {
+                length: 10,
+                cookie: hex!("0102030405060708").to_vec(),
+            },
+            tcp.options[1]
+        );
+    }
+
     #[test]
     fn test_tcp_checksum_update_v4() {
         let expected_checksum = 0xa958;
@@ -368,6 +722,11 @@ mod tests {
 
         let raw = Raw::try_from(hex!("474554202f646f776e6c6f61642e68746d6c20485454502f312e310d0a486f73743a207777772e657468657265616c2e636f6d0d0a557365722d4167656e743a204d6f7a696c6c612f352e30202857696e646f77733b20553b2057696e646f7773204e5420352e313b20656e2d55533b2072763a312e3629204765636b6f2f32303034303131330d0a4163636570743a20746578742f786d6c2c6170706c69636174696f6e2f786d6c2c6170706c69636174696f6e2f7868746d6c2b786d6c2c746578742f68746d6c3b713d302e392c746578742f706c61696e3b713d302e382c696d6167652f706e672c696d6167652f6a7065672c696d6167652f6769663b713d302e322c2a2f2a3b713d302e310d0a4163636570742d4c616e67756167653a20656e2d75732c656e3b713d302e350d0a4163636570742d456e636f64696e673a20677a69702c6465666c6174650d0a4163636570742d436861727365743a2049534f2d383835392d312c7574662d383b713d302e372c2a3b713d302e370d0a4b6565702d416c6976653a203330300d0a436f6e6e656374696f6e3a206b6565702d616c6976650d0a526566657265723a20687474703a2f2f7777772e657468657265616c2e636f6d2f646576656c6f706d656e742e68746d6c0d0a0d0a").as_ref()).unwrap();
 
+        // computing the checksum leaves the layer unchanged
+        let computed = tcp.compute_checksum_ipv4(&ipv4, &[Layer::Raw(raw.clone())]).unwrap();
+        assert_eq!(expected_checksum, computed);
+        assert_eq!(0xaaaa, tcp.checksum);
+
         tcp.update_checksum_ipv4(&ipv4, &[Layer::Raw(raw)]).unwrap();
 
         assert_eq!(expected_checksum, tcp.checksum);
@@ -390,8 +749,155 @@ mod tests {
 
         let raw = Raw::try_from(hex!("5553455220616e6f6e796d6f75730d0a").as_ref()).unwrap();
 
+        // computing the checksum leaves the layer unchanged
+        let computed = tcp.compute_checksum_ipv6(&ipv6, &[Layer::Raw(raw.clone())]).unwrap();
+        assert_eq!(expected_checksum, computed);
+        assert_eq!(0xaaaa, tcp.checksum);
+
         tcp.update_checksum_ipv6(&ipv6, &[Layer::Raw(raw)]).unwrap();
 
         assert_eq!(expected_checksum, tcp.checksum);
     }
+
+    #[rstest(input, include_nonce, expected,
+        case::valid(&hex!("0d2c005038affe14114c618c501825bca9580000"), false, vec![]),
+        case::reserved(&hex!("0d2c005038affe14114c618c5e1825bca9580000"), false, vec![ValidationError::Reserved]),
+        case::nonce(&hex!("0d2c005038affe14114c618c511825bca9580000"), false, vec![]),
+        case::nonce_included(&hex!("0d2c005038affe14114c618c511825bca9580000"), true, vec![ValidationError::Reserved]),
+    )]
+    fn test_tcp_reserved_validate(
+        input: &[u8],
+        include_nonce: bool,
+        expected: Vec<ValidationError>,
+    ) {
+        let tcp = Tcp::try_from(input).unwrap();
+
+        assert_eq!(expected, tcp.validate_reserved(include_nonce));
+        if !include_nonce {
+            assert_eq!(expected, tcp.validate().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_tcp_sack_blocks() {
+        let mut tcp =
+            Tcp::try_from(hex!("0d2c005038affe14114c618c501825bca9580000").as_ref()).unwrap();
+
+        tcp.add_sack_block(100, 200).unwrap();
+        tcp.add_sack_block(300, 400).unwrap();
+
+        assert_eq!(
+            vec![
+                TcpOption::NOP,
+                TcpOption::NOP,
+                TcpOption::SAck {
+                    length: 18,
+                    value: vec![
+                        SAckData { begin: 100, end: 200 },
+                        SAckData { begin: 300, end: 400 },
+                    ]
+                },
+            ],
+            tcp.options
+        );
+        assert_eq!(10, tcp.offset);
+
+        let bytes = tcp.to_bytes().unwrap();
+        assert_eq!(tcp.header_len(), bytes.len());
+        assert_eq!(tcp, Tcp::try_from(bytes.as_ref()).unwrap());
+
+        tcp.add_sack_block(500, 600).unwrap();
+        tcp.add_sack_block(700, 800).unwrap();
+        assert_eq!(
+            Err(LayerError::Unexpected("sack option already holds 4 blocks".to_string())),
+            tcp.add_sack_block(900, 1000)
+        );
+
+        tcp.clear_sack_blocks().unwrap();
+        assert_eq!(Vec::<TcpOption>::new(), tcp.options);
+        assert_eq!(5, tcp.offset);
+    }
+
+    #[test]
+    fn test_tcp_sack_blocks_padding() {
+        // MSS followed by a SACK option without the aligning NOPs
+        let mut tcp = Tcp::try_from(
+            hex!("0d2c005038affe14114c618c901825bca9580000 020405b4 050a00000064000000c8 0000")
+                .as_ref(),
+        )
+        .unwrap();
+        assert_eq!(
+            vec![TcpOption::EOL, TcpOption::EOL],
+            tcp.options[2..].to_vec()
+        );
+
+        tcp.add_sack_block(300, 400).unwrap();
+
+        // the previous padding is replaced
+        assert_eq!(4, tcp.options.len());
+        assert_eq!(TcpOption::EOL, tcp.options[2]);
+        assert_eq!(TcpOption::EOL, tcp.options[3]);
+        assert_eq!(11, tcp.offset);
+
+        let bytes = tcp.to_bytes().unwrap();
+        assert_eq!(tcp.header_len(), bytes.len());
+        assert_eq!(tcp, Tcp::try_from(bytes.as_ref()).unwrap());
+    }
+
+    #[test]
+    fn test_tcp_sack_blocks_too_long() {
+        // 36 bytes of NOPs, a new SACK option does not fit in the 40 bytes of options
+        let mut tcp = Tcp::try_from(
+            hex!("0d2c005038affe14114c618ce01825bca9580000 010101010101010101010101010101010101010101010101010101010101010101010101").as_ref(),
+        )
+        .unwrap();
+        let expected = tcp.clone();
+
+        assert_eq!(
+            Err(LayerError::Unexpected(
+                "tcp options of 48 bytes exceed the maximum offset".to_string()
+            )),
+            tcp.add_sack_block(100, 200)
+        );
+        assert_eq!(expected, tcp);
+    }
+
+    #[test]
+    fn test_tcp_options_iter() {
+        // MSS followed by a SACK option with an invalid length
+        let input = hex!("0d2c005038affe14114c618c700225bca9580000 020405b4 0500e4d6");
+
+        let mss = Tcp::options_iter(&input).find_map(|v| match v {
+            Ok(TcpOption::MSS { value, .. }) => Some(value),
+            _ => None,
+        });
+        assert_eq!(Some(1460), mss);
+
+        let options: Vec<_> = Tcp::options_iter(&input).collect();
+        assert_eq!(2, options.len());
+        assert_eq!(Ok(TcpOption::MSS { length: 4, value: 1460 }), options[0]);
+        assert!(options[1].is_err());
+
+        assert_eq!(0, Tcp::options_iter(&input[..20]).count());
+        assert!(Tcp::options_iter(&input[..10]).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_tcp_display() {
+        let tcp = Tcp {
+            sport: 20,
+            dport: 80,
+            seq: 1,
+            ack: 2,
+            window: 8192,
+            flags: TcpFlags {
+                syn: 1,
+                ack: 1,
+                ..TcpFlags::default()
+            },
+            ..Tcp::default()
+        };
+
+        assert_eq!("TCP 20 > 80 flags=SA seq=1 ack=2 win=8192", tcp.to_string());
+    }
 }
@@ -48,6 +48,19 @@ pub enum TcpOption {
     },
     #[deku(id = "0x08")]
     Timestamp { length: u8, value: TimestampData },
+    #[deku(id = "0x22")]
+    FastOpen {
+        #[deku(update = "{use std::convert::TryFrom; u8::try_from(
+            cookie.len()
+            .checked_add(2)
+            .ok_or_else(|| DekuError::Parse(\"overflow when parsing FastOpen length\".to_string()))?
+        )?}")]
+        length: u8,
+        #[deku(
+            count = "length.checked_sub(2).ok_or_else(|| DekuError::Parse(\"overflow when parsing FastOpen cookie\".to_string()))?"
+        )]
+        cookie: Vec<u8>,
+    },
 }
 
 #[cfg(test)]
@@ -73,15 +86,28 @@ mod tests {
                 end: 1081292766
             }
         }),
+        case(&hex!("220a0102030405060708"), TcpOption::FastOpen {
+            length: 10,
+            cookie: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+        }),
+        case::fast_open_request(&hex!("2202"), TcpOption::FastOpen {
+            length: 2,
+            cookie: vec![]
+        }),
 
 
         // Errors
         #[should_panic(expected = "Parse(\"overflow when parsing SAckData vec\")")]
         case::sack_length_underflow(&hex!("0500e4d6c0f0e4d6cba0"), TcpOption::EOL),
+        #[should_panic(expected = "Parse(\"overflow when parsing FastOpen cookie\")")]
+        case::fast_open_length_underflow(&hex!("2201"), TcpOption::EOL),
     )]
     fn test_tcp_option(input: &[u8], expected: TcpOption) {
         let (_rest, option) = TcpOption::from_bytes((input, 0)).unwrap();
         assert_eq!(expected, option);
+
+        let ret_write = option.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
     }
 
     #[rstest(sack_len,
@@ -32,11 +32,38 @@ pub struct Udp {
 
 impl Udp {
     pub fn update_checksum_ipv4(&mut self, ipv4: &Ipv4, data: &[Layer]) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv4(ipv4, data)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum of the layer followed by `data`, without updating it
+    pub fn compute_checksum_ipv4(&self, ipv4: &Ipv4, data: &[Layer]) -> Result<u16, LayerError> {
         let mut data_buf = Vec::new();
         for layer in data {
             data_buf.extend(layer.to_bytes()?)
         }
 
+        self.compute_checksum_ipv4_payload(ipv4, &data_buf)
+    }
+
+    /// Update the checksum from the written bytes of the following layers
+    pub fn update_checksum_ipv4_payload(
+        &mut self,
+        ipv4: &Ipv4,
+        data_buf: &[u8],
+    ) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv4_payload(ipv4, data_buf)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum from the written bytes of the following layers, without updating it
+    pub fn compute_checksum_ipv4_payload(
+        &self,
+        ipv4: &Ipv4,
+        data_buf: &[u8],
+    ) -> Result<u16, LayerError> {
         let mut udp = self.to_bytes()?;
         // Bytes 6, 7 are the checksum. Clear them for calculation.
         udp[6] = 0x00;
@@ -70,19 +97,44 @@ impl Udp {
         buf.extend(udp);
 
         // Write remaining data
-        buf.extend(data_buf);
+        buf.extend_from_slice(data_buf);
+
+        checksum(&buf)
+    }
 
-        self.checksum = checksum(&buf)?;
+    pub fn update_checksum_ipv6(&mut self, ipv6: &Ipv6, data: &[Layer]) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv6(ipv6, data)?;
 
         Ok(())
     }
 
-    pub fn update_checksum_ipv6(&mut self, ipv6: &Ipv6, data: &[Layer]) -> Result<(), LayerError> {
+    /// Returns the checksum of the layer followed by `data`, without updating it
+    pub fn compute_checksum_ipv6(&self, ipv6: &Ipv6, data: &[Layer]) -> Result<u16, LayerError> {
         let mut data_buf = Vec::new();
         for layer in data {
             data_buf.extend(layer.to_bytes()?)
         }
 
+        self.compute_checksum_ipv6_payload(ipv6, &data_buf)
+    }
+
+    /// Update the checksum from the written bytes of the following layers
+    pub fn update_checksum_ipv6_payload(
+        &mut self,
+        ipv6: &Ipv6,
+        data_buf: &[u8],
+    ) -> Result<(), LayerError> {
+        self.checksum = self.compute_checksum_ipv6_payload(ipv6, data_buf)?;
+
+        Ok(())
+    }
+
+    /// Returns the checksum from the written bytes of the following layers, without updating it
+    pub fn compute_checksum_ipv6_payload(
+        &self,
+        ipv6: &Ipv6,
+        data_buf: &[u8],
+    ) -> Result<u16, LayerError> {
         let mut udp = self.to_bytes()?;
         // Bytes 6, 7 are the checksum. Clear them for calculation.
         udp[6] = 0x00;
@@ -118,20 +170,23 @@ impl Udp {
         buf.extend(udp);
 
         // Write remaining data
-        buf.extend(data_buf);
+        buf.extend_from_slice(data_buf);
 
-        self.checksum = checksum(&buf)?;
-
-        Ok(())
+        checksum(&buf)
     }
 
     pub fn update_length(&mut self, data: &[Layer]) -> Result<(), LayerError> {
-        let header = self.to_bytes()?;
         let mut data_buf = Vec::new();
         for layer in data {
             data_buf.extend(layer.to_bytes()?)
         }
 
+        self.update_length_payload(&data_buf)
+    }
+
+    /// Update the length from the written bytes of the following layers
+    pub fn update_length_payload(&mut self, data_buf: &[u8]) -> Result<(), LayerError> {
+        let header = self.to_bytes()?;
         self.length = u16::try_from(header.len() + data_buf.len())?;
 
         Ok(())
@@ -149,6 +204,12 @@ impl Default for Udp {
     }
 }
 
+impl std::fmt::Display for Udp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UDP {} > {} len={}", self.sport, self.dport, self.length)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +264,11 @@ mod tests {
         )
         .unwrap();
 
+        // computing the checksum leaves the layer unchanged
+        let computed = udp.compute_checksum_ipv4(&ipv4, &[Layer::Raw(raw.clone())]).unwrap();
+        assert_eq!(expected_checksum, computed);
+        assert_eq!(0xaaaa, udp.checksum);
+
         udp.update_checksum_ipv4(&ipv4, &[Layer::Raw(raw)]).unwrap();
 
         assert_eq!(expected_checksum, udp.checksum);
@@ -224,8 +290,25 @@ mod tests {
 
         let raw = Raw::try_from(hex!("303502010104146e35724144316967333134497166696f59425777a11a020455e8831e020100020100300c300a06062b060102010b0500").as_ref()).unwrap();
 
+        // computing the checksum leaves the layer unchanged
+        let computed = udp.compute_checksum_ipv6(&ipv6, &[Layer::Raw(raw.clone())]).unwrap();
+        assert_eq!(expected_checksum, computed);
+        assert_eq!(0xaaaa, udp.checksum);
+
         udp.update_checksum_ipv6(&ipv6, &[Layer::Raw(raw)]).unwrap();
 
         assert_eq!(expected_checksum, udp.checksum);
     }
+
+    #[test]
+    fn test_udp_display() {
+        let udp = Udp {
+            sport: 1337,
+            dport: 53,
+            length: 20,
+            ..Udp::default()
+        };
+
+        assert_eq!("UDP 1337 > 53 len=20", udp.to_string());
+    }
 }
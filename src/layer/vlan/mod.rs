@@ -0,0 +1,73 @@
+/*!
+VLAN layer
+*/
+use crate::layer::ether::EtherType;
+use deku::prelude::*;
+
+/**
+IEEE 802.1Q VLAN Tag
+
+The Tag Protocol Identifier is the `ether_type` of the preceding layer
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+| PCP |D|          VID          |           EtherType           |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, Default, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Vlan {
+    #[deku(bits = "3")]
+    pub pcp: u8, // Priority Code Point
+    #[deku(bits = "1")]
+    pub dei: u8, // Drop Eligible Indicator
+    #[deku(bits = "12")]
+    pub vid: u16, // VLAN Identifier
+    pub ether_type: EtherType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case(&hex!("a0640800"), Vlan {
+            pcp: 5,
+            dei: 0,
+            vid: 100,
+            ether_type: EtherType::IPv4,
+        }),
+        case(&hex!("1fff8100"), Vlan {
+            pcp: 0,
+            dei: 1,
+            vid: 4095,
+            ether_type: EtherType::VLAN,
+        }),
+    )]
+    fn test_vlan(input: &[u8], expected: Vlan) {
+        let ret_read = Vlan::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_vlan_default() {
+        assert_eq!(
+            Vlan {
+                pcp: 0,
+                dei: 0,
+                vid: 0,
+                ether_type: EtherType::IPv4,
+            },
+            Vlan::default()
+        )
+    }
+}
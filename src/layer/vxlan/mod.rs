@@ -0,0 +1,82 @@
+/*!
+VXLAN layer
+*/
+use deku::prelude::*;
+
+/// Well-known UDP destination port for VXLAN
+pub const VXLAN_PORT: u16 = 4789;
+
+/**
+VXLAN Header
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|R|R|R|R|I|R|R|R|            Reserved                           |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                VXLAN Network Identifier (VNI) |   Reserved    |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(endian = "big")]
+pub struct Vxlan {
+    pub flags: u8,
+    #[deku(bits = "24")]
+    pub reserved1: u32,
+    #[deku(bits = "24")]
+    pub vni: u32, // VXLAN Network Identifier
+    pub reserved2: u8,
+}
+
+impl Default for Vxlan {
+    fn default() -> Self {
+        Vxlan {
+            flags: 0x08, // VNI is valid
+            reserved1: 0,
+            vni: 0,
+            reserved2: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case(
+            &hex!("0800000000002a00"),
+            Vxlan {
+                flags: 0x08,
+                reserved1: 0,
+                vni: 42,
+                reserved2: 0,
+            },
+        ),
+    )]
+    fn test_vxlan(input: &[u8], expected: Vxlan) {
+        let ret_read = Vxlan::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_vxlan_default() {
+        assert_eq!(
+            Vxlan {
+                flags: 0x08,
+                reserved1: 0,
+                vni: 0,
+                reserved2: 0,
+            },
+            Vxlan::default()
+        )
+    }
+}
@@ -0,0 +1,235 @@
+/*!
+WireGuard layer
+*/
+use deku::bitvec::{BitSlice, Msb0};
+use deku::prelude::*;
+
+/// Default UDP port for WireGuard
+///
+/// WireGuard peers commonly listen on other ports, the layer is not dispatched on it and is
+/// read with `Layer::parse_as`
+pub const WIREGUARD_PORT: u16 = 51820;
+
+// read all the rest of the message
+fn read_data(rest: &BitSlice<Msb0, u8>) -> Result<(&BitSlice<Msb0, u8>, Vec<u8>), DekuError> {
+    let ret = rest.as_raw_slice().to_vec();
+    let (empty, _rest) = rest.split_at(0);
+    Ok((empty, ret))
+}
+
+/// WireGuard message body, determined by the message type
+///
+/// The encrypted fields are kept opaque
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+#[deku(ctx = "message_type: u8", id = "message_type", endian = "little")]
+pub enum WireGuardMessage {
+    #[deku(id = "1")]
+    Initiation {
+        sender_index: u32,
+        ephemeral: [u8; 32],
+        #[deku(count = "48")]
+        encrypted_static: Vec<u8>,
+        encrypted_timestamp: [u8; 28],
+        mac1: [u8; 16],
+        mac2: [u8; 16],
+    },
+    #[deku(id = "2")]
+    Response {
+        sender_index: u32,
+        receiver_index: u32,
+        ephemeral: [u8; 32],
+        encrypted_nothing: [u8; 16],
+        mac1: [u8; 16],
+        mac2: [u8; 16],
+    },
+    #[deku(id = "3")]
+    CookieReply {
+        receiver_index: u32,
+        nonce: [u8; 24],
+        encrypted_cookie: [u8; 32],
+    },
+    #[deku(id = "4")]
+    Transport {
+        receiver_index: u32,
+        counter: u64,
+        #[deku(reader = "read_data(deku::rest)")]
+        data: Vec<u8>,
+    },
+    #[deku(id_pat = "_")]
+    Unknown {
+        #[deku(reader = "read_data(deku::rest)")]
+        data: Vec<u8>,
+    },
+}
+
+impl WireGuardMessage {
+    // Message type of the variant, None for an unknown message
+    fn message_type(&self) -> Option<u8> {
+        match self {
+            WireGuardMessage::Initiation { .. } => Some(1),
+            WireGuardMessage::Response { .. } => Some(2),
+            WireGuardMessage::CookieReply { .. } => Some(3),
+            WireGuardMessage::Transport { .. } => Some(4),
+            WireGuardMessage::Unknown { .. } => None,
+        }
+    }
+}
+
+/**
+WireGuard Message
+
+```text
+ 0                   1                   2                   3
+ 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5 6 7 8 9 0 1
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|     Type      |                   Reserved                    |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+|                    Message (little endian)                    |
++-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+-+
+```
+*/
+#[derive(Debug, PartialEq, Clone, DekuRead, DekuWrite)]
+pub struct WireGuard {
+    #[deku(update = "self.update_message_type()")]
+    pub message_type: u8,
+    pub reserved: [u8; 3],
+    #[deku(ctx = "*message_type")]
+    pub message: WireGuardMessage,
+}
+
+impl WireGuard {
+    fn update_message_type(&self) -> u8 {
+        self.message.message_type().unwrap_or(self.message_type)
+    }
+
+    /// Returns the receiver index of the message, the sender index of the peer
+    pub fn receiver_index(&self) -> Option<u32> {
+        match self.message {
+            WireGuardMessage::Response { receiver_index, .. }
+            | WireGuardMessage::CookieReply { receiver_index, .. }
+            | WireGuardMessage::Transport { receiver_index, .. } => Some(receiver_index),
+            _ => None,
+        }
+    }
+}
+
+impl Default for WireGuard {
+    fn default() -> Self {
+        WireGuard {
+            message_type: 4,
+            reserved: [0; 3],
+            message: WireGuardMessage::Transport {
+                receiver_index: 0,
+                counter: 0,
+                data: vec![],
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+    use std::convert::TryFrom;
+
+    #[rstest(input, expected,
+        case::initiation(
+            &hex!("01000000010000001111111111111111111111111111111111111111111111111111111111111111222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222222333333333333333333333333333333333333333333333333333333334444444444444444444444444444444400000000000000000000000000000000"),
+            WireGuard {
+                message_type: 1,
+                reserved: [0; 3],
+                message: WireGuardMessage::Initiation {
+                    sender_index: 1,
+                    ephemeral: [0x11; 32],
+                    encrypted_static: vec![0x22; 48],
+                    encrypted_timestamp: [0x33; 28],
+                    mac1: [0x44; 16],
+                    mac2: [0; 16],
+                },
+            },
+        ),
+        case::cookie_reply(
+            &hex!("03000000010000005555555555555555555555555555555555555555555555556666666666666666666666666666666666666666666666666666666666666666"),
+            WireGuard {
+                message_type: 3,
+                reserved: [0; 3],
+                message: WireGuardMessage::CookieReply {
+                    receiver_index: 1,
+                    nonce: [0x55; 24],
+                    encrypted_cookie: [0x66; 32],
+                },
+            },
+        ),
+        case::transport(
+            &hex!("04000000 78563412 0100000000000000 aabbccdd"),
+            WireGuard {
+                message_type: 4,
+                reserved: [0; 3],
+                message: WireGuardMessage::Transport {
+                    receiver_index: 0x12345678,
+                    counter: 1,
+                    data: vec![0xaa, 0xbb, 0xcc, 0xdd],
+                },
+            },
+        ),
+        case::unknown(
+            &hex!("09000000aabb"),
+            WireGuard {
+                message_type: 9,
+                reserved: [0; 3],
+                message: WireGuardMessage::Unknown {
+                    data: vec![0xaa, 0xbb],
+                },
+            },
+        ),
+    )]
+    fn test_wireguard(input: &[u8], expected: WireGuard) {
+        let ret_read = WireGuard::try_from(input).unwrap();
+        assert_eq!(expected, ret_read);
+
+        let ret_write = ret_read.to_bytes().unwrap();
+        assert_eq!(input.to_vec(), ret_write);
+    }
+
+    #[test]
+    fn test_wireguard_receiver_index() {
+        let wg =
+            WireGuard::try_from(hex!("04000000785634120100000000000000aabbccdd").as_ref()).unwrap();
+        assert_eq!(Some(0x12345678), wg.receiver_index());
+    }
+
+    #[test]
+    fn test_wireguard_update_message_type() {
+        let mut wg = WireGuard {
+            message_type: 0,
+            message: WireGuardMessage::CookieReply {
+                receiver_index: 1,
+                nonce: [0; 24],
+                encrypted_cookie: [0; 32],
+            },
+            ..WireGuard::default()
+        };
+
+        wg.update().unwrap();
+
+        assert_eq!(3, wg.message_type);
+    }
+
+    #[test]
+    fn test_wireguard_default() {
+        assert_eq!(
+            WireGuard {
+                message_type: 4,
+                reserved: [0; 3],
+                message: WireGuardMessage::Transport {
+                    receiver_index: 0,
+                    counter: 0,
+                    data: vec![],
+                },
+            },
+            WireGuard::default()
+        )
+    }
+}
@@ -40,6 +40,11 @@ pkt.update().unwrap();
 // Write the packet!
 let raw_bytes = pkt.to_bytes().unwrap();
 ```
+
+# no_std
+
+The crate requires `std`: the Ip layers store `std::net` addresses, for which deku only
+implements reading and writing with `std`.
 */
 
 pub mod datalink;
@@ -0,0 +1,146 @@
+/*!
+Ipv4 to MAC address table learned from ARP packets
+*/
+use super::Packet;
+use crate::layer::arp::{ARP_REPLY, ARP_REQUEST};
+use crate::layer::ether::MacAddress;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+/// A changed MAC address for an Ipv4 address, a possible ARP spoof
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArpConflict {
+    pub ip: Ipv4Addr,
+    /// Previously learned MAC address
+    pub old: MacAddress,
+    /// Newly announced MAC address, which replaces `old` in the table
+    pub new: MacAddress,
+}
+
+/**
+Table of the Ipv4 to MAC address mappings announced by the senders of ARP requests and
+replies
+
+```rust
+# use rust_packet::packet::ArpTable;
+let table = ArpTable::default();
+assert!(table.is_empty());
+```
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ArpTable {
+    entries: HashMap<Ipv4Addr, MacAddress>,
+}
+
+impl ArpTable {
+    /// Learn the sender mapping of an ARP packet
+    ///
+    /// Packets without an Arp layer and ARP probes, with an unspecified sender address, are
+    /// ignored. Returns the conflict if the sender changed the MAC address of a known Ipv4
+    /// address.
+    pub fn observe(&mut self, packet: &Packet) -> Option<ArpConflict> {
+        let arp = packet.arp()?;
+        if !(arp.operation == ARP_REQUEST || arp.operation == ARP_REPLY)
+            || arp.sender_ip.is_unspecified()
+        {
+            return None;
+        }
+
+        match self.entries.insert(arp.sender_ip, arp.sender_mac.clone()) {
+            Some(old) if old != arp.sender_mac => Some(ArpConflict {
+                ip: arp.sender_ip,
+                old,
+                new: arp.sender_mac.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Returns the MAC address learned for `ip`
+    pub fn get(&self, ip: &Ipv4Addr) -> Option<&MacAddress> {
+        self.entries.get(ip)
+    }
+
+    /// Returns the number of learned addresses
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no address was learned
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::arp::Arp;
+    use crate::layer::Layer;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_arp_table() {
+        // Ether / ARP request, 10.0.0.1 is at 00:11:22:33:44:55
+        let request = Packet::from_bytes(&hex!("ffffffffffff001122334455 0806 0001080006040001 001122334455 0a000001 000000000000 0a000002")).unwrap();
+        // Ether / ARP reply with padding, 10.0.0.2 is at 66:77:88:99:aa:bb
+        let reply = Packet::from_bytes(&hex!("00112233445566778899aabb 0806 0001080006040002 66778899aabb 0a000002 001122334455 0a000001 000000000000000000000000000000000000")).unwrap();
+        // Ether / 802.1Q / ARP reply, 10.0.0.2 is at de:ad:be:ef:c0:fe
+        let spoofed = Packet::from_bytes(&hex!("001122334455deadbeefc0fe 8100 0064 0806 0001080006040002 deadbeefc0fe 0a000002 001122334455 0a000001")).unwrap();
+
+        let mut table = ArpTable::default();
+        assert_eq!(None, table.observe(&request));
+        assert_eq!(None, table.observe(&reply));
+        assert_eq!(None, table.observe(&reply));
+        assert_eq!(2, table.len());
+        assert_eq!(
+            Some(&MacAddress([0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb])),
+            table.get(&Ipv4Addr::new(10, 0, 0, 2))
+        );
+
+        assert_eq!(
+            Some(ArpConflict {
+                ip: Ipv4Addr::new(10, 0, 0, 2),
+                old: MacAddress([0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb]),
+                new: MacAddress([0xde, 0xad, 0xbe, 0xef, 0xc0, 0xfe]),
+            }),
+            table.observe(&spoofed)
+        );
+        assert_eq!(
+            Some(&MacAddress([0xde, 0xad, 0xbe, 0xef, 0xc0, 0xfe])),
+            table.get(&Ipv4Addr::new(10, 0, 0, 2))
+        );
+    }
+
+    #[test]
+    fn test_arp_table_ignore() {
+        let mut table = ArpTable::default();
+
+        // Ether / ARP probe, unspecified sender address
+        let probe = Packet::from_bytes(&hex!("ffffffffffff001122334455 0806 0001080006040001 001122334455 00000000 000000000000 0a000001")).unwrap();
+        assert_eq!(None, table.observe(&probe));
+
+        // Ether / IP / TCP / "hello world"
+        let tcp = Packet::from_bytes(&hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64")).unwrap();
+        assert_eq!(None, table.observe(&tcp));
+
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_arp_table_arp_layer() {
+        let pkt = Packet::new(vec![Layer::Arp(Arp {
+            operation: ARP_REPLY,
+            sender_mac: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+            sender_ip: Ipv4Addr::new(10, 0, 0, 1),
+            ..Arp::default()
+        })]);
+
+        let mut table = ArpTable::default();
+        assert_eq!(None, table.observe(&pkt));
+        assert_eq!(
+            Some(&MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55])),
+            table.get(&Ipv4Addr::new(10, 0, 0, 1))
+        );
+    }
+}
@@ -0,0 +1,270 @@
+/*!
+Packet comparison ignoring volatile fields, and of written bytes
+*/
+use super::{Packet, PacketError};
+use crate::layer::{Layer, LayerError, Raw};
+
+/// Kind of header field which may be ignored in a comparison
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    /// Ipv4, Icmp, Igmp, Ospf, Tcp and Udp checksums
+    Checksum,
+    /// Ipv4 identification
+    Identification,
+    /// Ipv4 time to live and Ipv6 hop limit
+    Ttl,
+    /// Ipv4, Ipv6 and Udp lengths
+    Length,
+}
+
+impl FieldKind {
+    // Reset the field of this kind in `layer`
+    fn clear(self, layer: &mut Layer) {
+        match (self, layer) {
+            (FieldKind::Checksum, Layer::Ipv4(ipv4)) => ipv4.checksum = 0,
+            (FieldKind::Checksum, Layer::Tcp(tcp)) => tcp.checksum = 0,
+            (FieldKind::Checksum, Layer::Udp(udp)) => udp.checksum = 0,
+            (FieldKind::Checksum, Layer::Icmp(icmp)) => icmp.checksum = 0,
+            (FieldKind::Checksum, Layer::Igmp(igmp)) => igmp.checksum = 0,
+            (FieldKind::Checksum, Layer::Ospf(ospf)) => ospf.checksum = 0,
+            (FieldKind::Identification, Layer::Ipv4(ipv4)) => ipv4.identification = 0,
+            (FieldKind::Ttl, Layer::Ipv4(ipv4)) => ipv4.ttl = 0,
+            (FieldKind::Ttl, Layer::Ipv6(ipv6)) => ipv6.hop_limit = 0,
+            (FieldKind::Length, Layer::Ipv4(ipv4)) => ipv4.length = 0,
+            (FieldKind::Length, Layer::Ipv6(ipv6)) => ipv6.length = 0,
+            (FieldKind::Length, Layer::Udp(udp)) => udp.length = 0,
+            _ => {}
+        }
+    }
+}
+
+impl Packet {
+    /// Compare two packets, ignoring the fields of the kinds in `ignore`
+    pub fn equals_ignoring(&self, other: &Packet, ignore: &[FieldKind]) -> bool {
+        let normalize = |layer: &Layer| {
+            let mut layer = layer.clone();
+            for kind in ignore {
+                kind.clear(&mut layer);
+            }
+            layer
+        };
+
+        self.layers.len() == other.layers.len()
+            && self
+                .layers
+                .iter()
+                .zip(other.layers.iter())
+                .all(|(a, b)| normalize(a) == normalize(b))
+    }
+
+    /// Returns the `(offset, self_byte, other_byte)` of each differing byte of the written
+    /// packets
+    ///
+    /// When the lengths differ, the bytes past the end of the shorter packet are compared
+    /// against `0x00`
+    pub fn byte_diff(&self, other: &Packet) -> Result<Vec<(usize, u8, u8)>, PacketError> {
+        let a = self.to_bytes()?;
+        let b = other.to_bytes()?;
+
+        let ret = (0..a.len().max(b.len()))
+            .map(|i| {
+                (
+                    i,
+                    a.get(i).copied().unwrap_or(0x00),
+                    b.get(i).copied().unwrap_or(0x00),
+                )
+            })
+            .filter(|(_i, a, b)| a != b)
+            .collect();
+
+        Ok(ret)
+    }
+
+    /// Returns the number of differing bytes of the written packets, as counted by `byte_diff`
+    ///
+    /// The written packets must have the same length
+    pub fn byte_distance(&self, other: &Packet) -> Result<usize, PacketError> {
+        let a = self.to_bytes()?;
+        let b = other.to_bytes()?;
+
+        if a.len() != b.len() {
+            return Err(LayerError::Unexpected(format!(
+                "packet lengths differ: {} and {}",
+                a.len(),
+                b.len()
+            ))
+            .into());
+        }
+
+        Ok(a.iter().zip(b.iter()).filter(|(a, b)| a != b).count())
+    }
+
+    /// Returns true if `input` is written back identically once read as a packet
+    pub fn roundtrip_check(input: &[u8]) -> Result<bool, PacketError> {
+        Ok(Packet::roundtrip_first_diff(input)?.is_none())
+    }
+
+    /// Returns the offset of the first byte of `input` which is not written back identically
+    /// once read as a packet, `None` if the packet round-trips
+    pub fn roundtrip_first_diff(input: &[u8]) -> Result<Option<usize>, PacketError> {
+        Packet::from_bytes(input)?.first_diff(input)
+    }
+
+    // Offset of the first byte of `input` which differs from the written packet, the shorter
+    // of the two is compared as if padded with zero bytes
+    fn first_diff(&self, input: &[u8]) -> Result<Option<usize>, PacketError> {
+        let input = Packet::new(vec![Layer::Raw(Raw {
+            data: input.to_vec(),
+            bit_offset: 0,
+        })]);
+
+        let diff = input.byte_diff(self)?;
+        Ok(diff.first().map(|(offset, _a, _b)| *offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(ignore, expected,
+        case(&[], false),
+        case(&[FieldKind::Checksum], false),
+        case(&[FieldKind::Identification], false),
+        case(&[FieldKind::Checksum, FieldKind::Identification], true),
+        case(&[FieldKind::Identification, FieldKind::Checksum, FieldKind::Ttl], true),
+    )]
+    fn test_packet_equals_ignoring(ignore: &[FieldKind], expected: bool) {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        let mut other = Packet::from_bytes(test_data.as_ref()).unwrap();
+        other.ipv4_mut().unwrap().identification = 1337;
+        other.update().unwrap();
+
+        assert_eq!(expected, pkt.equals_ignoring(&other, ignore));
+        assert!(pkt.equals_ignoring(&pkt, &[]));
+    }
+
+    #[test]
+    fn test_packet_equals_ignoring_ospf_checksum() {
+        // Ether / IP / OSPF Hello
+        let test_data = hex!("01005e000005001122334455 0800 45c0004000010000015916f6c0a80101e0000005 0201002c0a0000010000000130f300000000000000000000 ffffff00000a020100000028c0a8010100000000");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        let mut other = pkt.clone();
+        other.ospf_mut().unwrap().checksum = 0;
+
+        assert!(!pkt.equals_ignoring(&other, &[]));
+        assert!(pkt.equals_ignoring(&other, &[FieldKind::Checksum]));
+    }
+
+    #[test]
+    fn test_packet_byte_diff() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(Vec::<(usize, u8, u8)>::new(), pkt.byte_diff(&pkt).unwrap());
+
+        // tcp window at offset 48, checksum not updated
+        let mut other = Packet::from_bytes(test_data.as_ref()).unwrap();
+        other.tcp_mut().unwrap().window = 0x1234;
+
+        assert_eq!(
+            vec![(48, 0x20, 0x12), (49, 0x00, 0x34)],
+            pkt.byte_diff(&other).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_packet_byte_diff_length() {
+        let pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000808")).unwrap();
+        let other = Packet::from_bytes(&hex!("ffffffffffff000000000000080800aa")).unwrap();
+
+        assert_eq!(vec![(15, 0x00, 0xaa)], pkt.byte_diff(&other).unwrap());
+    }
+
+    #[test]
+    fn test_packet_byte_distance() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(0, pkt.byte_distance(&pkt).unwrap());
+
+        // tcp window and the first payload byte, checksum not updated
+        let mut other = Packet::from_bytes(test_data.as_ref()).unwrap();
+        other.tcp_mut().unwrap().window = 0x1234;
+        other.raw_mut().unwrap().data[0] = b'H';
+
+        assert_eq!(3, pkt.byte_distance(&other).unwrap());
+        assert_eq!(3, other.byte_distance(&pkt).unwrap());
+    }
+
+    #[test]
+    fn test_packet_byte_distance_length() {
+        let pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000808")).unwrap();
+        let other = Packet::from_bytes(&hex!("ffffffffffff000000000000080800aa")).unwrap();
+
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Unexpected(
+                "packet lengths differ: 14 and 16".to_string()
+            ))),
+            pkt.byte_distance(&other)
+        );
+    }
+
+    #[test]
+    fn test_packet_roundtrip_check() {
+        // README example packet
+        let pkt = crate::pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse().unwrap()
+            }
+            .unwrap(),
+            crate::ipv4! {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.2".parse().unwrap(),
+            }
+            .unwrap(),
+            crate::udp! {
+                dport: 1337
+            }
+            .unwrap(),
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }
+            .unwrap(),
+        }
+        .unwrap();
+
+        let input = pkt.to_bytes().unwrap();
+
+        assert!(Packet::roundtrip_check(&input).unwrap());
+        assert_eq!(None, Packet::roundtrip_first_diff(&input).unwrap());
+    }
+
+    #[test]
+    fn test_packet_first_diff_lossy() {
+        // Ether / IP / TCP / "hello world" followed by a 4 byte trailer
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64 deadbeef");
+
+        let mut pkt = Packet::from_bytes_with_trailer(test_data.as_ref(), 4).unwrap();
+        assert_eq!(None, pkt.first_diff(test_data.as_ref()).unwrap());
+
+        // dropping the trailer is lossy from the end of the frame
+        pkt.set_trailer(vec![]);
+        assert_eq!(Some(65), pkt.first_diff(test_data.as_ref()).unwrap());
+
+        // a differing byte within the frame
+        let mut lossy = test_data;
+        lossy[54] = 0x48;
+        assert_eq!(Some(54), pkt.first_diff(lossy.as_ref()).unwrap());
+    }
+}
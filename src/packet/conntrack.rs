@@ -0,0 +1,206 @@
+/*!
+Tcp connection state inference from the headers of a flow
+*/
+use super::Packet;
+use std::net::IpAddr;
+
+/// State of a Tcp connection, as seen from the packets of both directions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TcpState {
+    /// No connection, or the connection was reset or torn down
+    Closed,
+    /// SYN seen
+    SynSent,
+    /// SYN-ACK seen
+    SynReceived,
+    /// Handshake completed
+    Established,
+    /// FIN seen from one endpoint
+    FinWait,
+    /// FIN seen from both endpoints, waiting for the last ACK
+    LastAck,
+}
+
+impl Default for TcpState {
+    fn default() -> Self {
+        TcpState::Closed
+    }
+}
+
+/**
+Tcp connection tracker inferring the state of a single flow from its flags
+
+Only the headers are inspected, sequence numbers are not verified.
+
+```rust
+# use rust_packet::packet::{TcpConntrack, TcpState};
+let conntrack = TcpConntrack::default();
+assert_eq!(TcpState::Closed, conntrack.state());
+```
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TcpConntrack {
+    state: TcpState,
+    // Endpoint which sent the first FIN
+    fin_sender: Option<(Option<IpAddr>, u16)>,
+}
+
+impl TcpConntrack {
+    /// Returns the current state of the connection
+    pub fn state(&self) -> TcpState {
+        self.state
+    }
+
+    /// Update the state from a packet of the flow, in either direction
+    ///
+    /// Packets without a Tcp layer are ignored. Returns the new state.
+    pub fn observe(&mut self, packet: &Packet) -> TcpState {
+        let tcp = match packet.tcp() {
+            Some(tcp) => tcp,
+            None => return self.state,
+        };
+        let flags = &tcp.flags;
+        let sender = (packet.ip_src(), tcp.sport);
+
+        if flags.reset == 1 {
+            self.state = TcpState::Closed;
+            self.fin_sender = None;
+            return self.state;
+        }
+
+        self.state = match self.state {
+            TcpState::Closed if flags.syn == 1 && flags.ack == 0 => TcpState::SynSent,
+            TcpState::SynSent if flags.syn == 1 && flags.ack == 1 => TcpState::SynReceived,
+            TcpState::SynReceived if flags.syn == 0 && flags.ack == 1 => {
+                if flags.fin == 1 {
+                    self.fin_sender = Some(sender);
+                    TcpState::FinWait
+                } else {
+                    TcpState::Established
+                }
+            }
+            TcpState::Established if flags.fin == 1 => {
+                self.fin_sender = Some(sender);
+                TcpState::FinWait
+            }
+            TcpState::FinWait if flags.fin == 1 && self.fin_sender != Some(sender) => {
+                TcpState::LastAck
+            }
+            TcpState::LastAck if flags.ack == 1 && self.fin_sender == Some(sender) => {
+                self.fin_sender = None;
+                TcpState::Closed
+            }
+            state => state,
+        };
+
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::ip::IpProtocol;
+    use crate::layer::tcp::TcpFlags;
+    use crate::layer::{Layer, Raw};
+    use crate::packet::PacketError;
+    use rstest::*;
+
+    // Tcp segment from the client if `from_client`, else from the server
+    fn segment(from_client: bool, flags: TcpFlags) -> Packet {
+        let (src, dst, sport, dport) = if from_client {
+            ("10.0.0.1", "10.0.0.2", 1337, 80)
+        } else {
+            ("10.0.0.2", "10.0.0.1", 80, 1337)
+        };
+
+        crate::pkt! {
+            crate::ether! {}?,
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::TCP,
+                src: src.parse()?,
+                dst: dst.parse()?,
+            }?,
+            crate::tcp! {
+                sport: sport,
+                dport: dport,
+                offset: 5,
+                flags: flags,
+            }?,
+        }
+        .unwrap()
+    }
+
+    fn flags(syn: u8, ack: u8, fin: u8, reset: u8) -> TcpFlags {
+        TcpFlags {
+            syn,
+            ack,
+            fin,
+            reset,
+            ..TcpFlags::default()
+        }
+    }
+
+    #[rstest(segments, expected,
+        case::handshake(
+            vec![
+                (true, flags(1, 0, 0, 0), TcpState::SynSent),
+                (false, flags(1, 1, 0, 0), TcpState::SynReceived),
+                (true, flags(0, 1, 0, 0), TcpState::Established),
+            ],
+            TcpState::Established,
+        ),
+        case::full_connection(
+            vec![
+                (true, flags(1, 0, 0, 0), TcpState::SynSent),
+                (false, flags(1, 1, 0, 0), TcpState::SynReceived),
+                (true, flags(0, 1, 0, 0), TcpState::Established),
+                (true, flags(0, 1, 0, 0), TcpState::Established),
+                (false, flags(0, 1, 0, 0), TcpState::Established),
+                (true, flags(0, 1, 1, 0), TcpState::FinWait),
+                (false, flags(0, 1, 0, 0), TcpState::FinWait),
+                (false, flags(0, 1, 1, 0), TcpState::LastAck),
+                (true, flags(0, 1, 0, 0), TcpState::Closed),
+            ],
+            TcpState::Closed,
+        ),
+        case::reset(
+            vec![
+                (true, flags(1, 0, 0, 0), TcpState::SynSent),
+                (false, flags(1, 1, 0, 0), TcpState::SynReceived),
+                (true, flags(0, 1, 0, 0), TcpState::Established),
+                (false, flags(0, 0, 0, 1), TcpState::Closed),
+            ],
+            TcpState::Closed,
+        ),
+        case::fin_retransmit(
+            vec![
+                (true, flags(1, 0, 0, 0), TcpState::SynSent),
+                (false, flags(1, 1, 0, 0), TcpState::SynReceived),
+                (true, flags(0, 1, 0, 0), TcpState::Established),
+                (true, flags(0, 1, 1, 0), TcpState::FinWait),
+                (true, flags(0, 1, 1, 0), TcpState::FinWait),
+            ],
+            TcpState::FinWait,
+        ),
+    )]
+    fn test_tcp_conntrack(segments: Vec<(bool, TcpFlags, TcpState)>, expected: TcpState) {
+        let mut conntrack = TcpConntrack::default();
+
+        for (from_client, flags, expected_state) in segments {
+            assert_eq!(expected_state, conntrack.observe(&segment(from_client, flags)));
+        }
+
+        assert_eq!(expected, conntrack.state());
+    }
+
+    #[test]
+    fn test_tcp_conntrack_ignore_non_tcp() {
+        let mut conntrack = TcpConntrack::default();
+        let pkt = Packet::new(vec![Layer::Raw(Raw::default())]);
+
+        assert_eq!(TcpState::Closed, conntrack.observe(&pkt));
+    }
+}
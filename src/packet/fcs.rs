@@ -0,0 +1,107 @@
+/*!
+Ethernet frame check sequence (FCS)
+*/
+use super::{Packet, PacketError};
+use crate::layer::LayerError;
+use std::convert::TryInto;
+
+/// Length of the Ethernet FCS
+pub const FCS_LEN: usize = 4;
+
+/// CRC-32 (IEEE 802.3) of `input`, as used by the Ethernet FCS
+pub fn crc32(input: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in input {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+impl Packet {
+    /// Read a packet from bytes, the last 4 bytes are the FCS if `has_fcs` is set
+    ///
+    /// The FCS is kept on the packet and written back by `to_bytes`
+    pub fn from_bytes_with_fcs(input: &[u8], has_fcs: bool) -> Result<Packet, PacketError> {
+        if !has_fcs {
+            return Packet::from_bytes(input);
+        }
+
+        let split = input
+            .len()
+            .checked_sub(FCS_LEN)
+            .ok_or_else(|| LayerError::Parse("not enough data to read fcs".to_string()))?;
+        let (frame, fcs) = input.split_at(split);
+
+        let mut pkt = Packet::from_bytes(frame)?;
+        pkt.fcs = Some(u32::from_le_bytes(
+            fcs.try_into().expect("slice of 4 bytes"),
+        ));
+
+        Ok(pkt)
+    }
+
+    /// Returns the FCS of the packet, if present
+    pub fn fcs(&self) -> Option<u32> {
+        self.fcs
+    }
+
+    /// Set the FCS of the packet, `None` removes it
+    pub fn set_fcs(&mut self, fcs: Option<u32>) {
+        self.fcs = fcs;
+    }
+
+    // Returns the FCS computed over the written layers and trailer
+    pub(super) fn compute_fcs(&self) -> Result<u32, PacketError> {
+        let mut frame = self.layers_to_bytes()?;
+        frame.extend_from_slice(self.trailer());
+        Ok(crc32(&frame))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::ValidationError;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(0xcbf43926, crc32(b"123456789"));
+    }
+
+    #[test]
+    fn test_packet_from_bytes_with_fcs() {
+        // Ether / IP / TCP / "hello world" / FCS
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64 949ab60d");
+
+        let pkt = Packet::from_bytes_with_fcs(test_data.as_ref(), true).unwrap();
+
+        assert_eq!(Some(0x0db69a94), pkt.fcs());
+        assert_eq!(b"hello world".to_vec(), pkt.raw().unwrap().data);
+        assert_eq!(Vec::<ValidationError>::new(), pkt.validate().unwrap());
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+
+        // without the flag, the FCS is read as payload
+        let pkt = Packet::from_bytes_with_fcs(test_data.as_ref(), false).unwrap();
+        assert_eq!(None, pkt.fcs());
+        assert_eq!(15, pkt.raw().unwrap().data.len());
+    }
+
+    #[test]
+    fn test_packet_fcs_update() {
+        // Ether / IP / TCP / "hello world" / FCS
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64 949ab60d");
+
+        let mut pkt = Packet::from_bytes_with_fcs(test_data.as_ref(), true).unwrap();
+        pkt.raw_mut().unwrap().data = b"HELLO WORLD".to_vec();
+        assert_eq!(vec![ValidationError::Fcs], pkt.validate().unwrap());
+
+        pkt.update().unwrap();
+        assert_eq!(Vec::<ValidationError>::new(), pkt.validate().unwrap());
+    }
+}
@@ -0,0 +1,121 @@
+/*!
+Declarative packet filter
+*/
+use super::Packet;
+use crate::layer::ether::EtherType;
+use crate::layer::ip::IpProtocol;
+use crate::layer::Layer;
+use std::net::IpAddr;
+
+/**
+Filter matching packets on their addresses, ports and protocols
+
+Fields which are `None` match any packet. The filter can be used with `Interface::sniff`:
+
+```rust
+# use rust_packet::packet::PacketFilter;
+let filter = PacketFilter {
+    dport: Some(80),
+    ..PacketFilter::default()
+};
+let sniff_filter = |pkt: &_, _user_data: &mut ()| filter.matches(pkt);
+```
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PacketFilter {
+    pub src_ip: Option<IpAddr>,
+    pub dst_ip: Option<IpAddr>,
+    /// Tcp or Udp source port
+    pub sport: Option<u16>,
+    /// Tcp or Udp destination port
+    pub dport: Option<u16>,
+    /// Ipv4 protocol, or next header of the last Ipv6 header
+    pub protocol: Option<IpProtocol>,
+    pub ether_type: Option<EtherType>,
+}
+
+// Match an optional filter field
+fn field_matches<T: PartialEq>(filter: &Option<T>, value: Option<T>) -> bool {
+    filter.is_none() || *filter == value
+}
+
+impl PacketFilter {
+    /// Returns true if the packet matches all the fields of the filter
+    pub fn matches(&self, packet: &Packet) -> bool {
+        let ports = packet.layers.iter().find_map(|v| match v {
+            Layer::Tcp(tcp) => Some((tcp.sport, tcp.dport)),
+            Layer::Udp(udp) => Some((udp.sport, udp.dport)),
+            _ => None,
+        });
+
+        let protocol = packet
+            .layers
+            .iter()
+            .filter_map(|v| match v {
+                Layer::Ipv4(ipv4) => Some(&ipv4.protocol),
+                Layer::Ipv6(ipv6) => Some(&ipv6.next_header),
+                Layer::Ipv6Ext(ipv6ext) => Some(&ipv6ext.next_header),
+                _ => None,
+            })
+            .last();
+
+        field_matches(&self.src_ip, packet.ip_src())
+            && field_matches(&self.dst_ip, packet.ip_dst())
+            && field_matches(&self.sport, ports.map(|(sport, _dport)| sport))
+            && field_matches(&self.dport, ports.map(|(_sport, dport)| dport))
+            && field_matches(&self.protocol.as_ref(), protocol)
+            && field_matches(&self.ether_type.as_ref(), packet.ether().map(|v| &v.ether_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    // Ether / IP / TCP 20 -> 80 / "hello world"
+    const TCP_80: [u8; 65] = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+    // Ether / IP / TCP 80 -> 20 / "hello world"
+    const TCP_20: [u8; 65] = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100500014000000000000000050022000ffa2000068656c6c6f20776f726c64");
+    // Ether / IP / UDP 1234 -> 5678 / "hello"
+    const UDP_5678: [u8; 47] = hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f");
+
+    #[test]
+    fn test_packet_filter_dport() {
+        let filter = PacketFilter {
+            dport: Some(80),
+            ..PacketFilter::default()
+        };
+
+        let packets = [&TCP_80[..], &TCP_20[..], &UDP_5678[..]];
+        let matched: Vec<bool> = packets
+            .iter()
+            .map(|v| filter.matches(&Packet::from_bytes(v).unwrap()))
+            .collect();
+
+        assert_eq!(vec![true, false, false], matched);
+    }
+
+    #[rstest(filter, expected,
+        case::any(PacketFilter::default(), true),
+        case::src_ip(PacketFilter { src_ip: Some("127.0.0.1".parse().unwrap()), ..PacketFilter::default() }, true),
+        case::dst_ip(PacketFilter { dst_ip: Some("127.0.0.2".parse().unwrap()), ..PacketFilter::default() }, false),
+        case::protocol(PacketFilter { protocol: Some(IpProtocol::UDP), ..PacketFilter::default() }, true),
+        case::protocol_tcp(PacketFilter { protocol: Some(IpProtocol::TCP), ..PacketFilter::default() }, false),
+        case::ether_type(PacketFilter { ether_type: Some(EtherType::IPv4), ..PacketFilter::default() }, true),
+        case::all(PacketFilter {
+            src_ip: Some("127.0.0.1".parse().unwrap()),
+            dst_ip: Some("127.0.0.1".parse().unwrap()),
+            sport: Some(1234),
+            dport: Some(5678),
+            protocol: Some(IpProtocol::UDP),
+            ether_type: Some(EtherType::IPv4),
+        }, true),
+        case::sport(PacketFilter { sport: Some(5678), ..PacketFilter::default() }, false),
+    )]
+    fn test_packet_filter(filter: PacketFilter, expected: bool) {
+        let pkt = Packet::from_bytes(&UDP_5678).unwrap();
+        assert_eq!(expected, filter.matches(&pkt));
+    }
+}
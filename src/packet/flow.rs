@@ -0,0 +1,227 @@
+/*!
+Flow 5-tuple of a packet, from a parsed packet or read directly from bytes
+*/
+use super::Packet;
+use crate::layer::ip::IpProtocol;
+use crate::layer::Layer;
+use deku::prelude::*;
+use std::convert::TryInto;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// 5-tuple identifying the flow of a packet
+///
+/// The ports are 0 for protocols other than Tcp and Udp
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FlowKey {
+    pub src: IpAddr,
+    pub dst: IpAddr,
+    pub protocol: IpProtocol,
+    pub sport: u16,
+    pub dport: u16,
+}
+
+// read the big-endian u16 at `offset`
+fn read_u16(input: &[u8], offset: usize) -> Option<u16> {
+    let bytes = input.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes(bytes.try_into().ok()?))
+}
+
+// read the ip protocol number at `offset`, `None` if unknown
+fn read_protocol(input: &[u8], offset: usize) -> Option<IpProtocol> {
+    let byte = input.get(offset..offset + 1)?;
+    IpProtocol::from_bytes((byte, 0)).ok().map(|(_rest, v)| v)
+}
+
+impl Packet {
+    /// Returns the flow 5-tuple of the packet, `None` if it has no Ip layer
+    ///
+    /// The addresses and protocol are the ones of the last Ipv4 layer or Ipv6 header chain,
+    /// the inner packet of a tunnel
+    pub fn flow_key(&self) -> Option<FlowKey> {
+        let mut ret: Option<FlowKey> = None;
+        for layer in self.layers.iter() {
+            match layer {
+                Layer::Ipv4(ipv4) => {
+                    ret = Some(FlowKey {
+                        src: IpAddr::V4(ipv4.src),
+                        dst: IpAddr::V4(ipv4.dst),
+                        protocol: ipv4.protocol.clone(),
+                        sport: 0,
+                        dport: 0,
+                    })
+                }
+                Layer::Ipv6(ipv6) => {
+                    ret = Some(FlowKey {
+                        src: IpAddr::V6(ipv6.src),
+                        dst: IpAddr::V6(ipv6.dst),
+                        protocol: ipv6.next_header.clone(),
+                        sport: 0,
+                        dport: 0,
+                    })
+                }
+                Layer::Ipv6Ext(ext) => {
+                    if let Some(key) = ret.as_mut() {
+                        key.protocol = ext.next_header.clone();
+                    }
+                }
+                Layer::Tcp(tcp) => {
+                    if let Some(key) = ret.as_mut() {
+                        key.sport = tcp.sport;
+                        key.dport = tcp.dport;
+                    }
+                }
+                Layer::Udp(udp) => {
+                    if let Some(key) = ret.as_mut() {
+                        key.sport = udp.sport;
+                        key.dport = udp.dport;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        ret
+    }
+
+    /// Returns the flow 5-tuple of an Ethernet frame, reading only the headers it depends on
+    ///
+    /// A fast path for `Packet::from_bytes(input)?.flow_key()`: no layer is read nor
+    /// allocated. `None` is returned if the frame does not carry Ipv4 or Ipv6, if a header is
+    /// cut off or if the ip protocol is unknown. The ports are read from the first 4 bytes of
+    /// the Tcp or Udp header. Tunnels are not followed, the key is the one of the outer packet.
+    pub fn flow_parse(input: &[u8]) -> Option<FlowKey> {
+        // skip the vlan tags
+        let mut offset = 12;
+        let mut ether_type = read_u16(input, offset)?;
+        while ether_type == 0x8100 || ether_type == 0x88a8 {
+            offset += 4;
+            ether_type = read_u16(input, offset)?;
+        }
+        offset += 2;
+
+        let (src, dst, mut protocol, mut offset) = match ether_type {
+            0x0800 => {
+                let header = input.get(offset..offset + 20)?;
+                let ihl = usize::from(header[0] & 0x0f) * 4;
+                if header[0] >> 4 != 4 || ihl < 20 {
+                    return None;
+                }
+
+                let src: [u8; 4] = header[12..16].try_into().ok()?;
+                let dst: [u8; 4] = header[16..20].try_into().ok()?;
+                (
+                    IpAddr::V4(Ipv4Addr::from(src)),
+                    IpAddr::V4(Ipv4Addr::from(dst)),
+                    read_protocol(header, 9)?,
+                    offset + ihl,
+                )
+            }
+            0x86dd => {
+                let header = input.get(offset..offset + 40)?;
+                let src: [u8; 16] = header[8..24].try_into().ok()?;
+                let dst: [u8; 16] = header[24..40].try_into().ok()?;
+                (
+                    IpAddr::V6(Ipv6Addr::from(src)),
+                    IpAddr::V6(Ipv6Addr::from(dst)),
+                    read_protocol(header, 6)?,
+                    offset + 40,
+                )
+            }
+            _ => return None,
+        };
+
+        // follow the ipv6 extension header chain
+        while matches!(
+            protocol,
+            IpProtocol::HOPOPT | IpProtocol::IPV6ROUTE | IpProtocol::IPV6FRAG | IpProtocol::IPV6OPTS
+        ) {
+            let length = usize::from(*input.get(offset + 1)?);
+            protocol = read_protocol(input, offset)?;
+            offset += length * 8 + 8;
+        }
+
+        let (sport, dport) = match protocol {
+            IpProtocol::TCP | IpProtocol::UDP => {
+                (read_u16(input, offset)?, read_u16(input, offset + 2)?)
+            }
+            _ => (0, 0),
+        };
+
+        Some(FlowKey {
+            src,
+            dst,
+            protocol,
+            sport,
+            dport,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    #[rstest(input, expected,
+        case::ipv4_tcp(
+            &hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000200140050000000000000000050022000ffa2000068656c6c6f20776f726c64"),
+            Some(FlowKey {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.2".parse().unwrap(),
+                protocol: IpProtocol::TCP,
+                sport: 20,
+                dport: 80,
+            }),
+        ),
+        case::vlan_ipv4_udp(
+            &hex!("ffffffffffff000000000000 8100 00640800 450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"),
+            Some(FlowKey {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.1".parse().unwrap(),
+                protocol: IpProtocol::UDP,
+                sport: 1234,
+                dport: 5678,
+            }),
+        ),
+        case::ipv6_hop_by_hop_udp(
+            &hex!("ffffffffffff000000000000 86dd 6000000000150040 00000000000000000000000000000001 00000000000000000000000000000002 1100050200000100 04d2162e000d0000 68656c6c6f"),
+            Some(FlowKey {
+                src: "::1".parse().unwrap(),
+                dst: "::2".parse().unwrap(),
+                protocol: IpProtocol::UDP,
+                sport: 1234,
+                dport: 5678,
+            }),
+        ),
+        case::ipv4_icmp(
+            &hex!("ffffffffffff0000000000000800450000210001000040017cc97f0000017f000001 0800b42b00010001 68656c6c6f"),
+            Some(FlowKey {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.1".parse().unwrap(),
+                protocol: IpProtocol::ICMP,
+                sport: 0,
+                dport: 0,
+            }),
+        ),
+        case::arp(&hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002"), None),
+    )]
+    fn test_packet_flow_parse(input: &[u8], expected: Option<FlowKey>) {
+        assert_eq!(expected, Packet::flow_parse(input));
+
+        // the fast path matches the full parse
+        let pkt = Packet::from_bytes(input).unwrap();
+        assert_eq!(expected, pkt.flow_key());
+    }
+
+    #[rstest(input,
+        case::short_ether(&hex!("ffffffffffff00000000")),
+        case::short_ipv4(&hex!("ffffffffffff0000000000000800450000330001")),
+        case::bad_ihl(&hex!("ffffffffffff0000000000000800420000330001000040067cc27f0000017f00000200140050")),
+        case::short_ports(&hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f0000020014")),
+        case::short_ipv6_ext(&hex!("ffffffffffff000000000000 86dd 6000000000150040 00000000000000000000000000000001 00000000000000000000000000000002 11")),
+    )]
+    fn test_packet_flow_parse_malformed(input: &[u8]) {
+        assert_eq!(None, Packet::flow_parse(input));
+    }
+}
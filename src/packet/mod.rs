@@ -4,355 +4,3019 @@ Module containing the `Packet` type
 A Packet is a collection of layers
 */
 
+mod arptable;
+mod compare;
+mod conntrack;
 pub mod error;
+mod fcs;
+mod filter;
+mod flow;
+mod pcap;
+mod report;
+mod stats;
+mod time;
+mod trailer;
+
+pub use arptable::{ArpConflict, ArpTable};
+pub use compare::FieldKind;
+pub use conntrack::{TcpConntrack, TcpState};
 pub use error::PacketError;
+pub use fcs::{crc32, FCS_LEN};
+pub use filter::PacketFilter;
+pub use flow::FlowKey;
+pub use pcap::PCAP_RECORD_HEADER_LEN;
+pub use report::{FieldChange, UpdateReport};
+pub use stats::CaptureStats;
+pub use time::{merge_packets_by_time, sort_packets_by_time};
 
-use crate::layer::{Layer, LayerType};
+use crate::datalink::LinkType;
+use crate::layer::arp::{ARP_REPLY, ARP_REQUEST};
+use crate::layer::ether::{Ether, EtherType, ETHER_PREAMBLE};
+use crate::layer::icmp::{Icmp, ICMP_DEST_UNREACHABLE};
+use crate::layer::ip::{ones_complement_sum, IpProtocol, Ipv4, Ipv6, Ipv6Ext};
+use crate::layer::raw::Raw;
+use crate::layer::tcp::Tcp;
+use crate::layer::udp::Udp;
+use crate::layer::vxlan::{Vxlan, VXLAN_PORT};
+use crate::layer::{
+    Layer, LayerError, LayerType, LayerValidate, UnknownHandling, ValidationError,
+};
+use deku::bitvec::{BitVec, BitView, Msb0};
+use deku::prelude::*;
+use std::convert::TryFrom;
+use std::net::{IpAddr, Ipv4Addr};
 
 const MAX_LAYERS: usize = 10;
 
 /// Container for network layers
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Packet {
     layers: Vec<Layer>,
+    trailer: Vec<u8>, // Link trailer bytes following the layers
+    fcs: Option<u32>, // Ethernet frame check sequence
 }
 
 impl Packet {
     pub fn new(layers: Vec<Layer>) -> Self {
-        Packet { layers }
+        Packet {
+            layers,
+            trailer: Vec::new(),
+            fcs: None,
+        }
     }
 
     /// Read a packet from bytes
     /// This will read layers in accordance to the protocol
+    ///
+    /// Malformed input is reported as an error, reading is exercised by the
+    /// `fuzz_packet_from_bytes` fuzz target
     pub fn from_bytes(input: &[u8]) -> Result<Packet, PacketError> {
         let layers = Layer::from_bytes_multi_layer(input, MAX_LAYERS)?;
         Ok(Packet::new(layers))
     }
 
-    /// Write packet to bytes
-    pub fn to_bytes(&self) -> Result<Vec<u8>, PacketError> {
-        let mut acc = Vec::new();
-        for layer in &self.layers {
-            acc.extend(layer.to_bytes()?);
-        }
+    /// Read all the packets from concatenated bytes
+    ///
+    /// Each packet is bounded by the length of its Ip layer
+    pub fn from_bytes_all(input: &[u8]) -> Result<Vec<Packet>, PacketError> {
+        let mut ret = Vec::new();
+        let mut offset = 0;
+        while offset < input.len() {
+            let rest = &input[offset..];
 
-        Ok(acc)
-    }
+            let pkt = Packet::packet_len(rest)
+                .map_err(PacketError::from)
+                .and_then(|len| Ok((len, Packet::from_bytes(&rest[..len])?)));
 
-    /// Update the packet
-    /// This is used to re-compute dynamic data such as checksums and lengths
-    pub fn update(&mut self) -> Result<(), PacketError> {
-        /* TODO:
-            I feel like this routine can be optimized.
-            The main quirk is that some layers depend on others,
-            such as lengths and checksums
-        */
-
-        for i in 0..self.layers.len() {
-            let layers = &mut self.layers.as_mut_slice()[i..];
-
-            let layers_copy = layers.to_vec();
-
-            match layers {
-                [] => {}
-                [layer] => {
-                    match layer {
-                        Layer::Ipv4(ipv4) => ipv4.update_length(&[])?,
-                        Layer::Ipv6(ipv6) => ipv6.update_length(&[])?,
-                        Layer::Udp(udp) => udp.update_length(&[])?,
-                        _ => {}
-                    }
+            let (len, pkt) = pkt.map_err(|e| {
+                LayerError::Parse(format!("malformed packet at offset {}: {}", offset, e))
+            })?;
 
-                    layer.update()?;
-                }
-                [layer, next_layer, ..] => {
-                    // Update current layers which depend on next-layers
-                    match layer {
-                        Layer::Ipv4(ipv4) => ipv4.update_length(&layers_copy[1..])?,
-                        Layer::Ipv6(ipv6) => ipv6.update_length(&layers_copy[1..])?,
-                        _ => {}
-                    }
+            ret.push(pkt);
+            offset += len;
+        }
 
-                    // Update next-layers which depend on current layer
-                    match next_layer {
-                        Layer::Tcp(tcp) => match layer {
-                            Layer::Ipv4(ipv4) => {
-                                tcp.update_checksum_ipv4(ipv4, &layers_copy[2..])?
-                            }
-                            Layer::Ipv6(ipv6) => {
-                                tcp.update_checksum_ipv6(ipv6, &layers_copy[2..])?
-                            }
-                            _ => {}
-                        },
-                        Layer::Udp(udp) => {
-                            udp.update_length(&layers_copy[2..])?;
-
-                            match layer {
-                                Layer::Ipv4(ipv4) => {
-                                    udp.update_checksum_ipv4(ipv4, &layers_copy[2..])?
-                                }
-                                Layer::Ipv6(ipv6) => {
-                                    udp.update_checksum_ipv6(ipv6, &layers_copy[2..])?
-                                }
-                                _ => {}
-                            }
-                        }
-                        _ => {}
-                    }
+        Ok(ret)
+    }
 
-                    // Update current layer
-                    layer.update()?;
-                }
+    // Length of the packet at the start of `input`, from the Ip length
+    fn packet_len(input: &[u8]) -> Result<usize, LayerError> {
+        let ((rest, _), ether) = Ether::from_bytes((input, 0))?;
+        let ether_len = input.len() - rest.len();
+
+        let ip_len = match ether.ether_type {
+            EtherType::IPv4 => usize::from(Ipv4::from_bytes((rest, 0))?.1.length),
+            EtherType::IPv6 => {
+                let (_rest, ipv6) = Ipv6::from_bytes((rest, 0))?;
+                ipv6.header_len() + usize::from(ipv6.length)
             }
+            _ => {
+                return Err(LayerError::Parse(
+                    "cannot bound a non-ip packet".to_string(),
+                ))
+            }
+        };
+
+        let len = ether_len + ip_len;
+        if len > input.len() {
+            return Err(LayerError::Parse("not enough data to read packet".to_string()));
         }
 
-        Ok(())
+        Ok(len)
     }
-}
 
-macro_rules! impl_layer_packet_funcs {
-    ($layer:ident, $func:ident, $func_mut:ident) => {
-        /// Returns the first layer as a reference
-        pub fn $func(&self) -> Option<&crate::layer::$layer> {
-            let layer = self
-                .layers
-                .iter()
-                .find(|v| v.layer_type() == LayerType::$layer);
+    /// Read a packet from bytes, starting with a `layer_type` layer
+    /// This will read layers in accordance to the protocol
+    pub fn from_bytes_as(input: &[u8], layer_type: LayerType) -> Result<Packet, PacketError> {
+        let layers = Layer::from_bytes_multi_layer_as(input, layer_type, MAX_LAYERS)?;
+        Ok(Packet::new(layers))
+    }
 
-            if let Some(Layer::$layer(layer)) = layer {
-                Some(layer)
-            } else {
-                None
+    /// Read a packet captured on a `link_type` interface, the first layer is the link-layer
+    /// header of that link type
+    pub fn from_bytes_for_link(input: &[u8], link_type: LinkType) -> Result<Packet, PacketError> {
+        Packet::from_bytes_as(input, link_type.first_layer(input))
+    }
+
+    /// Read a packet from bytes, the bytes following an unsupported ether type are handled
+    /// according to `unknown`
+    ///
+    /// `from_bytes` reads them as a Raw layer, as `UnknownHandling::Raw` does
+    pub fn from_bytes_with_unknown(
+        input: &[u8],
+        unknown: UnknownHandling,
+    ) -> Result<Packet, PacketError> {
+        let layers =
+            Layer::from_bytes_multi_layer_with(input, LayerType::Ether, MAX_LAYERS, unknown)?;
+        Ok(Packet::new(layers))
+    }
+
+    /// Read a packet from bytes, a trailing Raw layer of more than `max_payload` bytes is an
+    /// error
+    ///
+    /// The Raw layer is not read when it exceeds `max_payload`, this bounds the memory used to
+    /// read untrusted input
+    pub fn from_bytes_with_max_payload(
+        input: &[u8],
+        max_payload: usize,
+    ) -> Result<Packet, PacketError> {
+        let layers = Layer::from_bytes_multi_layer_bounded(
+            input,
+            LayerType::Ether,
+            MAX_LAYERS,
+            UnknownHandling::Raw,
+            max_payload,
+        )?;
+        Ok(Packet::new(layers))
+    }
+
+    /// Read a packet from bytes which may be cut off, as in a capture with a snaplen
+    ///
+    /// The layers are read until one cannot be read, the remaining bytes are read as a Raw
+    /// layer. The first layer must be read. A Tcp header cut off in its options is kept with
+    /// the options which were read completely, see `Tcp::options_truncated`.
+    pub fn from_bytes_truncated(input: &[u8]) -> Result<Packet, PacketError> {
+        let mut ret = Layer::from_bytes_multi_layer(input, MAX_LAYERS);
+        for max_depth in (0..MAX_LAYERS).rev() {
+            if ret.is_ok() {
+                break;
             }
+
+            ret = Layer::from_bytes_multi_layer(input, max_depth);
         }
 
-        /// Returns the first layer as a mutable reference
-        pub fn $func_mut(&mut self) -> Option<&mut crate::layer::$layer> {
-            let layer = self
-                .layers
-                .iter_mut()
-                .find(|v| v.layer_type() == LayerType::$layer);
+        let mut layers = ret?;
 
-            if let Some(Layer::$layer(layer)) = layer {
-                Some(layer)
-            } else {
-                None
+        // the trailing Raw layer may be a Tcp header cut off in its options
+        let tcp = match layers.as_slice() {
+            [.., Layer::Ipv4(ipv4), Layer::Raw(raw)] if ipv4.protocol == IpProtocol::TCP => {
+                Some(raw)
+            }
+            [.., Layer::Ipv6(ipv6), Layer::Raw(raw)] if ipv6.next_header == IpProtocol::TCP => {
+                Some(raw)
             }
+            [.., Layer::Ipv6Ext(ext), Layer::Raw(raw)] if ext.next_header == IpProtocol::TCP => {
+                Some(raw)
+            }
+            _ => None,
         }
-    };
-}
-
-// # LAYER: Function to access layer from packet
-impl Packet {
-    impl_layer_packet_funcs!(Raw, raw, raw_mut);
-    impl_layer_packet_funcs!(Ether, ether, ether_mut);
-    impl_layer_packet_funcs!(Ipv4, ipv4, ipv4_mut);
-    impl_layer_packet_funcs!(Ipv6, ipv6, ipv6_mut);
-    impl_layer_packet_funcs!(Tcp, tcp, tcp_mut);
-    impl_layer_packet_funcs!(Udp, udp, udp_mut);
-}
+        .and_then(|raw| Tcp::from_bytes_lenient(&raw.data).ok())
+        .map(|(rest, tcp, _note)| (rest.to_vec(), tcp));
 
-impl std::ops::Index<LayerType> for Packet {
-    type Output = Layer;
+        if let Some((rest, tcp)) = tcp {
+            layers.pop();
+            layers.push(Layer::Tcp(tcp));
+            if !rest.is_empty() {
+                layers.push(Layer::Raw(Raw {
+                    data: rest,
+                    bit_offset: 0,
+                }));
+            }
+        }
 
-    fn index(&self, layer_type: LayerType) -> &Self::Output {
-        self.layers
-            .iter()
-            .find(|v| v.layer_type() == layer_type)
-            .unwrap_or_else(|| panic!("could not find layer: {:?}", layer_type))
+        Ok(Packet::new(layers))
     }
-}
 
-impl std::ops::IndexMut<LayerType> for Packet {
-    fn index_mut(&mut self, layer_type: LayerType) -> &mut Self::Output {
-        self.layers
-            .iter_mut()
-            .find(|v| v.layer_type() == layer_type)
-            .unwrap_or_else(|| panic!("could not find layer: {:?}", layer_type))
+    /// Read a packet from bytes starting with an Ipv4 or Ipv6 layer
+    ///
+    /// The Ip version is detected from the first nibble, as in tun or `DLT_RAW` captures
+    pub fn from_ip_bytes(input: &[u8]) -> Result<Packet, PacketError> {
+        let layer_type = match input.first().map(|v| v >> 4) {
+            Some(4) => LayerType::Ipv4,
+            Some(6) => LayerType::Ipv6,
+            Some(version) => {
+                return Err(
+                    LayerError::Parse(format!("unsupported ip version {}", version)).into(),
+                )
+            }
+            None => {
+                return Err(LayerError::Parse("no data to read ip version".to_string()).into())
+            }
+        };
+
+        Packet::from_bytes_as(input, layer_type)
     }
-}
 
-/**
-Create a [Packet](packet/struct.Packet.html)
+    /// Read an Ethernet packet from bytes which may start with the preamble and SFD
+    ///
+    /// The leading bytes are only stripped if they match the preamble and SFD exactly
+    pub fn from_bytes_skip_preamble(input: &[u8]) -> Result<Packet, PacketError> {
+        let input = input.strip_prefix(&ETHER_PREAMBLE[..]).unwrap_or(input);
+        Packet::from_bytes(input)
+    }
 
-Returns `Result<Packet, PacketError>`
+    /// Build a packet of default layers from a layer path such as `"Ether/IP/TCP"`
+    ///
+    /// Supported names are `Ether`, `IP`, `IPv6`, `TCP`, `UDP` and `Raw`. The protocol fields
+    /// of each layer are set to the following layer and the packet is updated.
+    pub fn from_spec(spec: &str) -> Result<Packet, PacketError> {
+        let mut layers: Vec<Layer> = Vec::new();
+        for name in spec.split('/') {
+            let layer = match name.trim() {
+                "Ether" => Layer::Ether(Ether::default()),
+                "IP" => Layer::Ipv4(Ipv4 {
+                    version: 4,
+                    ihl: 5,
+                    ttl: 64,
+                    ..Ipv4::default()
+                }),
+                "IPv6" => Layer::Ipv6(Ipv6 {
+                    version: 6,
+                    hop_limit: 64,
+                    ..Ipv6::default()
+                }),
+                "TCP" => Layer::Tcp(Tcp {
+                    offset: 5,
+                    ..Tcp::default()
+                }),
+                "UDP" => Layer::Udp(Udp::default()),
+                "Raw" => Layer::Raw(Raw::default()),
+                _ => {
+                    return Err(
+                        LayerError::Unexpected(format!("unknown layer name: {}", name)).into(),
+                    )
+                }
+            };
 
-Example:
+            // Link the previous layer to this one
+            match (layers.last_mut(), &layer) {
+                (Some(Layer::Ether(ether)), Layer::Ipv4(_)) => ether.ether_type = EtherType::IPv4,
+                (Some(Layer::Ether(ether)), Layer::Ipv6(_)) => ether.ether_type = EtherType::IPv6,
+                (Some(Layer::Ipv4(ipv4)), Layer::Tcp(_)) => ipv4.protocol = IpProtocol::TCP,
+                (Some(Layer::Ipv4(ipv4)), Layer::Udp(_)) => ipv4.protocol = IpProtocol::UDP,
+                (Some(Layer::Ipv6(ipv6)), Layer::Tcp(_)) => ipv6.next_header = IpProtocol::TCP,
+                (Some(Layer::Ipv6(ipv6)), Layer::Udp(_)) => ipv6.next_header = IpProtocol::UDP,
+                _ => {}
+            }
 
-```rust
-# use rust_packet::prelude::*;
-let pkt: Packet = pkt! {
-    ether! {
-        dst: "de:ad:be:ef:c0:fe".parse()?
-    }?,
-    ipv4! {
-        src: "127.0.0.1".parse()?,
-        dst: "127.0.0.2".parse()?,
-    }?,
-    udp! {
-        dport: 1337
-    }?,
-    raw! {
-        data: b"hello world!".to_vec()
-    }?,
-}.unwrap();
-```
-*/
-#[macro_export]
-macro_rules! pkt {
-    ($($layers:expr),+ $(,)?) => ({
-        || -> Result<_, PacketError> {
-            let mut pkt = Packet::new(vec![$($layers),*]);
-            pkt.update()?;
-            Ok(pkt)
-        }()
-    });
-}
+            layers.push(layer);
+        }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use hex_literal::hex;
+        let mut pkt = Packet::new(layers);
+        pkt.update()?;
 
-    use crate::layer::ether::{Ether, EtherType, MacAddress};
-    use crate::layer::ip::{IpProtocol, Ipv4};
-    use crate::layer::tcp::{Tcp, TcpFlags};
-    use crate::layer::Raw;
+        Ok(pkt)
+    }
 
-    #[test]
-    fn test_packet_read_multi_layer() {
-        // Ether / IP / TCP / "hello world"
-        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+    /// Write packet to bytes
+    /// The trailer and the FCS are appended if present
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PacketError> {
+        let mut ret = self.layers_to_bytes()?;
+        ret.extend_from_slice(&self.trailer);
+        if let Some(fcs) = self.fcs {
+            ret.extend_from_slice(&fcs.to_le_bytes());
+        }
 
-        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
-        assert_eq!(4, pkt.layers.len());
+        Ok(ret)
+    }
 
-        assert_eq!(
-            Layer::Ether(Ether {
-                dst: MacAddress([255, 255, 255, 255, 255, 255]),
-                src: MacAddress([0, 0, 0, 0, 0, 0]),
-                ether_type: EtherType::IPv4
-            }),
-            pkt.layers[0]
-        );
+    // Write the layers of the packet
+    fn layers_to_bytes(&self) -> Result<Vec<u8>, PacketError> {
+        let mut acc = BitVec::<Msb0, u8>::new();
+        for layer in &self.layers {
+            if let Layer::Raw(Raw { bit_offset, .. }) = layer {
+                let start = Packet::raw_start(acc.len(), *bit_offset)?;
+                acc.resize(start, false);
+            }
 
-        assert_eq!(
-            Layer::Ipv4(Ipv4 {
-                version: 4,
-                ihl: 5,
-                dscp: 0,
-                ecn: 0,
-                length: 51,
-                identification: 1,
-                flags: 0,
-                offset: 0,
-                ttl: 64,
-                protocol: IpProtocol::TCP,
-                checksum: 31938,
-                src: "127.0.0.1".parse().unwrap(),
-                dst: "127.0.0.1".parse().unwrap(),
-                options: vec![],
-            }),
-            pkt.layers[1]
-        );
+            acc.extend_from_bitslice(layer.to_bytes()?.view_bits::<Msb0>());
+        }
 
-        assert_eq!(
-            Layer::Tcp(Tcp {
-                sport: 20,
-                dport: 80,
-                seq: 0,
-                ack: 0,
-                offset: 5,
-                flags: TcpFlags {
-                    syn: 1,
-                    ..TcpFlags::default()
-                },
-                window: 8192,
-                checksum: 65442,
-                urgptr: 0,
-                options: vec![]
-            }),
-            pkt.layers[2]
-        );
+        // pad the last byte
+        let padding = (8 - acc.len() % 8) % 8;
+        acc.resize(acc.len() + padding, false);
 
-        assert_eq!(
-            Layer::Raw(Raw {
-                data: b"hello world".to_vec(),
-                bit_offset: 0,
-            }),
-            pkt.layers[3]
-        );
+        Ok(acc.into_vec())
     }
 
-    #[test]
-    fn test_packet_update_ipv4_tcp() {
-        // Ether / IPv4 / TCP / Raw
-        let test_data = hex!("feff2000010000000100000008004500 AAAA 0f4540008006 AAAA 91fea0ed41d0e4df0d2c005038affe14114c618c501825bc AAAA 0000474554202f646f776e6c6f61642e68746d6c20485454502f312e310d0a486f73743a207777772e657468657265616c2e636f6d0d0a557365722d4167656e743a204d6f7a696c6c612f352e30202857696e646f77733b20553b2057696e646f7773204e5420352e313b20656e2d55533b2072763a312e3629204765636b6f2f32303034303131330d0a4163636570743a20746578742f786d6c2c6170706c69636174696f6e2f786d6c2c6170706c69636174696f6e2f7868746d6c2b786d6c2c746578742f68746d6c3b713d302e392c746578742f706c61696e3b713d302e382c696d6167652f706e672c696d6167652f6a7065672c696d6167652f6769663b713d302e322c2a2f2a3b713d302e310d0a4163636570742d4c616e67756167653a20656e2d75732c656e3b713d302e350d0a4163636570742d456e636f64696e673a20677a69702c6465666c6174650d0a4163636570742d436861727365743a2049534f2d383835392d312c7574662d383b713d302e372c2a3b713d302e370d0a4b6565702d416c6976653a203330300d0a436f6e6e656374696f6e3a206b6565702d616c6976650d0a526566657265723a20687474703a2f2f7777772e657468657265616c2e636f6d2f646576656c6f706d656e742e68746d6c0d0a0d0a");
-        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
-        assert_eq!(4, pkt.layers.len());
+    // Bit position where a Raw layer of `bit_offset` starts once `bits` bits are written
+    fn raw_start(bits: usize, bit_offset: usize) -> Result<usize, PacketError> {
+        if bit_offset >= 8 {
+            return Err(LayerError::Unexpected(format!(
+                "raw bit offset {} exceeds a byte",
+                bit_offset
+            ))
+            .into());
+        }
 
-        assert_eq!(0xAAAA, pkt.ipv4().unwrap().length);
-        assert_eq!(0xAAAA, pkt.ipv4().unwrap().checksum);
-        assert_eq!(0xAAAA, pkt.tcp().unwrap().checksum);
+        if bit_offset == 0 {
+            return Ok(bits);
+        }
 
-        pkt.update().unwrap();
+        // start `bit_offset` bits into the last byte written
+        let last_byte = ((bits + 7) / 8).saturating_sub(1);
+        Ok(last_byte * 8 + bit_offset)
+    }
 
-        assert_eq!(0x0207, pkt.ipv4().unwrap().length);
-        assert_eq!(0x9010, pkt.ipv4().unwrap().checksum);
-        assert_eq!(0xa958, pkt.tcp().unwrap().checksum);
+    /// Returns the first `snaplen` bytes of the written packet, as captured with a snaplen
+    pub fn truncate_bytes(&self, snaplen: usize) -> Result<Vec<u8>, PacketError> {
+        let mut ret = self.to_bytes()?;
+        ret.truncate(snaplen);
+        Ok(ret)
     }
 
-    #[test]
-    fn test_packet_update_ipv6_tcp() {
-        // Ether / IPv6 / TCP / Raw
-        let test_data = hex!("b40c25058e13000c29c134dc86dd60000000 AAAA 0640200300de20160125fc3683174e86cb72200300de20160110000000000a1204431d76005072e11aa7e255014450183f9e AAAA 0000474554202f66617669636f6e2e69636f20485454502f312e310d0a486f73743a2069702e77656265726e65747a2e6e65740d0a557365722d4167656e743a204d6f7a696c6c612f352e30202857696e646f7773204e5420362e313b2072763a35392e3029204765636b6f2f32303130303130312046697265666f782f35392e300d0a4163636570743a20746578742f68746d6c2c6170706c69636174696f6e2f7868746d6c2b786d6c2c6170706c69636174696f6e2f786d6c3b713d302e392c2a2f2a3b713d302e380d0a4163636570742d4c616e67756167653a2064652c656e2d55533b713d302e372c656e3b713d302e330d0a4163636570742d456e636f64696e673a20677a69702c206465666c6174650d0a436f6e6e656374696f6e3a206b6565702d616c6976650d0a0d0a");
-        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
-        assert_eq!(4, pkt.layers.len());
+    /// Returns true if the packet contains a layer of type `layer_type`
+    pub fn contains(&self, layer_type: LayerType) -> bool {
+        self.layers.iter().any(|v| v.layer_type() == layer_type)
+    }
 
-        assert_eq!(0xAAAA, pkt.ipv6().unwrap().length);
-        assert_eq!(0xAAAA, pkt.tcp().unwrap().checksum);
+    /// Returns the layer types of the packet, in order
+    pub fn protocol_stack(&self) -> Vec<LayerType> {
+        self.layers.iter().map(|v| v.layer_type()).collect()
+    }
 
-        pkt.update().unwrap();
+    /// Returns true if the layer types of the packet are exactly `stack`, in order
+    pub fn stack_matches(&self, stack: &[LayerType]) -> bool {
+        self.layers.len() == stack.len()
+            && self
+                .layers
+                .iter()
+                .zip(stack.iter())
+                .all(|(layer, layer_type)| layer.layer_type() == *layer_type)
+    }
 
-        assert_eq!(0x0143, pkt.ipv6().unwrap().length);
-        assert_eq!(0x2eda, pkt.tcp().unwrap().checksum);
+    /// Returns true if the packet contains an Ipv4 layer
+    pub fn is_ipv4(&self) -> bool {
+        self.contains(LayerType::Ipv4)
     }
 
-    #[test]
-    fn test_packet_update_ipv4_udp() {
-        // Ether / IPv4 / UDP / Raw
-        let test_data = hex!("000c4182b25300d0596c404e08004500 AAAA 0a4100008011 AAAA c0a83232c0a80001ff02ff35 AAAA AAAA 002b0100000100000000000002757304706f6f6c036e7470036f72670000010001");
-        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
-        assert_eq!(4, pkt.layers.len());
+    /// Returns true if the packet contains an Ipv6 layer
+    pub fn is_ipv6(&self) -> bool {
+        self.contains(LayerType::Ipv6)
+    }
 
-        assert_eq!(0xAAAA, pkt.ipv4().unwrap().length);
-        assert_eq!(0xAAAA, pkt.ipv4().unwrap().checksum);
-        assert_eq!(0xAAAA, pkt.udp().unwrap().length);
-        assert_eq!(0xAAAA, pkt.udp().unwrap().checksum);
+    /// Returns true if the packet contains a Tcp layer
+    pub fn is_tcp(&self) -> bool {
+        self.contains(LayerType::Tcp)
+    }
 
-        pkt.update().unwrap();
+    /// Returns true if the packet contains a Udp layer
+    pub fn is_udp(&self) -> bool {
+        self.contains(LayerType::Udp)
+    }
 
-        assert_eq!(0x003d, pkt.ipv4().unwrap().length);
-        assert_eq!(0x7ceb, pkt.ipv4().unwrap().checksum);
-        assert_eq!(0x0029, pkt.udp().unwrap().length);
-        assert_eq!(0x07a9, pkt.udp().unwrap().checksum);
+    /// Returns true if the packet carries ARP
+    pub fn is_arp(&self) -> bool {
+        self.contains(LayerType::Arp)
     }
 
-    #[test]
-    fn test_packet_update_ipv6_udp() {
-        // Ether / IPv6 / UDP / Raw
-        let test_data = hex!("5475d0c90b810050568706b686dd60000000 AAAA 114020010470e5bf10011cc773ff65f5a2f720010470e5bf10960002009900c10010b4d100a1 AAAA AAAA 303e0201033011020429cdb17a020300ffcf0401040201030410300e0400020100020100040004000400301404000400a00e020460ba10f60201000201003000");
-        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
-        assert_eq!(4, pkt.layers.len());
+    /// Returns the source address of the first Ipv4 or Ipv6 layer
+    pub fn ip_src(&self) -> Option<IpAddr> {
+        self.layers.iter().find_map(|v| match v {
+            Layer::Ipv4(ipv4) => Some(IpAddr::V4(ipv4.src)),
+            Layer::Ipv6(ipv6) => Some(IpAddr::V6(ipv6.src)),
+            _ => None,
+        })
+    }
 
-        assert_eq!(0xAAAA, pkt.ipv6().unwrap().length);
-        assert_eq!(0xAAAA, pkt.udp().unwrap().length);
-        assert_eq!(0xAAAA, pkt.udp().unwrap().checksum);
+    /// Returns the destination address of the first Ipv4 or Ipv6 layer
+    pub fn ip_dst(&self) -> Option<IpAddr> {
+        self.layers.iter().find_map(|v| match v {
+            Layer::Ipv4(ipv4) => Some(IpAddr::V4(ipv4.dst)),
+            Layer::Ipv6(ipv6) => Some(IpAddr::V6(ipv6.dst)),
+            _ => None,
+        })
+    }
 
-        pkt.update().unwrap();
+    /// Returns the version field of the first Ipv4 or Ipv6 layer
+    pub fn ip_version(&self) -> Option<u8> {
+        self.layers.iter().find_map(|v| match v {
+            Layer::Ipv4(ipv4) => Some(ipv4.version),
+            Layer::Ipv6(ipv6) => Some(ipv6.version),
+            _ => None,
+        })
+    }
 
-        assert_eq!(0x0048, pkt.ipv6().unwrap().length);
-        assert_eq!(0x0048, pkt.udp().unwrap().length);
+    /// Returns the source and destination addresses of every Ip layer, outer layers first
+    pub fn ip_addresses(&self) -> Vec<IpAddr> {
+        self.layers
+            .iter()
+            .flat_map(|v| match v {
+                Layer::Ipv4(ipv4) => vec![IpAddr::V4(ipv4.src), IpAddr::V4(ipv4.dst)],
+                Layer::Ipv6(ipv6) => vec![IpAddr::V6(ipv6.src), IpAddr::V6(ipv6.dst)],
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    /// Swap the source and destination of the Ether, Ip, Tcp and Udp layers
+    ///
+    /// This produces the reverse-direction packet, `update()` is left to the caller
+    pub fn swap_endpoints(&mut self) {
+        for layer in self.layers.iter_mut() {
+            match layer {
+                Layer::Ether(ether) => std::mem::swap(&mut ether.src, &mut ether.dst),
+                Layer::Ipv4(ipv4) => std::mem::swap(&mut ipv4.src, &mut ipv4.dst),
+                Layer::Ipv6(ipv6) => std::mem::swap(&mut ipv6.src, &mut ipv6.dst),
+                Layer::Tcp(tcp) => std::mem::swap(&mut tcp.sport, &mut tcp.dport),
+                Layer::Udp(udp) => std::mem::swap(&mut udp.sport, &mut udp.dport),
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the skeleton of a response to the packet
+    ///
+    /// The endpoints are swapped, an Arp request becomes a reply from its target and the
+    /// payload following the Tcp or Udp layer is removed, as well as the trailer. The caller
+    /// fills in the response before calling `update()`.
+    pub fn response_template(&self) -> Result<Packet, PacketError> {
+        let mut ret = self.clone();
+        ret.swap_endpoints();
+        ret.trailer.clear();
+
+        let transport = ret
+            .layers
+            .iter()
+            .rposition(|v| matches!(v, Layer::Tcp(_) | Layer::Udp(_)));
+        match transport {
+            Some(i) => ret.layers.truncate(i + 1),
+            None => {
+                while let Some(Layer::Raw(_)) = ret.layers.last() {
+                    ret.layers.pop();
+                }
+            }
+        }
+
+        for layer in ret.layers.iter_mut() {
+            if let Layer::Arp(arp) = layer {
+                std::mem::swap(&mut arp.sender_mac, &mut arp.target_mac);
+                std::mem::swap(&mut arp.sender_ip, &mut arp.target_ip);
+                if arp.operation == ARP_REQUEST {
+                    arp.operation = ARP_REPLY;
+                }
+            }
+        }
+
+        if ret.layers.is_empty() {
+            return Err(
+                LayerError::Unexpected("packet has no layer to respond with".to_string()).into(),
+            );
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the type, byte offset and byte length of each layer in the written packet
+    ///
+    /// A Raw layer with a `bit_offset` starts within the last byte of the preceding layer, the
+    /// byte ranges of the two layers then share that byte
+    pub fn layer_offsets(&self) -> Result<Vec<(LayerType, usize, usize)>, PacketError> {
+        let mut bits = 0;
+        let mut ret = Vec::with_capacity(self.layers.len());
+        for layer in &self.layers {
+            if let Layer::Raw(Raw { bit_offset, .. }) = layer {
+                bits = Packet::raw_start(bits, *bit_offset)?;
+            }
+
+            let start = bits / 8;
+            bits += layer.to_bytes()?.len() * 8;
+            let end = (bits + 7) / 8;
+            ret.push((layer.layer_type(), start, end - start));
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the byte range `(start, end)` of the application payload in the written packet
+    ///
+    /// The payload follows the last Tcp or Udp header and is bounded by the length of the Ip
+    /// layer in front of it, which excludes ethernet padding and the trailer. Returns `None` if
+    /// there is no Tcp or Udp layer or the packet cannot be written.
+    pub fn transport_payload_range(&self) -> Option<(usize, usize)> {
+        let offsets = self.layer_offsets().ok()?;
+        let transport = self
+            .layers
+            .iter()
+            .rposition(|layer| matches!(layer, Layer::Tcp(_) | Layer::Udp(_)))?;
+
+        let (_, offset, len) = offsets[transport];
+        let start = offset + len;
+        let end = offsets.last().map_or(start, |(_, offset, len)| offset + len);
+
+        let ip_end = self.layers[..transport]
+            .iter()
+            .zip(&offsets)
+            .rev()
+            .find_map(|(layer, (_, offset, _))| match layer {
+                Layer::Ipv4(ipv4) => Some(offset + usize::from(ipv4.length)),
+                Layer::Ipv6(ipv6) => Some(offset + ipv6.header_len() + usize::from(ipv6.length)),
+                _ => None,
+            });
+
+        let end = match ip_end {
+            Some(ip_end) => end.min(ip_end).max(start),
+            None => end,
+        };
+
+        Some((start, end))
+    }
+
+    /// Update the packet
+    /// This is used to re-compute dynamic data such as checksums and lengths
+    pub fn update(&mut self) -> Result<(), PacketError> {
+        self.update_with(true)
+    }
+
+    /// Update the checksums of the packet
+    /// Length fields are left as-is and checksums are computed using the current lengths
+    pub fn update_checksums(&mut self) -> Result<(), PacketError> {
+        self.update_with(false)
+    }
+
+    /// Update the Ipv4, Ipv6 and Udp lengths of the packet
+    /// Checksums are left as-is
+    pub fn update_lengths(&mut self) -> Result<(), PacketError> {
+        let mut payload: Vec<u8> = Vec::new();
+
+        for layer in self.layers.iter_mut().rev() {
+            match layer {
+                Layer::Ipv4(ipv4) => ipv4.update_length_payload(&payload)?,
+                Layer::Ipv6(ipv6) => ipv6.update_length_payload(&payload)?,
+                Layer::Udp(udp) => udp.update_length_payload(&payload)?,
+                Layer::Gtpu(gtpu) => gtpu.update_length_payload(&payload)?,
+                _ => {}
+            }
+
+            let mut layer_bytes = layer.to_bytes()?;
+            layer_bytes.extend_from_slice(&payload);
+            payload = layer_bytes;
+        }
+
+        Ok(())
+    }
+
+    fn update_with(&mut self, update_lengths: bool) -> Result<(), PacketError> {
+        // Layers are updated from last to first. Some layers depend on the bytes of the
+        // following layers (lengths and checksums), `payload` holds the written bytes of the
+        // already updated following layers so each layer is only written once.
+        let mut payload: Vec<u8> = Vec::new();
+
+        for i in (0..self.layers.len()).rev() {
+            let (previous_layers, layers) = self.layers.split_at_mut(i);
+            let layer = &mut layers[0];
+
+            // Update current layer which depends on next-layers
+            if update_lengths {
+                match layer {
+                    Layer::Ipv4(ipv4) => ipv4.update_length_payload(&payload)?,
+                    Layer::Ipv6(ipv6) => ipv6.update_length_payload(&payload)?,
+                    Layer::Udp(udp) => udp.update_length_payload(&payload)?,
+                    Layer::Gtpu(gtpu) => gtpu.update_length_payload(&payload)?,
+                    _ => {}
+                }
+            }
+
+            // Update current layer which depends on the previous layer
+            match (&mut *layer, Packet::ip_layer_of(previous_layers)) {
+                (Layer::Tcp(tcp), Some(Layer::Ipv4(ipv4))) => {
+                    tcp.update_checksum_ipv4_payload(ipv4, &payload)?
+                }
+                (Layer::Tcp(tcp), Some(Layer::Ipv6(ipv6))) => {
+                    tcp.update_checksum_ipv6_payload(ipv6, &payload)?
+                }
+                (Layer::Udp(udp), Some(Layer::Ipv4(ipv4))) => {
+                    udp.update_checksum_ipv4_payload(ipv4, &payload)?
+                }
+                (Layer::Udp(udp), Some(Layer::Ipv6(ipv6))) => {
+                    udp.update_checksum_ipv6_payload(ipv6, &payload)?
+                }
+                _ => {}
+            }
+
+            // Update current layer
+            Packet::update_layer(layer, update_lengths)?;
+
+            let mut layer_bytes = layer.to_bytes()?;
+            layer_bytes.extend_from_slice(&payload);
+            payload = layer_bytes;
+        }
+
+        if self.fcs.is_some() {
+            self.fcs = Some(self.compute_fcs()?);
+        }
+
+        Ok(())
+    }
+
+    // The Ip layer of a Tcp or Udp layer following `previous_layers`, the last layer which is
+    // not an Ipv6 extension header
+    fn ip_layer_of(previous_layers: &[Layer]) -> Option<&Layer> {
+        previous_layers
+            .iter()
+            .rev()
+            .find(|v| !matches!(v, Layer::Ipv6Ext(_)))
+    }
+
+    // Update the fields of a layer which only depend on the layer itself
+    fn update_layer(layer: &mut Layer, update_lengths: bool) -> Result<(), PacketError> {
+        if update_lengths {
+            layer.update()?;
+        } else {
+            // only the checksums, computed over the layer as written
+            match layer {
+                Layer::Ipv4(ipv4) => ipv4.checksum = ipv4.compute_checksum()?,
+                Layer::Icmp(icmp) => icmp.checksum = icmp.compute_checksum()?,
+                Layer::Igmp(igmp) => igmp.checksum = igmp.compute_checksum()?,
+                Layer::Ospf(ospf) => ospf.checksum = ospf.compute_checksum()?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Advance the packet to the next one in a series
+    ///
+    /// The Ipv4 identification is incremented and the Tcp sequence number is advanced by
+    /// the length of the Tcp payload. The packet is then updated.
+    pub fn next_in_series(&mut self) -> Result<(), PacketError> {
+        if let Some(ipv4) = self.ipv4_mut() {
+            ipv4.next_identification();
+        }
+
+        if let Some(i) = self
+            .layers
+            .iter()
+            .position(|v| v.layer_type() == LayerType::Tcp)
+        {
+            let mut payload_len = 0;
+            for layer in &self.layers[i + 1..] {
+                payload_len += layer.to_bytes()?.len();
+            }
+            let payload_len = u32::try_from(payload_len).map_err(LayerError::from)?;
+
+            if let Layer::Tcp(tcp) = &mut self.layers[i] {
+                tcp.seq = tcp.seq.wrapping_add(payload_len);
+            }
+        }
+
+        self.update()
+    }
+
+    /// Returns the one's complement sum of the Tcp pseudo header, not inverted
+    ///
+    /// This is the partial checksum seeded in the checksum field for checksum offload
+    pub fn partial_checksum_tcp(&self) -> Result<u16, PacketError> {
+        let index = self
+            .layers
+            .iter()
+            .position(|v| matches!(v, Layer::Tcp(_)))
+            .ok_or_else(|| LayerError::Unexpected("packet has no tcp layer".to_string()))?;
+
+        let mut tcp_len = 0;
+        for layer in &self.layers[index..] {
+            tcp_len += layer.to_bytes()?.len();
+        }
+
+        let pseudo_header = match Packet::ip_layer_of(&self.layers[..index]) {
+            Some(Layer::Ipv4(ipv4)) => Tcp::pseudo_header_ipv4(ipv4, tcp_len)?,
+            Some(Layer::Ipv6(ipv6)) => Tcp::pseudo_header_ipv6(ipv6, tcp_len)?,
+            _ => {
+                return Err(LayerError::Unexpected(
+                    "tcp layer is not preceded by an ip layer".to_string(),
+                )
+                .into())
+            }
+        };
+
+        Ok(ones_complement_sum(&pseudo_header))
+    }
+
+    /// Returns the type, stored checksum and expected checksum of each checksummed layer
+    ///
+    /// The Ipv4, Icmp, Igmp, Ospf, Tcp and Udp layers are listed, the Tcp and Udp checksums are
+    /// computed from the preceding Ip layer, past any Ipv6 extension headers, and the following
+    /// layers. A Tcp or Udp layer which does not follow an Ip layer is not listed.
+    pub fn checksum_summary(&self) -> Result<Vec<(LayerType, u16, u16)>, PacketError> {
+        let mut ret = Vec::new();
+
+        for (i, layer) in self.layers.iter().enumerate() {
+            let following = &self.layers[i + 1..];
+            let previous = Packet::ip_layer_of(&self.layers[..i]);
+
+            let checksums = match (layer, previous) {
+                (Layer::Ipv4(ipv4), _) => Some((ipv4.checksum, ipv4.compute_checksum()?)),
+                (Layer::Icmp(icmp), _) => Some((icmp.checksum, icmp.compute_checksum()?)),
+                (Layer::Igmp(igmp), _) => Some((igmp.checksum, igmp.compute_checksum()?)),
+                (Layer::Ospf(ospf), _) => Some((ospf.checksum, ospf.compute_checksum()?)),
+                (Layer::Tcp(tcp), Some(Layer::Ipv4(ipv4))) => {
+                    Some((tcp.checksum, tcp.compute_checksum_ipv4(ipv4, following)?))
+                }
+                (Layer::Tcp(tcp), Some(Layer::Ipv6(ipv6))) => {
+                    Some((tcp.checksum, tcp.compute_checksum_ipv6(ipv6, following)?))
+                }
+                (Layer::Udp(udp), Some(Layer::Ipv4(ipv4))) => {
+                    Some((udp.checksum, udp.compute_checksum_ipv4(ipv4, following)?))
+                }
+                (Layer::Udp(udp), Some(Layer::Ipv6(ipv6))) => {
+                    Some((udp.checksum, udp.compute_checksum_ipv6(ipv6, following)?))
+                }
+                _ => None,
+            };
+
+            if let Some((stored, expected)) = checksums {
+                ret.push((layer.layer_type(), stored, expected));
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the VLAN identifiers of the packet, from outer to inner tag
+    pub fn vlan_ids(&self) -> Vec<u16> {
+        self.layers
+            .iter()
+            .filter_map(|v| match v {
+                Layer::Vlan(vlan) => Some(vlan.vid),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the priority code point of the outer VLAN tag
+    pub fn vlan_priority(&self) -> Option<u8> {
+        self.vlan().map(|vlan| vlan.pcp)
+    }
+
+    /// Set the priority code point of the outer VLAN tag, leaving the DEI and VID unchanged
+    pub fn set_vlan_priority(&mut self, pcp: u8) -> Result<(), PacketError> {
+        if pcp > 7 {
+            return Err(LayerError::Unexpected(format!("invalid vlan priority {}", pcp)).into());
+        }
+
+        let vlan = self
+            .vlan_mut()
+            .ok_or_else(|| LayerError::Unexpected("packet has no vlan layer".to_string()))?;
+        vlan.pcp = pcp;
+
+        Ok(())
+    }
+
+    /// Returns the drop eligible indicator of the outer VLAN tag
+    pub fn vlan_dei(&self) -> Option<bool> {
+        self.vlan().map(|vlan| vlan.dei != 0)
+    }
+
+    /// Set the drop eligible indicator of the outer VLAN tag, leaving the PCP and VID unchanged
+    pub fn set_vlan_dei(&mut self, dei: bool) -> Result<(), PacketError> {
+        let vlan = self
+            .vlan_mut()
+            .ok_or_else(|| LayerError::Unexpected("packet has no vlan layer".to_string()))?;
+        vlan.dei = u8::from(dei);
+
+        Ok(())
+    }
+
+    /// Decrement the TTL of the outer Ipv4 layer or the hop limit of the outer Ipv6 layer
+    ///
+    /// Returns the new value. An error is returned if the value would drop below 1, in which
+    /// case the packet should be discarded with an ICMP Time Exceeded message.
+    ///
+    /// The Ipv4 checksum is updated incrementally (RFC 1624), other fields are not recomputed
+    pub fn decrement_ttl(&mut self) -> Result<u8, PacketError> {
+        let layer = self
+            .layers
+            .iter_mut()
+            .find(|v| matches!(v, Layer::Ipv4(_) | Layer::Ipv6(_)));
+
+        match layer {
+            Some(Layer::Ipv4(ipv4)) => {
+                if ipv4.ttl <= 1 {
+                    return Err(
+                        LayerError::Unexpected(format!("ipv4 ttl {} expired", ipv4.ttl)).into(),
+                    );
+                }
+
+                // the ttl is the high byte of its 16-bit word, the protocol byte cancels out
+                let old_word = u16::from_be_bytes([ipv4.ttl, 0x00]);
+                let new_word = u16::from_be_bytes([ipv4.ttl - 1, 0x00]);
+
+                let mut data = Vec::with_capacity(6);
+                data.extend(&(!ipv4.checksum).to_be_bytes());
+                data.extend(&(!old_word).to_be_bytes());
+                data.extend(&new_word.to_be_bytes());
+
+                ipv4.ttl -= 1;
+                ipv4.checksum = !ones_complement_sum(&data);
+
+                Ok(ipv4.ttl)
+            }
+            Some(Layer::Ipv6(ipv6)) => {
+                if ipv6.hop_limit <= 1 {
+                    return Err(LayerError::Unexpected(format!(
+                        "ipv6 hop limit {} expired",
+                        ipv6.hop_limit
+                    ))
+                    .into());
+                }
+
+                ipv6.hop_limit -= 1;
+
+                Ok(ipv6.hop_limit)
+            }
+            _ => Err(LayerError::Unexpected("packet has no ip layer".to_string()).into()),
+        }
+    }
+
+    /// Build an ICMP Destination Unreachable message in response to the packet
+    ///
+    /// The message quotes the Ipv4 header of the packet and the first 8 bytes of its payload.
+    /// It is sent from `src`, the responding host or router, to the source of the packet. If
+    /// the packet starts with an Ether layer, the returned packet starts with an Ether layer
+    /// with the addresses swapped, otherwise it starts at the Ipv4 layer.
+    pub fn icmp_unreachable(&self, src: Ipv4Addr, code: u8) -> Result<Packet, PacketError> {
+        let (index, ipv4) = self
+            .layers
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| match v {
+                Layer::Ipv4(ipv4) => Some((i, ipv4)),
+                _ => None,
+            })
+            .ok_or_else(|| LayerError::Unexpected("packet has no ipv4 layer".to_string()))?;
+
+        let mut quoted = Packet::new(self.layers[index..].to_vec()).to_bytes()?;
+        quoted.truncate(ipv4.header_len() + 8);
+
+        let mut layers = Vec::with_capacity(3);
+        if let Some(Layer::Ether(ether)) = self.layers.first() {
+            layers.push(Layer::Ether(Ether {
+                dst: ether.src.clone(),
+                src: ether.dst.clone(),
+                ether_type: EtherType::IPv4,
+            }));
+        }
+
+        layers.push(Layer::Ipv4(Ipv4 {
+            version: 4,
+            ihl: 5,
+            ttl: 64,
+            protocol: IpProtocol::ICMP,
+            src,
+            dst: ipv4.src,
+            ..Ipv4::default()
+        }));
+        layers.push(Layer::Icmp(Icmp {
+            icmp_type: ICMP_DEST_UNREACHABLE,
+            code,
+            data: quoted,
+            ..Icmp::default()
+        }));
+
+        let mut pkt = Packet::new(layers);
+        pkt.update()?;
+
+        Ok(pkt)
+    }
+
+    /// Validate the layers of the packet
+    ///
+    /// Returns the validation errors reported by each layer
+    pub fn validate(&self) -> Result<Vec<ValidationError>, PacketError> {
+        let mut ret = Vec::new();
+        for layer in &self.layers {
+            match layer {
+                Layer::Ipv4(ipv4) => ret.extend(ipv4.validate()?),
+                Layer::Tcp(tcp) => ret.extend(tcp.validate()?),
+                _ => {}
+            }
+        }
+
+        if let Some(fcs) = self.fcs {
+            if fcs != self.compute_fcs()? {
+                ret.push(ValidationError::Fcs)
+            }
+        }
+
+        Ok(ret)
+    }
+
+    /// Check the packet can be written and sent on the wire
+    ///
+    /// The packet must start with a link layer, the Ip versions and header lengths must be
+    /// valid, the Ip and Udp lengths must fit their 16-bit fields and the Tcp offset must
+    /// cover the fixed header. Returns the problems found, if any.
+    pub fn is_transmittable(&self) -> Result<(), Vec<String>> {
+        let offsets = self.layer_offsets().map_err(|e| vec![e.to_string()])?;
+        let packet_len = offsets.last().map_or(0, |(_, offset, len)| offset + len);
+        let max_len = usize::from(u16::MAX);
+
+        let mut ret = Vec::new();
+
+        match self.layers.first() {
+            None => ret.push("packet has no layers".to_string()),
+            Some(Layer::Ether(_)) | Some(Layer::LinuxSll(_)) | Some(Layer::Null(_)) => {}
+            Some(layer) => {
+                ret.push(format!("first layer is {}, not a link layer", layer.name()))
+            }
+        }
+
+        for (layer, (_, offset, len)) in self.layers.iter().zip(&offsets) {
+            // written length of the layer and the following layers
+            let total_len = packet_len - offset;
+
+            match layer {
+                Layer::Ipv4(ipv4) => {
+                    if ipv4.version != 4 {
+                        ret.push(format!("ipv4 version is {}", ipv4.version));
+                    }
+                    if ipv4.ihl < 5 {
+                        ret.push(format!("ipv4 ihl {} is less than 5", ipv4.ihl));
+                    }
+                    if total_len > max_len {
+                        ret.push(format!("ipv4 length {} exceeds {}", total_len, max_len));
+                    }
+                }
+                Layer::Ipv6(ipv6) => {
+                    if ipv6.version != 6 {
+                        ret.push(format!("ipv6 version is {}", ipv6.version));
+                    }
+                    let payload_len = total_len - len;
+                    if payload_len > max_len {
+                        ret.push(format!("ipv6 payload length {} exceeds {}", payload_len, max_len));
+                    }
+                }
+                Layer::Udp(_) => {
+                    if total_len > max_len {
+                        ret.push(format!("udp length {} exceeds {}", total_len, max_len));
+                    }
+                }
+                Layer::Tcp(tcp) => {
+                    if tcp.offset < 5 {
+                        ret.push(format!("tcp offset {} is less than 5", tcp.offset));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if ret.is_empty() {
+            Ok(())
+        } else {
+            Err(ret)
+        }
+    }
+
+    /// Merge adjacent Raw layers into a single Raw layer
+    ///
+    /// The written bytes of the packet are unchanged, a Raw layer with a `bit_offset` is not
+    /// merged into the preceding one
+    pub fn coalesce_raw(&mut self) {
+        let mut layers: Vec<Layer> = Vec::with_capacity(self.layers.len());
+
+        for layer in self.layers.drain(..) {
+            if let (Some(Layer::Raw(prev)), Layer::Raw(raw)) = (layers.last_mut(), &layer) {
+                // a bit offset overwrites bits of the preceding layer
+                if raw.bit_offset == 0 {
+                    prev.data.extend_from_slice(&raw.data);
+                    continue;
+                }
+            }
+
+            layers.push(layer);
+        }
+
+        self.layers = layers;
+    }
+
+    /// Pad the packet with zero bytes so that the written packet is `size` bytes long
+    ///
+    /// The padding extends a trailing Raw layer, or is appended as a new Raw layer. The
+    /// packet is then updated so that lengths include the padding.
+    pub fn pad_to(&mut self, size: usize) -> Result<(), PacketError> {
+        let len = self.to_bytes()?.len();
+        if len > size {
+            return Err(LayerError::Unexpected(format!(
+                "packet length {} exceeds pad size {}",
+                len, size
+            ))
+            .into());
+        }
+
+        let padding = size - len;
+        if padding != 0 {
+            if let Some(Layer::Raw(raw)) = self.layers.last_mut() {
+                raw.data.resize(raw.data.len() + padding, 0x00);
+            } else {
+                self.layers.push(Layer::Raw(Raw {
+                    data: vec![0x00; padding],
+                    bit_offset: 0,
+                }));
+            }
+        }
+
+        self.update()
+    }
+
+    /// Insert an Ipv6 extension header of type `protocol` at layer `index`
+    ///
+    /// See [Ipv6::insert_ext_header](../layer/ip/ipv6/struct.Ipv6.html#method.insert_ext_header)
+    pub fn insert_ipv6_ext_header(
+        &mut self,
+        index: usize,
+        protocol: IpProtocol,
+        ext: Ipv6Ext,
+    ) -> Result<(), PacketError> {
+        Ipv6::insert_ext_header(&mut self.layers, index, protocol, ext)?;
+        Ok(())
+    }
+
+    /// Remove the Ipv6 extension header at layer `index`
+    ///
+    /// See [Ipv6::remove_ext_header](../layer/ip/ipv6/struct.Ipv6.html#method.remove_ext_header)
+    pub fn remove_ipv6_ext_header(&mut self, index: usize) -> Result<Ipv6Ext, PacketError> {
+        Ok(Ipv6::remove_ext_header(&mut self.layers, index)?)
+    }
+
+    /// Encapsulate the packet in Ether / Ipv4 / Udp / Vxlan
+    ///
+    /// The packet must start with an Ether layer. The outer lengths and checksums are updated.
+    pub fn encapsulate_vxlan(
+        &self,
+        outer_src_ip: Ipv4Addr,
+        outer_dst_ip: Ipv4Addr,
+        vni: u32,
+    ) -> Result<Packet, PacketError> {
+        if !matches!(self.layers.first(), Some(Layer::Ether(_))) {
+            return Err(LayerError::Unexpected(
+                "vxlan encapsulation requires an ether layer".to_string(),
+            )
+            .into());
+        }
+
+        let mut layers = vec![
+            Layer::Ether(Ether::default()),
+            Layer::Ipv4(Ipv4 {
+                version: 4,
+                ihl: 5,
+                ttl: 64,
+                protocol: IpProtocol::UDP,
+                src: outer_src_ip,
+                dst: outer_dst_ip,
+                ..Ipv4::default()
+            }),
+            Layer::Udp(Udp {
+                dport: VXLAN_PORT,
+                ..Udp::default()
+            }),
+            Layer::Vxlan(Vxlan {
+                vni,
+                ..Vxlan::default()
+            }),
+        ];
+        layers.extend(self.layers.iter().cloned());
+
+        let mut pkt = Packet::new(layers);
+        pkt.update()?;
+
+        Ok(pkt)
+    }
+
+    /// Split the packet into the outer layers `[0, index)` and the inner layers `[index, ..)`
+    ///
+    /// An `index` past the last layer gives an empty inner packet. Neither half is updated,
+    /// call [update](#method.update) on each before writing them.
+    pub fn split_at_layer(&self, index: usize) -> (Packet, Packet) {
+        let (outer, inner) = self.layers.split_at(index.min(self.layers.len()));
+        (Packet::new(outer.to_vec()), Packet::new(inner.to_vec()))
+    }
+}
+
+impl std::fmt::Display for Packet {
+    /// Writes the layers separated by ` / `
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, layer) in self.layers.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" / ")?;
+            }
+            write!(f, "{}", layer)?;
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_layer_packet_funcs {
+    ($layer:ident, $func:ident, $func_mut:ident, $func_for_each:ident) => {
+        /// Returns the first layer as a reference
+        pub fn $func(&self) -> Option<&crate::layer::$layer> {
+            let layer = self
+                .layers
+                .iter()
+                .find(|v| v.layer_type() == LayerType::$layer);
+
+            if let Some(Layer::$layer(layer)) = layer {
+                Some(layer)
+            } else {
+                None
+            }
+        }
+
+        /// Returns the first layer as a mutable reference
+        pub fn $func_mut(&mut self) -> Option<&mut crate::layer::$layer> {
+            let layer = self
+                .layers
+                .iter_mut()
+                .find(|v| v.layer_type() == LayerType::$layer);
+
+            if let Some(Layer::$layer(layer)) = layer {
+                Some(layer)
+            } else {
+                None
+            }
+        }
+
+        /// Applies `f` to each layer of this type, from first to last
+        pub fn $func_for_each(&mut self, mut f: impl FnMut(&mut crate::layer::$layer)) {
+            for layer in self.layers.iter_mut() {
+                if let Layer::$layer(layer) = layer {
+                    f(layer)
+                }
+            }
+        }
+    };
+}
+
+// # LAYER: Function to access layer from packet
+impl Packet {
+    impl_layer_packet_funcs!(Raw, raw, raw_mut, for_each_raw);
+    impl_layer_packet_funcs!(Ether, ether, ether_mut, for_each_ether);
+    impl_layer_packet_funcs!(LinuxSll, linux_sll, linux_sll_mut, for_each_linux_sll);
+    impl_layer_packet_funcs!(Vlan, vlan, vlan_mut, for_each_vlan);
+    impl_layer_packet_funcs!(Ipv4, ipv4, ipv4_mut, for_each_ipv4);
+    impl_layer_packet_funcs!(Ipv6, ipv6, ipv6_mut, for_each_ipv6);
+    impl_layer_packet_funcs!(Ipv6Ext, ipv6ext, ipv6ext_mut, for_each_ipv6ext);
+    impl_layer_packet_funcs!(Tcp, tcp, tcp_mut, for_each_tcp);
+    impl_layer_packet_funcs!(Udp, udp, udp_mut, for_each_udp);
+    impl_layer_packet_funcs!(Vxlan, vxlan, vxlan_mut, for_each_vxlan);
+    impl_layer_packet_funcs!(Bgp, bgp, bgp_mut, for_each_bgp);
+    impl_layer_packet_funcs!(Smb, smb, smb_mut, for_each_smb);
+    impl_layer_packet_funcs!(Ptp, ptp, ptp_mut, for_each_ptp);
+    impl_layer_packet_funcs!(WireGuard, wireguard, wireguard_mut, for_each_wireguard);
+    impl_layer_packet_funcs!(Igmp, igmp, igmp_mut, for_each_igmp);
+    impl_layer_packet_funcs!(Lldp, lldp, lldp_mut, for_each_lldp);
+    impl_layer_packet_funcs!(Avtp, avtp, avtp_mut, for_each_avtp);
+    impl_layer_packet_funcs!(NetflowV5, netflow_v5, netflow_v5_mut, for_each_netflow_v5);
+    impl_layer_packet_funcs!(MacSec, macsec, macsec_mut, for_each_macsec);
+    impl_layer_packet_funcs!(Arp, arp, arp_mut, for_each_arp);
+    impl_layer_packet_funcs!(Gtpu, gtpu, gtpu_mut, for_each_gtpu);
+    impl_layer_packet_funcs!(Ospf, ospf, ospf_mut, for_each_ospf);
+    impl_layer_packet_funcs!(Radiotap, radiotap, radiotap_mut, for_each_radiotap);
+    impl_layer_packet_funcs!(Icmp, icmp, icmp_mut, for_each_icmp);
+    impl_layer_packet_funcs!(Llc, llc, llc_mut, for_each_llc);
+    impl_layer_packet_funcs!(Bpdu, bpdu, bpdu_mut, for_each_bpdu);
+    impl_layer_packet_funcs!(Dhcpv6, dhcpv6, dhcpv6_mut, for_each_dhcpv6);
+    impl_layer_packet_funcs!(Coap, coap, coap_mut, for_each_coap);
+    impl_layer_packet_funcs!(Null, null, null_mut, for_each_null);
+    impl_layer_packet_funcs!(Radius, radius, radius_mut, for_each_radius);
+}
+
+impl std::ops::Index<LayerType> for Packet {
+    type Output = Layer;
+
+    fn index(&self, layer_type: LayerType) -> &Self::Output {
+        self.layers
+            .iter()
+            .find(|v| v.layer_type() == layer_type)
+            .unwrap_or_else(|| panic!("could not find layer: {:?}", layer_type))
+    }
+}
+
+impl std::ops::IndexMut<LayerType> for Packet {
+    fn index_mut(&mut self, layer_type: LayerType) -> &mut Self::Output {
+        self.layers
+            .iter_mut()
+            .find(|v| v.layer_type() == layer_type)
+            .unwrap_or_else(|| panic!("could not find layer: {:?}", layer_type))
+    }
+}
+
+/**
+Create a [Packet](packet/struct.Packet.html)
+
+Returns `Result<Packet, PacketError>`
+
+Example:
+
+```rust
+# use rust_packet::prelude::*;
+let pkt: Packet = pkt! {
+    ether! {
+        dst: "de:ad:be:ef:c0:fe".parse()?
+    }?,
+    ipv4! {
+        src: "127.0.0.1".parse()?,
+        dst: "127.0.0.2".parse()?,
+    }?,
+    udp! {
+        dport: 1337
+    }?,
+    raw! {
+        data: b"hello world!".to_vec()
+    }?,
+}.unwrap();
+```
+*/
+#[macro_export]
+macro_rules! pkt {
+    ($($layers:expr),+ $(,)?) => ({
+        || -> Result<_, PacketError> {
+            let mut pkt = Packet::new(vec![$($layers),*]);
+            pkt.update()?;
+            Ok(pkt)
+        }()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use rstest::*;
+
+    use crate::layer::ether::MacAddress;
+    use crate::layer::ip::checksum;
+    use crate::layer::tcp::TcpFlags;
+
+    #[test]
+    fn test_packet_read_multi_layer() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+
+        assert_eq!(
+            Layer::Ether(Ether {
+                dst: MacAddress([255, 255, 255, 255, 255, 255]),
+                src: MacAddress([0, 0, 0, 0, 0, 0]),
+                ether_type: EtherType::IPv4
+            }),
+            pkt.layers[0]
+        );
+
+        assert_eq!(
+            Layer::Ipv4(Ipv4 {
+                version: 4,
+                ihl: 5,
+                dscp: 0,
+                ecn: 0,
+                length: 51,
+                identification: 1,
+                flags: 0,
+                offset: 0,
+                ttl: 64,
+                protocol: IpProtocol::TCP,
+                checksum: 31938,
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.1".parse().unwrap(),
+                options: vec![],
+                padding: vec![],
+            }),
+            pkt.layers[1]
+        );
+
+        assert_eq!(
+            Layer::Tcp(Tcp {
+                sport: 20,
+                dport: 80,
+                seq: 0,
+                ack: 0,
+                offset: 5,
+                flags: TcpFlags {
+                    syn: 1,
+                    ..TcpFlags::default()
+                },
+                window: 8192,
+                checksum: 65442,
+                urgptr: 0,
+                options: vec![]
+            }),
+            pkt.layers[2]
+        );
+
+        assert_eq!(
+            Layer::Raw(Raw {
+                data: b"hello world".to_vec(),
+                bit_offset: 0,
+            }),
+            pkt.layers[3]
+        );
+    }
+
+    #[test]
+    fn test_packet_update_ipv4_tcp() {
+        // Ether / IPv4 / TCP / Raw
+        let test_data = hex!("feff2000010000000100000008004500 AAAA 0f4540008006 AAAA 91fea0ed41d0e4df0d2c005038affe14114c618c501825bc AAAA 0000474554202f646f776e6c6f61642e68746d6c20485454502f312e310d0a486f73743a207777772e657468657265616c2e636f6d0d0a557365722d4167656e743a204d6f7a696c6c612f352e30202857696e646f77733b20553b2057696e646f7773204e5420352e313b20656e2d55533b2072763a312e3629204765636b6f2f32303034303131330d0a4163636570743a20746578742f786d6c2c6170706c69636174696f6e2f786d6c2c6170706c69636174696f6e2f7868746d6c2b786d6c2c746578742f68746d6c3b713d302e392c746578742f706c61696e3b713d302e382c696d6167652f706e672c696d6167652f6a7065672c696d6167652f6769663b713d302e322c2a2f2a3b713d302e310d0a4163636570742d4c616e67756167653a20656e2d75732c656e3b713d302e350d0a4163636570742d456e636f64696e673a20677a69702c6465666c6174650d0a4163636570742d436861727365743a2049534f2d383835392d312c7574662d383b713d302e372c2a3b713d302e370d0a4b6565702d416c6976653a203330300d0a436f6e6e656374696f6e3a206b6565702d616c6976650d0a526566657265723a20687474703a2f2f7777772e657468657265616c2e636f6d2f646576656c6f706d656e742e68746d6c0d0a0d0a");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+
+        assert_eq!(0xAAAA, pkt.ipv4().unwrap().length);
+        assert_eq!(0xAAAA, pkt.ipv4().unwrap().checksum);
+        assert_eq!(0xAAAA, pkt.tcp().unwrap().checksum);
+
+        pkt.update().unwrap();
+
+        assert_eq!(0x0207, pkt.ipv4().unwrap().length);
+        assert_eq!(0x9010, pkt.ipv4().unwrap().checksum);
+        assert_eq!(0xa958, pkt.tcp().unwrap().checksum);
+    }
+
+    #[test]
+    fn test_packet_checksum_summary() {
+        // Ether / IPv4 / TCP / "hello world" with a wrong tcp checksum
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000 AAAA 000068656c6c6f20776f726c64");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        assert_eq!(
+            vec![
+                (LayerType::Ipv4, 0x7cc2, 0x7cc2),
+                (LayerType::Tcp, 0xaaaa, 0xffa2),
+            ],
+            pkt.checksum_summary().unwrap()
+        );
+
+        pkt.update().unwrap();
+        assert_eq!(
+            vec![
+                (LayerType::Ipv4, 0x7cc2, 0x7cc2),
+                (LayerType::Tcp, 0xffa2, 0xffa2),
+            ],
+            pkt.checksum_summary().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_packet_checksum_summary_ospf() {
+        // Ether / IP / OSPF Hello
+        let test_data = hex!("01005e000005001122334455 0800 45c0004000010000015916f6c0a80101e0000005 0201002c0a0000010000000130f300000000000000000000 ffffff00000a020100000028c0a8010100000000");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.ospf_mut().unwrap().checksum = 0;
+
+        assert_eq!(
+            vec![
+                (LayerType::Ipv4, 0x16f6, 0x16f6),
+                (LayerType::Ospf, 0x0000, 0x30f3),
+            ],
+            pkt.checksum_summary().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_packet_update_ipv6_tcp() {
+        // Ether / IPv6 / TCP / Raw
+        let test_data = hex!("b40c25058e13000c29c134dc86dd60000000 AAAA 0640200300de20160125fc3683174e86cb72200300de20160110000000000a1204431d76005072e11aa7e255014450183f9e AAAA 0000474554202f66617669636f6e2e69636f20485454502f312e310d0a486f73743a2069702e77656265726e65747a2e6e65740d0a557365722d4167656e743a204d6f7a696c6c612f352e30202857696e646f7773204e5420362e313b2072763a35392e3029204765636b6f2f32303130303130312046697265666f782f35392e300d0a4163636570743a20746578742f68746d6c2c6170706c69636174696f6e2f7868746d6c2b786d6c2c6170706c69636174696f6e2f786d6c3b713d302e392c2a2f2a3b713d302e380d0a4163636570742d4c616e67756167653a2064652c656e2d55533b713d302e372c656e3b713d302e330d0a4163636570742d456e636f64696e673a20677a69702c206465666c6174650d0a436f6e6e656374696f6e3a206b6565702d616c6976650d0a0d0a");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+
+        assert_eq!(0xAAAA, pkt.ipv6().unwrap().length);
+        assert_eq!(0xAAAA, pkt.tcp().unwrap().checksum);
+
+        pkt.update().unwrap();
+
+        assert_eq!(0x0143, pkt.ipv6().unwrap().length);
+        assert_eq!(0x2eda, pkt.tcp().unwrap().checksum);
+    }
+
+    #[test]
+    fn test_packet_update_ipv4_udp() {
+        // Ether / IPv4 / UDP / Raw
+        let test_data = hex!("000c4182b25300d0596c404e08004500 AAAA 0a4100008011 AAAA c0a83232c0a80001ff02ff35 AAAA AAAA 002b0100000100000000000002757304706f6f6c036e7470036f72670000010001");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+
+        assert_eq!(0xAAAA, pkt.ipv4().unwrap().length);
+        assert_eq!(0xAAAA, pkt.ipv4().unwrap().checksum);
+        assert_eq!(0xAAAA, pkt.udp().unwrap().length);
+        assert_eq!(0xAAAA, pkt.udp().unwrap().checksum);
+
+        pkt.update().unwrap();
+
+        assert_eq!(0x003d, pkt.ipv4().unwrap().length);
+        assert_eq!(0x7ceb, pkt.ipv4().unwrap().checksum);
+        assert_eq!(0x0029, pkt.udp().unwrap().length);
+        assert_eq!(0x07a9, pkt.udp().unwrap().checksum);
+    }
+
+    #[test]
+    fn test_packet_update_ipv6_udp() {
+        // Ether / IPv6 / UDP / Raw
+        let test_data = hex!("5475d0c90b810050568706b686dd60000000 AAAA 114020010470e5bf10011cc773ff65f5a2f720010470e5bf10960002009900c10010b4d100a1 AAAA AAAA 303e0201033011020429cdb17a020300ffcf0401040201030410300e0400020100020100040004000400301404000400a00e020460ba10f60201000201003000");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+
+        assert_eq!(0xAAAA, pkt.ipv6().unwrap().length);
+        assert_eq!(0xAAAA, pkt.udp().unwrap().length);
+        assert_eq!(0xAAAA, pkt.udp().unwrap().checksum);
+
+        pkt.update().unwrap();
+
+        assert_eq!(0x0048, pkt.ipv6().unwrap().length);
+        assert_eq!(0x0048, pkt.udp().unwrap().length);
         assert_eq!(0x15b3, pkt.udp().unwrap().checksum);
     }
+
+    #[test]
+    fn test_packet_encapsulate_vxlan() {
+        let inner = pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse()?
+            }?,
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::UDP,
+                src: "10.0.0.1".parse()?,
+                dst: "10.0.0.2".parse()?,
+            }?,
+            crate::udp! {
+                dport: 1337
+            }?,
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }?,
+        }
+        .unwrap();
+
+        let outer = inner
+            .encapsulate_vxlan(
+                "192.168.0.1".parse().unwrap(),
+                "192.168.0.2".parse().unwrap(),
+                42,
+            )
+            .unwrap();
+
+        // Read back the encapsulated packet
+        let pkt = Packet::from_bytes(outer.to_bytes().unwrap().as_ref()).unwrap();
+        assert_eq!(8, pkt.layers.len());
+        assert_eq!(VXLAN_PORT, pkt.udp().unwrap().dport);
+        assert_eq!(42, pkt.vxlan().unwrap().vni);
+
+        // Decapsulate, the inner packet is preserved
+        let decapsulated = Packet::new(pkt.layers[4..].to_vec());
+        assert_eq!(inner.to_bytes().unwrap(), decapsulated.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_split_at_layer() {
+        // Ether / IP / UDP / VXLAN / Ether / IP / UDP / "hello world!"
+        let inner = pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse()?
+            }?,
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::UDP,
+                src: "10.0.0.1".parse()?,
+                dst: "10.0.0.2".parse()?,
+            }?,
+            crate::udp! {
+                dport: 1337
+            }?,
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }?,
+        }
+        .unwrap();
+        let test_data = inner
+            .encapsulate_vxlan(
+                "192.168.0.1".parse().unwrap(),
+                "192.168.0.2".parse().unwrap(),
+                42,
+            )
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        let (mut outer, mut decapsulated) = pkt.split_at_layer(4);
+
+        assert_eq!(4, outer.layers.len());
+        assert_eq!(42, outer.vxlan().unwrap().vni);
+        assert_eq!(pkt.layers[..4], outer.layers[..]);
+        assert_eq!(pkt.layers[4..], decapsulated.layers[..]);
+
+        outer.update().unwrap();
+        decapsulated.update().unwrap();
+        assert_eq!(inner.to_bytes().unwrap(), decapsulated.to_bytes().unwrap());
+
+        let (all, none) = pkt.split_at_layer(10);
+        assert_eq!(pkt.layers, all.layers);
+        assert!(none.layers.is_empty());
+    }
+
+    #[test]
+    fn test_packet_read_bgp() {
+        // Ether / IP / TCP / BGP OPEN
+        let test_data = hex!("ffffffffffff00000000000008004500004500010000400600007f0000017f00000100b300b300000000000000005002000000000000ffffffffffffffffffffffffffffffff001d0104fde900b4c0a8000100");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+        assert_eq!(29, pkt.bgp().unwrap().length);
+    }
+
+    #[test]
+    fn test_packet_coalesce_raw() {
+        let mut pkt = Packet::new(vec![
+            Layer::Ether(Ether::default()),
+            Layer::Raw(Raw {
+                data: b"hello ".to_vec(),
+                bit_offset: 0,
+            }),
+            Layer::Raw(Raw {
+                data: b"world".to_vec(),
+                bit_offset: 0,
+            }),
+        ]);
+        let expected_bytes = pkt.to_bytes().unwrap();
+
+        pkt.coalesce_raw();
+
+        assert_eq!(2, pkt.layers.len());
+        assert_eq!(
+            Layer::Raw(Raw {
+                data: b"hello world".to_vec(),
+                bit_offset: 0,
+            }),
+            pkt.layers[1]
+        );
+        assert_eq!(expected_bytes, pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_coalesce_raw_bit_offset() {
+        // 4-bit wide header, written in the high bits of a byte
+        let layers = vec![
+            Layer::Raw(Raw {
+                data: vec![0xa0],
+                bit_offset: 0,
+            }),
+            Layer::Raw(Raw {
+                data: vec![0xbc, 0xde],
+                bit_offset: 4,
+            }),
+        ];
+        let mut pkt = Packet::new(layers.clone());
+        let expected_bytes = pkt.to_bytes().unwrap();
+
+        pkt.coalesce_raw();
+
+        assert_eq!(layers, pkt.layers);
+        assert_eq!(expected_bytes, pkt.to_bytes().unwrap());
+    }
+
+    #[rstest(size, expected_raw_len,
+        case::pad(128, 86),
+        case::exact(42, 0),
+
+        #[should_panic(expected = "packet length 42 exceeds pad size 41")]
+        case::too_large(41, 0),
+    )]
+    fn test_packet_pad_to(size: usize, expected_raw_len: usize) {
+        let mut pkt = pkt! {
+            crate::ether! {}?,
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::UDP,
+            }?,
+            crate::udp! {
+                dport: 1337
+            }?,
+        }
+        .unwrap();
+
+        pkt.pad_to(size).unwrap();
+
+        assert_eq!(size, pkt.to_bytes().unwrap().len());
+        assert_eq!(expected_raw_len, pkt.raw().map_or(0, |raw| raw.data.len()));
+        assert_eq!((size - 14) as u16, pkt.ipv4().unwrap().length);
+        assert_eq!((size - 34) as u16, pkt.udp().unwrap().length);
+    }
+
+    #[test]
+    fn test_packet_remove_ipv6_ext_header() {
+        // Ether / IPv6 / Hop-by-Hop / UDP
+        let test_data = hex!("ffffffffffff00000000000086dd6000000000100040000000000000000000000000000000010000000000000000000000000000000111000000000000000035003500080000");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+        assert_eq!(IpProtocol::UDP, pkt.ipv6ext().unwrap().next_header);
+
+        pkt.remove_ipv6_ext_header(2).unwrap();
+
+        assert_eq!(3, pkt.layers.len());
+        assert_eq!(IpProtocol::UDP, pkt.ipv6().unwrap().next_header);
+        assert_eq!(8, pkt.ipv6().unwrap().length);
+    }
+
+    #[test]
+    fn test_packet_insert_ipv6_ext_header_update() {
+        // Ether / IPv6 / UDP / "hello", the udp checksum is not set
+        let test_data = hex!("ffffffffffff00000000000086dd60000000000d11400000000000000000000000000000000100000000000000000000000000000001 04d2162e000d0000 68656c6c6f");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.insert_ipv6_ext_header(2, IpProtocol::HOPOPT, Ipv6Ext::default())
+            .unwrap();
+        pkt.update().unwrap();
+
+        assert_eq!(
+            vec![
+                LayerType::Ether,
+                LayerType::Ipv6,
+                LayerType::Ipv6Ext,
+                LayerType::Udp,
+                LayerType::Raw
+            ],
+            pkt.protocol_stack()
+        );
+        assert_eq!(21, pkt.ipv6().unwrap().length);
+
+        // the pseudo header is taken from the ipv6 layer preceding the extension header
+        assert_eq!(0xa100, pkt.udp().unwrap().checksum);
+        assert_eq!(
+            vec![(LayerType::Udp, 0xa100, 0xa100)],
+            pkt.checksum_summary().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_packet_validate() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(Vec::<ValidationError>::new(), pkt.validate().unwrap());
+
+        pkt.tcp_mut().unwrap().flags.reserved = 0b101;
+        assert_eq!(vec![ValidationError::Reserved], pkt.validate().unwrap());
+    }
+
+    #[test]
+    fn test_packet_is_transmittable() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(Ok(()), pkt.is_transmittable());
+
+        // the ethernet layer is missing
+        pkt.layers.remove(0);
+        assert_eq!(
+            Err(vec!["first layer is IPv4, not a link layer".to_string()]),
+            pkt.is_transmittable()
+        );
+
+        let pkt = Packet::new(vec![
+            Layer::Ether(Ether::default()),
+            Layer::Ipv4(Ipv4::default()),
+            Layer::Tcp(Tcp::default()),
+        ]);
+        assert_eq!(
+            Err(vec![
+                "ipv4 version is 0".to_string(),
+                "ipv4 ihl 0 is less than 5".to_string(),
+                "tcp offset 0 is less than 5".to_string(),
+            ]),
+            pkt.is_transmittable()
+        );
+    }
+
+    #[test]
+    fn test_packet_next_in_series() {
+        let mut pkt = Packet::new(vec![
+            Layer::Ether(Ether::default()),
+            Layer::Ipv4(Ipv4 {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::TCP,
+                ..Ipv4::default()
+            }),
+            Layer::Tcp(Tcp {
+                offset: 5,
+                seq: 1000,
+                ..Tcp::default()
+            }),
+            Layer::Raw(Raw {
+                data: b"hello".to_vec(),
+                bit_offset: 0,
+            }),
+        ]);
+        pkt.update().unwrap();
+
+        for i in 1..=3 {
+            pkt.next_in_series().unwrap();
+
+            assert_eq!(i, pkt.ipv4().unwrap().identification);
+            assert_eq!(1000 + 5 * u32::from(i), pkt.tcp().unwrap().seq);
+        }
+    }
+
+    #[test]
+    fn test_packet_update_checksums() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.ipv4_mut().unwrap().length = 1000;
+
+        pkt.update_checksums().unwrap();
+
+        assert_eq!(1000, pkt.ipv4().unwrap().length);
+        assert_eq!(Vec::<ValidationError>::new(), pkt.validate().unwrap());
+
+        // tcp checksum is unchanged, the segment was not modified
+        assert_eq!(0xffa2, pkt.tcp().unwrap().checksum);
+    }
+
+    #[test]
+    fn test_packet_update_checksums_icmp() {
+        // Ether / IP / ICMP echo request / "hello"
+        let test_data = hex!("ffffffffffff0000000000000800450000210001000040017cd97f0000017f0000010800b42b0001000168656c6c6f");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.ipv4_mut().unwrap().checksum = 0;
+        pkt.icmp_mut().unwrap().checksum = 0;
+
+        pkt.update_checksums().unwrap();
+
+        assert_eq!(0x7cd9, pkt.ipv4().unwrap().checksum);
+        assert_eq!(0xb42b, pkt.icmp().unwrap().checksum);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_partial_checksum_tcp() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        let partial = pkt.partial_checksum_tcp().unwrap();
+        assert_eq!(0xfe27, partial);
+
+        // seeding the partial sum in place of the pseudo header gives the full checksum
+        let mut segment = test_data[34..].to_vec();
+        segment[16..18].copy_from_slice(&partial.to_be_bytes());
+        assert_eq!(0xffa2, crate::layer::ip::checksum(&segment).unwrap());
+    }
+
+    #[test]
+    fn test_packet_partial_checksum_tcp_no_tcp() {
+        let pkt = Packet::from_spec("Ether/IP/UDP").unwrap();
+
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Unexpected(
+                "packet has no tcp layer".to_string()
+            ))),
+            pkt.partial_checksum_tcp()
+        );
+    }
+
+    #[rstest(bit_offset, expected,
+        case::aligned(0, vec![0xa0, 0xbc, 0xde]),
+        case::nibble(4, vec![0xab, 0xcd, 0xe0]),
+        case::one_bit(1, vec![0xde, 0x6f, 0x00]),
+    )]
+    fn test_packet_to_bytes_raw_bit_offset(bit_offset: usize, expected: Vec<u8>) {
+        // 4-bit wide header, written in the high bits of a byte
+        let header = Layer::Raw(Raw {
+            data: vec![0xa0],
+            bit_offset: 0,
+        });
+
+        let pkt = Packet::new(vec![
+            header,
+            Layer::Raw(Raw {
+                data: vec![0xbc, 0xde],
+                bit_offset,
+            }),
+        ]);
+
+        assert_eq!(expected, pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_to_bytes_raw_bit_offset_invalid() {
+        let pkt = Packet::new(vec![Layer::Raw(Raw {
+            data: vec![0xbc],
+            bit_offset: 8,
+        })]);
+
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Unexpected(
+                "raw bit offset 8 exceeds a byte".to_string()
+            ))),
+            pkt.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_packet_update_lengths() {
+        // Ether / IP / UDP / "hello"
+        let test_data = hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.ipv4_mut().unwrap().checksum = 0xbad;
+        pkt.raw_mut().unwrap().data = b"hello world".to_vec();
+
+        pkt.update_lengths().unwrap();
+
+        assert_eq!(39, pkt.ipv4().unwrap().length);
+        assert_eq!(19, pkt.udp().unwrap().length);
+        assert_eq!(0xbad, pkt.ipv4().unwrap().checksum);
+        assert_eq!(0, pkt.udp().unwrap().checksum);
+    }
+
+    #[test]
+    fn test_packet_vlan_ids() {
+        // Ether / 802.1ad / 802.1Q / ARP
+        let test_data = hex!("ffffffffffff00000000000088a8006481000c8c0806 0001080006040001 001122334455 0a000001 000000000000 0a000002");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+        assert!(pkt.ether().unwrap().is_vlan_tagged());
+
+        assert_eq!(vec![100, 3212], pkt.vlan_ids());
+    }
+
+    #[test]
+    fn test_packet_vlan_dei() {
+        // Ether / 802.1ad / 802.1Q / ARP
+        let test_data = hex!("ffffffffffff00000000000088a8 a064 8100 1c8c 0806 0001080006040001 001122334455 0a000001 000000000000 0a000002");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(Some(false), pkt.vlan_dei());
+
+        pkt.set_vlan_dei(true).unwrap();
+        assert_eq!(Some(true), pkt.vlan_dei());
+
+        // PCP 5, DEI 1, VID 100 on the outer tag, the inner tag is unchanged
+        let vlan = pkt.vlan().unwrap();
+        assert_eq!(5, vlan.pcp);
+        assert_eq!(100, vlan.vid);
+        assert_eq!(
+            hex!("ffffffffffff00000000000088a8 b064 8100 1c8c 0806 0001080006040001 001122334455 0a000001 000000000000 0a000002").to_vec(),
+            pkt.to_bytes().unwrap()
+        );
+
+        pkt.set_vlan_dei(false).unwrap();
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+
+        let mut pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002")).unwrap();
+        assert_eq!(None, pkt.vlan_dei());
+        assert!(pkt.set_vlan_dei(true).is_err());
+    }
+
+    #[test]
+    fn test_packet_vlan_priority() {
+        // Ether / 802.1ad / 802.1Q / ARP
+        let test_data = hex!("ffffffffffff00000000000088a8 1064 8100 0c8c 0806 0001080006040001 001122334455 0a000001 000000000000 0a000002");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(Some(0), pkt.vlan_priority());
+
+        pkt.set_vlan_priority(5).unwrap();
+        assert_eq!(Some(5), pkt.vlan_priority());
+
+        // PCP 5, DEI 1, VID 100 on the outer tag, the inner tag is unchanged
+        let vlan = pkt.vlan().unwrap();
+        assert_eq!(1, vlan.dei);
+        assert_eq!(100, vlan.vid);
+        assert_eq!(
+            hex!("ffffffffffff00000000000088a8 b064 8100 0c8c 0806 0001080006040001 001122334455 0a000001 000000000000 0a000002").to_vec(),
+            pkt.to_bytes().unwrap()
+        );
+
+        assert!(pkt.set_vlan_priority(8).is_err());
+
+        let mut pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002")).unwrap();
+        assert_eq!(None, pkt.vlan_priority());
+        assert!(pkt.set_vlan_priority(5).is_err());
+    }
+
+    #[test]
+    fn test_packet_decrement_ttl_ipv4() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(63, pkt.decrement_ttl().unwrap());
+
+        // the incremental checksum matches a full recompute
+        let mut updated = pkt.clone();
+        updated.ipv4_mut().unwrap().checksum = 0;
+        updated.update().unwrap();
+        assert_eq!(0x7dc2, pkt.ipv4().unwrap().checksum);
+        assert_eq!(updated, pkt);
+
+        pkt.ipv4_mut().unwrap().ttl = 1;
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Unexpected(
+                "ipv4 ttl 1 expired".to_string()
+            ))),
+            pkt.decrement_ttl()
+        );
+        assert_eq!(1, pkt.ipv4().unwrap().ttl);
+    }
+
+    #[test]
+    fn test_packet_for_each_ipv4() {
+        // Ether / IP / IP / UDP, IP-in-IP
+        let mut pkt = pkt! {
+            crate::ether! {}.unwrap(),
+            crate::ipv4! {
+                protocol: IpProtocol::IPIP,
+                ttl: 64,
+            }
+            .unwrap(),
+            crate::ipv4! {
+                protocol: IpProtocol::UDP,
+                ttl: 10,
+            }
+            .unwrap(),
+            crate::udp! {}.unwrap(),
+        }
+        .unwrap();
+
+        let mut count = 0;
+        pkt.for_each_ipv4(|ipv4| {
+            ipv4.ttl -= 1;
+            count += 1;
+        });
+
+        assert_eq!(2, count);
+        let ttls: Vec<u8> = pkt
+            .layers
+            .iter()
+            .filter_map(|v| match v {
+                Layer::Ipv4(ipv4) => Some(ipv4.ttl),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![63, 9], ttls);
+
+        // no layer of the type, `f` is not called
+        pkt.for_each_tcp(|_tcp| unreachable!());
+    }
+
+    #[test]
+    fn test_packet_decrement_ttl_ipv6() {
+        let mut pkt = pkt! {
+            crate::ipv6! {
+                hop_limit: 2
+            }
+            .unwrap(),
+        }
+        .unwrap();
+
+        assert_eq!(1, pkt.decrement_ttl().unwrap());
+        assert_eq!(1, pkt.ipv6().unwrap().hop_limit);
+        assert!(pkt.decrement_ttl().is_err());
+
+        let mut pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002")).unwrap();
+        assert!(pkt.decrement_ttl().is_err());
+    }
+
+    #[test]
+    fn test_packet_icmp_unreachable() {
+        let pkt = pkt! {
+            crate::ether! {
+                dst: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                src: MacAddress([0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb]),
+            }
+            .unwrap(),
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                ttl: 64,
+                protocol: IpProtocol::UDP,
+                src: "10.0.0.1".parse().unwrap(),
+                dst: "10.0.0.2".parse().unwrap(),
+            }
+            .unwrap(),
+            crate::udp! {
+                dport: 1337
+            }
+            .unwrap(),
+            crate::raw! {
+                data: b"hello world".to_vec()
+            }
+            .unwrap(),
+        }
+        .unwrap();
+
+        // host unreachable, sent by a router on the path
+        let response = pkt
+            .icmp_unreachable(Ipv4Addr::new(10, 0, 0, 254), 1)
+            .unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Icmp],
+            response.protocol_stack()
+        );
+
+        let ether = response.ether().unwrap();
+        assert_eq!(MacAddress([0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb]), ether.dst);
+        assert_eq!(MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]), ether.src);
+        assert_eq!(EtherType::IPv4, ether.ether_type);
+
+        let ipv4 = response.ipv4().unwrap();
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 254), ipv4.src);
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 1), ipv4.dst);
+        assert_eq!(Vec::<ValidationError>::new(), response.validate().unwrap());
+
+        // the ip header and the udp header are quoted
+        let icmp = response.icmp().unwrap();
+        assert_eq!(ICMP_DEST_UNREACHABLE, icmp.icmp_type);
+        assert_eq!(1, icmp.code);
+        assert_eq!(pkt.to_bytes().unwrap()[14..42].to_vec(), icmp.data);
+        assert_eq!(0, checksum(&icmp.to_bytes().unwrap()).unwrap());
+
+        // port unreachable, sent by the destination, without a link layer
+        let response = Packet::new(pkt.layers[1..].to_vec())
+            .icmp_unreachable(Ipv4Addr::new(10, 0, 0, 2), 3)
+            .unwrap();
+        assert_eq!(
+            vec![LayerType::Ipv4, LayerType::Icmp],
+            response.protocol_stack()
+        );
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 2), response.ipv4().unwrap().src);
+        assert_eq!(3, response.icmp().unwrap().code);
+
+        let pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002")).unwrap();
+        assert!(pkt.icmp_unreachable(Ipv4Addr::new(10, 0, 0, 2), 3).is_err());
+    }
+
+    #[test]
+    fn test_packet_layer_offsets() {
+        let pkt = pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse().unwrap()
+            }.unwrap(),
+            crate::ipv4! {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.2".parse().unwrap(),
+            }.unwrap(),
+            crate::udp! {
+                dport: 1337
+            }.unwrap(),
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }.unwrap(),
+        }
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                (LayerType::Ether, 0, 14),
+                (LayerType::Ipv4, 14, 20),
+                (LayerType::Udp, 34, 8),
+                (LayerType::Raw, 42, 12),
+            ],
+            pkt.layer_offsets().unwrap()
+        );
+    }
+
+    #[rstest(bit_offset, expected,
+        case::aligned(0, vec![(LayerType::Raw, 0, 1), (LayerType::Raw, 1, 2)]),
+        case::nibble(4, vec![(LayerType::Raw, 0, 1), (LayerType::Raw, 0, 3)]),
+    )]
+    fn test_packet_layer_offsets_raw_bit_offset(
+        bit_offset: usize,
+        expected: Vec<(LayerType, usize, usize)>,
+    ) {
+        // 4-bit wide header, written in the high bits of a byte
+        let pkt = Packet::new(vec![
+            Layer::Raw(Raw {
+                data: vec![0xa0],
+                bit_offset: 0,
+            }),
+            Layer::Raw(Raw {
+                data: vec![0xbc, 0xde],
+                bit_offset,
+            }),
+        ]);
+
+        assert_eq!(expected, pkt.layer_offsets().unwrap());
+    }
+
+    #[test]
+    fn test_packet_transport_payload_range() {
+        let pkt = pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse().unwrap()
+            }.unwrap(),
+            crate::ipv4! {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.2".parse().unwrap(),
+            }.unwrap(),
+            crate::udp! {
+                dport: 1337
+            }.unwrap(),
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }.unwrap(),
+        }
+        .unwrap();
+
+        assert_eq!(Some((42, 54)), pkt.transport_payload_range());
+        let bytes = pkt.to_bytes().unwrap();
+        assert_eq!(b"hello world!", &bytes[42..54]);
+
+        // Ether / IP / UDP / "hi" with 4 bytes of ethernet padding
+        let test_data = hex!("ffffffffffff0000000000000800 4500001e0001000040110000 7f0000017f000001 04d2162e000a0000 6869 00000000");
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(Some((42, 44)), pkt.transport_payload_range());
+
+        let pkt = Packet::new(vec![crate::ether! {}.unwrap()]);
+        assert_eq!(None, pkt.transport_payload_range());
+    }
+
+    #[rstest(input,
+        // Ether / PTP Sync
+        case::ether(&hex!("011b19000000001b19000001 88f7 0002002c00000200000000000000000000000000001b19fffe0000010001123400fd000060a1b2c31a2b3c4d")),
+        // Ether / IPv4 / UDP 319 / PTP Sync
+        case::udp(&hex!("011b19000000001b19000001 0800 4500004800010000401100007f0000017f000001 013f013f00340000 0002002c00000200000000000000000000000000001b19fffe0000010001123400fd000060a1b2c31a2b3c4d")),
+    )]
+    fn test_packet_read_ptp(input: &[u8]) {
+        let pkt = Packet::from_bytes(input).unwrap();
+
+        let ptp = pkt.ptp().unwrap();
+        assert_eq!(0x1234, ptp.sequence_id);
+        assert_eq!([0x00, 0x1b, 0x19, 0xff, 0xfe, 0x00, 0x00, 0x01], ptp.clock_identity);
+    }
+
+    #[test]
+    fn test_packet_read_igmp() {
+        // Ether / IP / IGMPv3 report
+        let test_data = hex!("ffffffffffff00000000000008004500003800000000010200 00c0a80001e0000016 22008a9d00000002 01000002e1010101c0a80001c0a80002 02000001e10202020a000001");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        let records = pkt.igmp().unwrap().group_records();
+        assert_eq!(2, records.len());
+        assert_eq!("225.1.1.1".parse::<Ipv4Addr>().unwrap(), records[0].multicast_address);
+    }
+
+    #[test]
+    fn test_packet_read_lldp() {
+        // Ether / LLDP / padding
+        let test_data = hex!("0180c200000e00112233445588cc 0207040011223344550405056574683006020078 0000 000000000000");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        let lldp = pkt.lldp().unwrap();
+        assert_eq!(Some(hex!("001122334455").as_ref()), lldp.chassis_id());
+        assert_eq!(Some(b"eth0".as_ref()), lldp.port_id());
+        assert_eq!(vec![0; 6], pkt.raw().unwrap().data);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_gtpu() {
+        // Ether / IP / UDP / GTP-U / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800 4500005700010000401166930a0000010a000002 0868086800430000 30ff003312345678 450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![
+                LayerType::Ether,
+                LayerType::Ipv4,
+                LayerType::Udp,
+                LayerType::Gtpu,
+                LayerType::Ipv4,
+                LayerType::Tcp,
+                LayerType::Raw
+            ],
+            pkt.protocol_stack()
+        );
+        assert_eq!(0x12345678, pkt.gtpu().unwrap().teid);
+        assert_eq!(80, pkt.tcp().unwrap().dport);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+
+        // the gtpu length covers the inner packet
+        let mut updated = pkt.clone();
+        updated.gtpu_mut().unwrap().length = 0;
+        updated.update().unwrap();
+        assert_eq!(0x33, updated.gtpu().unwrap().length);
+    }
+
+    #[test]
+    fn test_packet_read_ospf() {
+        // Ether / IP / OSPF Hello
+        let test_data = hex!("01005e000005001122334455 0800 45c0004000010000015916f6c0a80101e0000005 0201002c0a0000010000000130f300000000000000000000 ffffff00000a020100000028c0a8010100000000");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Ospf],
+            pkt.protocol_stack()
+        );
+
+        let ospf = pkt.ospf().unwrap();
+        assert_eq!(crate::layer::ospf::OSPF_HELLO, ospf.ospf_type);
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 1), ospf.router_id);
+        assert_eq!(Ipv4Addr::new(0, 0, 0, 1), ospf.area_id);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+
+        let mut updated = pkt.clone();
+        updated.ospf_mut().unwrap().checksum = 0;
+        updated.update().unwrap();
+        assert_eq!(pkt, updated);
+    }
+
+    #[test]
+    fn test_packet_read_bpdu() {
+        // 802.3 / LLC / STP Configuration BPDU / padding
+        let test_data = hex!("0180c2000000001122334466 0026 424203 0000 00 00 00 8000001122334455 00000004 8000001122334466 8001 0000 1400 0200 0f00 0000000000000000");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Llc, LayerType::Bpdu, LayerType::Raw],
+            pkt.protocol_stack()
+        );
+        assert_eq!(EtherType::Length(38), pkt.ether().unwrap().ether_type);
+
+        let config = pkt.bpdu().unwrap().config.as_ref().unwrap();
+        assert_eq!(0x8000001122334455, config.root_id);
+        assert_eq!(4, config.root_path_cost);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_llc_length_512() {
+        // 802.3 of 512 bytes / LLC / data, the length was once shadowed by the PUP ether type
+        let mut test_data = hex!("0180c2000000001122334466 0200 f0f003").to_vec();
+        test_data.resize(14 + 512, 0xaa);
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Llc, LayerType::Raw],
+            pkt.protocol_stack()
+        );
+        assert_eq!(EtherType::Length(512), pkt.ether().unwrap().ether_type);
+        assert_eq!(test_data, pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_dhcpv6() {
+        // Ether / IPv6 / UDP / DHCPv6 Solicit
+        let test_data = hex!("3333000100020800 27fe8f95 86dd 60000000002c1101 fe800000000000000a0027fffefe8f95 ff020000000000000000000000010002 02220223002ca1b2 01100874 0001000e000100011c39cf88080027fe8f95 000600040017 0018 000800020000");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv6, LayerType::Udp, LayerType::Dhcpv6],
+            pkt.protocol_stack()
+        );
+
+        let dhcpv6 = pkt.dhcpv6().unwrap();
+        assert_eq!(crate::layer::dhcpv6::DHCPV6_SOLICIT, dhcpv6.msg_type);
+        assert_eq!(0x100874, dhcpv6.transaction_id);
+        assert_eq!(crate::layer::dhcpv6::DHCPV6_OPTION_CLIENTID, dhcpv6.options[0].code);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_coap() {
+        // Ether / IP / UDP / CoAP GET /sensors/temp
+        let test_data = hex!("ffffffffffff000000000000 0800 4500002f00010000401100007f0000017f000001 d9031633001b0000 420104d2abcdb773656e736f72730474656d70");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp, LayerType::Coap],
+            pkt.protocol_stack()
+        );
+
+        let coap = pkt.coap().unwrap();
+        assert_eq!(crate::layer::coap::COAP_GET, coap.code);
+        assert_eq!(0x04d2, coap.message_id);
+        assert_eq!("sensors/temp", coap.uri_path());
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_coap_response() {
+        // Ether / IP / UDP / CoAP ACK 2.05 Content "22.5", sent from the CoAP port
+        let test_data = hex!("ffffffffffff000000000000 0800 4500002700010000401100007f0000017f000001 1633d90300130000 624504d2abcdff32322e35");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp, LayerType::Coap],
+            pkt.protocol_stack()
+        );
+
+        let coap = pkt.coap().unwrap();
+        assert_eq!(crate::layer::coap::COAP_ACKNOWLEDGEMENT, coap.msg_type);
+        assert_eq!(0x45, coap.code);
+        assert_eq!(0x04d2, coap.message_id);
+        assert_eq!(b"22.5".to_vec(), coap.payload);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[rstest(link_type, input, expected,
+        case::ethernet(LinkType::Ethernet, &hex!("ffffffffffff0000000000000800 450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"), vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp, LayerType::Raw]),
+        case::raw_ipv4(LinkType::Raw, &hex!("450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"), vec![LayerType::Ipv4, LayerType::Udp, LayerType::Raw]),
+        case::raw_ipv6(LinkType::Raw, &hex!("60000000000d11400000000000000000000000000000000100000000000000000000000000000001 04d2162e000d0000 68656c6c6f"), vec![LayerType::Ipv6, LayerType::Udp, LayerType::Raw]),
+        case::null_little_endian(LinkType::Null, &hex!("02000000 450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"), vec![LayerType::Null, LayerType::Ipv4, LayerType::Udp, LayerType::Raw]),
+        case::null_big_endian(LinkType::Null, &hex!("00000002 450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"), vec![LayerType::Null, LayerType::Ipv4, LayerType::Udp, LayerType::Raw]),
+        case::null_ipv6(LinkType::Null, &hex!("1e000000 60000000000d11400000000000000000000000000000000100000000000000000000000000000001 04d2162e000d0000 68656c6c6f"), vec![LayerType::Null, LayerType::Ipv6, LayerType::Udp, LayerType::Raw]),
+        case::null_unknown_family(LinkType::Null, &hex!("07000000 68656c6c6f"), vec![LayerType::Null, LayerType::Raw]),
+        case::ieee80211(LinkType::Ieee80211, &hex!("08020000ffffffffffff"), vec![LayerType::Raw]),
+    )]
+    fn test_packet_from_bytes_for_link(
+        link_type: LinkType,
+        input: &[u8],
+        expected: Vec<LayerType>,
+    ) {
+        let pkt = Packet::from_bytes_for_link(input, link_type).unwrap();
+        assert_eq!(expected, pkt.protocol_stack());
+        assert_eq!(input.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[rstest(input, expected,
+        case::ipv4(&hex!("02000000 450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"), LayerType::Ipv4),
+        case::ipv6(&hex!("1e000000 60000000000d11400000000000000000000000000000000100000000000000000000000000000001 04d2162e000d0000 68656c6c6f"), LayerType::Ipv6),
+    )]
+    fn test_packet_read_null(input: &[u8], expected: LayerType) {
+        // Null / IP / UDP / Raw, as captured on lo0
+        let pkt = Packet::from_bytes_as(input, LayerType::Null).unwrap();
+        assert_eq!(
+            vec![LayerType::Null, expected, LayerType::Udp, LayerType::Raw],
+            pkt.protocol_stack()
+        );
+
+        assert_eq!(5678, pkt.udp().unwrap().dport);
+        assert_eq!(b"hello".to_vec(), pkt.raw().unwrap().data);
+        assert_eq!(input.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_radius() {
+        // Ether / IP / UDP / RADIUS Access-Request
+        let test_data = hex!("ffffffffffff000000000000 0800 4500003d00010000401100007f0000017f000001 d903071400290000 012a00210f403f9473978057bd83d5cb98f4227a 01077374657665 0406c0a80001");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp, LayerType::Radius],
+            pkt.protocol_stack()
+        );
+
+        let radius = pkt.radius().unwrap();
+        assert_eq!(crate::layer::radius::RADIUS_ACCESS_REQUEST, radius.code);
+        assert_eq!(0x2a, radius.identifier);
+        assert_eq!(
+            crate::layer::radius::RADIUS_ATTR_USER_NAME,
+            radius.attributes[0].attr_type
+        );
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_radius_response() {
+        // Ether / IP / UDP / RADIUS Access-Accept, sent from the authentication port
+        let test_data = hex!("ffffffffffff000000000000 0800 4500003000010000401100007f0000017f000001 0714d903001c0000 022a00140f403f9473978057bd83d5cb98f4227a");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp, LayerType::Radius],
+            pkt.protocol_stack()
+        );
+
+        let radius = pkt.radius().unwrap();
+        assert_eq!(crate::layer::radius::RADIUS_ACCESS_ACCEPT, radius.code);
+        assert_eq!(0x2a, radius.identifier);
+        assert!(radius.attributes.is_empty());
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_macsec() {
+        // Ether / MACsec with SCI
+        let test_data = hex!("ffffffffffff001122334455 88e5 2c000000002a0011223344550001 aabbccdd");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(2, pkt.layers.len());
+
+        let macsec = pkt.macsec().unwrap();
+        assert_eq!(42, macsec.packet_number);
+        assert_eq!(Some(0x0011223344550001), macsec.sci);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_read_netflow_v5() {
+        // Ether / IP / UDP / NetFlow v5 with three records
+        let test_data = hex!("ffffffffffff0000000000000800450000c4000100004011 7c26 7f0000017f00000107d30807"
+            "00b00000 00050003000010005f000000000000000000000100000000"
+            "0a0000010a00010100000000000100020000000a000003e8000001000000020003e80050001806000000000018180000"
+            "0a0000020a00010200000000000100020000000a000003e8000001000000020003e90050001806000000000018180000"
+            "0a0000030a00010300000000000100020000000a000003e8000001000000020003ea0050001806000000000018180000");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(4, pkt.layers.len());
+
+        let netflow = pkt.netflow_v5().unwrap();
+        assert_eq!(3, netflow.count);
+        assert_eq!(
+            vec![
+                ("10.0.0.1".parse().unwrap(), "10.0.1.1".parse().unwrap()),
+                ("10.0.0.2".parse().unwrap(), "10.0.1.2".parse().unwrap()),
+                ("10.0.0.3".parse().unwrap(), "10.0.1.3".parse().unwrap()),
+            ],
+            netflow
+                .records
+                .iter()
+                .map(|record| (record.src, record.dst))
+                .collect::<Vec<(Ipv4Addr, Ipv4Addr)>>()
+        );
+    }
+
+    #[test]
+    fn test_packet_read_avtp() {
+        // Ether / AVTP
+        let test_data = hex!("91e0f000fe00001122334455 22f0 02812a000011223344550001010203040210006000040000aabbccdd");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        assert_eq!(
+            [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x00, 0x01],
+            pkt.avtp().unwrap().stream_id
+        );
+        assert_eq!(2, pkt.layers.len());
+    }
+
+    #[test]
+    fn test_packet_predicates() {
+        let pkt = pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse().unwrap()
+            }.unwrap(),
+            crate::ipv4! {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.2".parse().unwrap(),
+            }.unwrap(),
+            crate::udp! {
+                dport: 1337
+            }.unwrap(),
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }.unwrap(),
+        }
+        .unwrap();
+
+        assert!(pkt.contains(LayerType::Raw));
+        assert!(pkt.is_ipv4());
+        assert!(pkt.is_udp());
+        assert!(!pkt.is_ipv6());
+        assert!(!pkt.is_tcp());
+        assert!(!pkt.is_arp());
+
+        // Ether / ARP
+        let pkt = Packet::from_bytes(hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002").as_ref()).unwrap();
+        assert!(pkt.is_arp());
+    }
+
+    #[test]
+    fn test_packet_protocol_stack() {
+        // README example packet
+        let pkt = pkt! {
+            crate::ether! {
+                dst: "de:ad:be:ef:c0:fe".parse().unwrap()
+            }.unwrap(),
+            crate::ipv4! {
+                src: "127.0.0.1".parse().unwrap(),
+                dst: "127.0.0.2".parse().unwrap(),
+            }.unwrap(),
+            crate::udp! {
+                dport: 1337
+            }.unwrap(),
+            crate::raw! {
+                data: b"hello world!".to_vec()
+            }.unwrap(),
+        }
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                LayerType::Ether,
+                LayerType::Ipv4,
+                LayerType::Udp,
+                LayerType::Raw
+            ],
+            pkt.protocol_stack()
+        );
+
+        assert!(pkt.stack_matches(&[
+            LayerType::Ether,
+            LayerType::Ipv4,
+            LayerType::Udp,
+            LayerType::Raw
+        ]));
+        assert!(!pkt.stack_matches(&[LayerType::Ether, LayerType::Ipv4, LayerType::Udp]));
+        assert!(!pkt.stack_matches(&[
+            LayerType::Ether,
+            LayerType::Ipv4,
+            LayerType::Tcp,
+            LayerType::Raw
+        ]));
+    }
+
+    #[test]
+    fn test_packet_ip_addresses() {
+        // Ether / IP / IP / UDP
+        let pkt = pkt! {
+            crate::ether! {}?,
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::IPENCAP,
+                src: "192.168.0.1".parse()?,
+                dst: "192.168.0.2".parse()?,
+            }?,
+            crate::ipv4! {
+                version: 4,
+                ihl: 5,
+                protocol: IpProtocol::UDP,
+                src: "10.0.0.1".parse()?,
+                dst: "10.0.0.2".parse()?,
+            }?,
+            crate::udp! {
+                dport: 1337
+            }?,
+        }
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                "192.168.0.1".parse::<IpAddr>().unwrap(),
+                "192.168.0.2".parse().unwrap(),
+                "10.0.0.1".parse().unwrap(),
+                "10.0.0.2".parse().unwrap(),
+            ],
+            pkt.ip_addresses()
+        );
+
+        let pkt = Packet::from_bytes(&hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002")).unwrap();
+        assert!(pkt.ip_addresses().is_empty());
+    }
+
+    #[rstest(input, expected_src, expected_dst, expected_version,
+        case::ipv4(
+            &hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64"),
+            Some("127.0.0.1".parse().unwrap()),
+            Some("127.0.0.1".parse().unwrap()),
+            Some(4),
+        ),
+        case::ipv6(
+            &hex!("ffffffffffff00000000000086dd6000000000100040000000000000000000000000000000010000000000000000000000000000000111000000000000000035003500080000"),
+            Some("::1".parse().unwrap()),
+            Some("::1".parse().unwrap()),
+            Some(6),
+        ),
+        case::no_ip(
+            &hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002"),
+            None,
+            None,
+            None,
+        ),
+    )]
+    fn test_packet_ip_addr(
+        input: &[u8],
+        expected_src: Option<IpAddr>,
+        expected_dst: Option<IpAddr>,
+        expected_version: Option<u8>,
+    ) {
+        let pkt = Packet::from_bytes(input).unwrap();
+        assert_eq!(expected_src, pkt.ip_src());
+        assert_eq!(expected_dst, pkt.ip_dst());
+        assert_eq!(expected_version, pkt.ip_version());
+    }
+
+    #[test]
+    fn test_packet_swap_endpoints() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000200140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.swap_endpoints();
+
+        let ether = pkt.ether().unwrap();
+        assert_eq!(MacAddress([0xff; 6]), ether.src);
+        assert_eq!(MacAddress([0x00; 6]), ether.dst);
+
+        let ipv4 = pkt.ipv4().unwrap();
+        assert_eq!(Ipv4Addr::new(127, 0, 0, 2), ipv4.src);
+        assert_eq!(Ipv4Addr::new(127, 0, 0, 1), ipv4.dst);
+
+        let tcp = pkt.tcp().unwrap();
+        assert_eq!(80, tcp.sport);
+        assert_eq!(20, tcp.dport);
+    }
+
+    #[test]
+    fn test_packet_response_template_arp() {
+        let request = Packet::new(vec![
+            crate::ether! {
+                dst: MacAddress([0xff; 6]),
+                src: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                ether_type: EtherType::ARP,
+            }
+            .unwrap(),
+            crate::arp! {
+                sender_mac: MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]),
+                sender_ip: Ipv4Addr::new(10, 0, 0, 1),
+                target_ip: Ipv4Addr::new(10, 0, 0, 2),
+            }
+            .unwrap(),
+        ]);
+
+        let reply = request.response_template().unwrap();
+        assert_eq!(vec![LayerType::Ether, LayerType::Arp], reply.protocol_stack());
+        assert_eq!(MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]), reply.ether().unwrap().dst);
+
+        let arp = reply.arp().unwrap();
+        assert_eq!(crate::layer::arp::ARP_REPLY, arp.operation);
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 2), arp.sender_ip);
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 1), arp.target_ip);
+        assert_eq!(MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]), arp.target_mac);
+    }
+
+    #[test]
+    fn test_packet_read_arp() {
+        // Ether / ARP request with padding, who has 10.0.0.2, tell 10.0.0.1
+        let test_data = hex!("ffffffffffff001122334455 0806 0001080006040001 001122334455 0a000001 000000000000 0a000002 000000000000000000000000000000000000");
+
+        let request = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Arp, LayerType::Raw],
+            request.protocol_stack()
+        );
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 2), request.arp().unwrap().target_ip);
+        assert_eq!(test_data.to_vec(), request.to_bytes().unwrap());
+
+        // the padding is not part of the reply
+        let reply = request.response_template().unwrap();
+        assert_eq!(vec![LayerType::Ether, LayerType::Arp], reply.protocol_stack());
+
+        let arp = reply.arp().unwrap();
+        assert_eq!(crate::layer::arp::ARP_REPLY, arp.operation);
+        assert_eq!(Ipv4Addr::new(10, 0, 0, 2), arp.sender_ip);
+        assert_eq!(MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]), arp.target_mac);
+    }
+
+    #[test]
+    fn test_packet_response_template_udp() {
+        // Ether / IP / UDP / "hello"
+        let test_data = hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000204d2162e000d000068656c6c6f");
+
+        let request = Packet::from_bytes(test_data.as_ref()).unwrap();
+        let mut response = request.response_template().unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp],
+            response.protocol_stack()
+        );
+
+        let ipv4 = response.ipv4().unwrap();
+        assert_eq!(Ipv4Addr::new(127, 0, 0, 2), ipv4.src);
+        assert_eq!(Ipv4Addr::new(127, 0, 0, 1), ipv4.dst);
+
+        let udp = response.udp().unwrap();
+        assert_eq!(5678, udp.sport);
+        assert_eq!(1234, udp.dport);
+
+        // the request is left unchanged
+        assert_eq!(test_data.to_vec(), request.to_bytes().unwrap());
+
+        response.layers.push(crate::raw! { data: b"world!".to_vec() }.unwrap());
+        response.update().unwrap();
+        assert_eq!(14, response.udp().unwrap().length);
+    }
+
+    #[rstest(input, expected,
+        case::ipv4_tcp(
+            &hex!("450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64"),
+            vec![LayerType::Ipv4, LayerType::Tcp, LayerType::Raw],
+        ),
+        case::ipv6_udp(
+            &hex!("60000000000c114000000000000000000000000000000001000000000000000000000000000000010035003500 0c0000deadbeef"),
+            vec![LayerType::Ipv6, LayerType::Udp, LayerType::Raw],
+        ),
+
+        #[should_panic(expected = "unsupported ip version 5")]
+        case::bad_version(&hex!("5000"), vec![]),
+        #[should_panic(expected = "no data to read ip version")]
+        case::empty(&[], vec![]),
+    )]
+    fn test_packet_from_ip_bytes(input: &[u8], expected: Vec<LayerType>) {
+        let pkt = Packet::from_ip_bytes(input).unwrap();
+
+        assert_eq!(expected, pkt.protocol_stack());
+        assert_eq!(input.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[rstest(unknown, expected,
+        case::raw(
+            UnknownHandling::Raw,
+            Ok(vec![LayerType::Ether, LayerType::Raw]),
+        ),
+        case::stop(UnknownHandling::Stop, Ok(vec![LayerType::Ether])),
+        case::error(
+            UnknownHandling::Error,
+            Err(PacketError::LayerError(LayerError::Parse(
+                "unsupported ether type Unknown(34997)".to_string()
+            ))),
+        ),
+    )]
+    fn test_packet_from_bytes_with_unknown(
+        unknown: UnknownHandling,
+        expected: Result<Vec<LayerType>, PacketError>,
+    ) {
+        // Ether / local experimental ether type
+        let test_data = hex!("ffffffffffff000000000000 88b5 68656c6c6f");
+
+        let pkt = Packet::from_bytes_with_unknown(test_data.as_ref(), unknown);
+        assert_eq!(expected, pkt.map(|v| v.protocol_stack()));
+    }
+
+    #[test]
+    fn test_packet_from_bytes_unknown_ether_type() {
+        let test_data = hex!("ffffffffffff000000000000 88b5 68656c6c6f");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(EtherType::Unknown(0x88b5), pkt.ether().unwrap().ether_type);
+        assert_eq!(b"hello".to_vec(), pkt.raw().unwrap().data);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_from_bytes_with_max_payload() {
+        // Ether / IP / UDP / 4096 bytes of payload
+        let mut test_data = hex!("ffffffffffff000000000000 0800 4500101c0001000040110000 7f0000017f000001 04d2162e10080000").to_vec();
+        test_data.resize(test_data.len() + 4096, 0xaa);
+
+        let pkt = Packet::from_bytes_with_max_payload(&test_data, 4096).unwrap();
+        assert_eq!(4096, pkt.raw().unwrap().data.len());
+
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Parse(
+                "raw payload of 4096 bytes exceeds the maximum of 64".to_string()
+            ))),
+            Packet::from_bytes_with_max_payload(&test_data, 64)
+        );
+
+        // headers are read regardless of the maximum
+        let pkt = Packet::from_bytes_with_max_payload(&test_data[..42], 0).unwrap();
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Udp],
+            pkt.protocol_stack()
+        );
+    }
+
+    #[rstest(input,
+        case::empty(&[]),
+        case::short_ether(&hex!("ffffffffffff0000")),
+        case::ipv4_options_cut_off(&hex!("ffffffffffff000000000000 0800 4f0000330001000040067cc27f0000017f000001 0000")),
+        case::tcp_offset_zero(&hex!("ffffffffffff000000000000 0800 450000280001000040067cc27f0000017f000001 00140050000000000000000000022000ffa20000")),
+        case::ptp_length_zero(&hex!("ffffffffffff000000000000 88f7 00020000 00000000000000000000000000000000000000000000000000000000")),
+    )]
+    fn test_packet_from_bytes_malformed(input: &[u8]) {
+        assert!(Packet::from_bytes(input).is_err());
+    }
+
+    #[rstest(input, expected,
+        case::bgp_truncated(&hex!("ffffffffffff000000000000 0800 4500003000010000400600007f0000017f000001 001400b3000000000000000050022000ffa20000 ffffffffffffffff"), LayerType::Tcp),
+        case::bgp_length_zero(&hex!("ffffffffffff000000000000 0800 4500003b00010000400600007f0000017f000001 00b30050000000000000000050022000ffa20000 ffffffffffffffffffffffffffffffff 0000 01"), LayerType::Tcp),
+        case::vxlan_truncated(&hex!("ffffffffffff000000000000 0800 4500001f00010000401100007f0000017f000001 04d212b5000b0000 080000"), LayerType::Udp),
+    )]
+    fn test_packet_from_bytes_port_layer_fallback(input: &[u8], expected: LayerType) {
+        // a payload on a known port which is not that protocol is read as Raw
+        let pkt = Packet::from_bytes(input).unwrap();
+
+        assert_eq!(
+            vec![LayerType::Ether, LayerType::Ipv4, expected, LayerType::Raw],
+            pkt.protocol_stack()
+        );
+        assert_eq!(input.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[rstest(input,
+        case::with_preamble(&hex!("55555555555555d5 ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64")),
+        case::without_preamble(&hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64")),
+    )]
+    fn test_packet_from_bytes_skip_preamble(input: &[u8]) {
+        let pkt = Packet::from_bytes_skip_preamble(input).unwrap();
+
+        assert_eq!(4, pkt.layers.len());
+        assert_eq!(MacAddress([0xff; 6]), pkt.ether().unwrap().dst);
+        assert_eq!(b"hello world".to_vec(), pkt.raw().unwrap().data);
+    }
+
+    #[test]
+    fn test_packet_from_bytes_skip_preamble_no_sfd() {
+        // a preamble without the SFD is read as the start of the frame
+        let test_data = hex!("555555555555555500000000 0800 450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes_skip_preamble(test_data.as_ref()).unwrap();
+
+        assert_eq!(MacAddress([0x55; 6]), pkt.ether().unwrap().dst);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_from_bytes_truncated_tcp_options() {
+        // Ether / IP / TCP with an offset of 8, the 12 option bytes are cut off
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f000001 00140050000000000000000080022000ffa20000");
+
+        let pkt = Packet::from_bytes_truncated(test_data.as_ref()).unwrap();
+        assert_eq!(vec![LayerType::Ether, LayerType::Ipv4, LayerType::Tcp], pkt.protocol_stack());
+
+        let tcp = pkt.tcp().unwrap();
+        assert_eq!(80, tcp.dport);
+        assert_eq!(8, tcp.offset);
+        assert!(tcp.options.is_empty());
+        assert!(tcp.options_truncated().unwrap());
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+    }
+
+    #[rstest(snaplen, expected_stack, expected_raw, expected_read_err,
+        case::tcp_cut_off(
+            40,
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Raw],
+            &hex!("001400500000"),
+            true,
+        ),
+        case::ipv4_cut_off(
+            20,
+            vec![LayerType::Ether, LayerType::Raw],
+            &hex!("450000330001"),
+            true,
+        ),
+        case::payload_cut_off(
+            58,
+            vec![LayerType::Ether, LayerType::Ipv4, LayerType::Tcp, LayerType::Raw],
+            b"hell",
+            false,
+        ),
+    )]
+    fn test_packet_truncate_bytes(
+        snaplen: usize,
+        expected_stack: Vec<LayerType>,
+        expected_raw: &[u8],
+        expected_read_err: bool,
+    ) {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        let truncated = pkt.truncate_bytes(snaplen).unwrap();
+        assert_eq!(test_data[..snaplen].to_vec(), truncated);
+
+        assert_eq!(expected_read_err, Packet::from_bytes(&truncated).is_err());
+
+        let pkt = Packet::from_bytes_truncated(&truncated).unwrap();
+        assert_eq!(expected_stack, pkt.protocol_stack());
+        assert_eq!(expected_raw.to_vec(), pkt.raw().unwrap().data);
+        assert_eq!(truncated, pkt.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_packet_truncate_bytes_past_end() {
+        let test_data = hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert_eq!(test_data.to_vec(), pkt.truncate_bytes(1500).unwrap());
+
+        assert!(Packet::from_bytes_truncated(&test_data[..10]).is_err());
+    }
+
+    #[test]
+    fn test_packet_display() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+
+        assert_eq!(
+            "Ether 00:00:00:00:00:00 > ff:ff:ff:ff:ff:ff type=IPv4 / \
+             IPv4 127.0.0.1 > 127.0.0.1 proto=TCP len=51 / \
+             TCP 20 > 80 flags=S seq=0 ack=0 win=8192 / \
+             Raw len=11",
+            pkt.to_string()
+        );
+
+        let pkt = Packet::new(vec![Layer::Vxlan(Vxlan::default())]);
+        assert_eq!("VXLAN", pkt.to_string());
+    }
+
+    #[test]
+    fn test_packet_from_spec() {
+        let pkt = Packet::from_spec("Ether/IP/UDP/Raw").unwrap();
+
+        assert_eq!(4, pkt.layers.len());
+        assert_eq!(
+            vec![
+                LayerType::Ether,
+                LayerType::Ipv4,
+                LayerType::Udp,
+                LayerType::Raw
+            ],
+            pkt.layers.iter().map(|v| v.layer_type()).collect::<Vec<_>>()
+        );
+        assert_eq!(IpProtocol::UDP, pkt.ipv4().unwrap().protocol);
+        assert_eq!(28, pkt.ipv4().unwrap().length);
+        assert_eq!(Vec::<ValidationError>::new(), pkt.validate().unwrap());
+
+        // The written packet reads back to the same layers, the empty Raw layer aside
+        let bytes = pkt.to_bytes().unwrap();
+        let read_pkt = Packet::from_bytes(&bytes).unwrap();
+        assert_eq!(pkt.layers[..3], read_pkt.layers[..]);
+    }
+
+    #[rstest(spec,
+        case("Ether/IP/Foo"),
+        case("Ether//TCP"),
+    )]
+    fn test_packet_from_spec_invalid(spec: &str) {
+        assert!(Packet::from_spec(spec).is_err());
+    }
+
+    #[test]
+    fn test_packet_from_bytes_all() {
+        let mut input = Vec::new();
+        for dport in 1..=3 {
+            let pkt = pkt! {
+                crate::ether! {}.unwrap(),
+                crate::ipv4! {
+                    version: 4,
+                    ihl: 5,
+                    protocol: IpProtocol::UDP,
+                }.unwrap(),
+                crate::udp! {
+                    dport: dport
+                }.unwrap(),
+                crate::raw! {
+                    data: b"hello".to_vec()
+                }.unwrap(),
+            }
+            .unwrap();
+
+            input.extend(pkt.to_bytes().unwrap());
+        }
+
+        let pkts = Packet::from_bytes_all(&input).unwrap();
+        assert_eq!(3, pkts.len());
+        for (i, pkt) in pkts.iter().enumerate() {
+            assert_eq!(i as u16 + 1, pkt.udp().unwrap().dport);
+            assert_eq!(b"hello".to_vec(), pkt.raw().unwrap().data);
+        }
+
+        // Trailing malformed bytes
+        input.extend(&[0xaa, 0xbb, 0xcc]);
+        let err = Packet::from_bytes_all(&input).unwrap_err();
+        match err {
+            PacketError::LayerError(LayerError::Parse(msg)) => {
+                assert!(msg.starts_with("malformed packet at offset 141"), "{}", msg)
+            }
+            _ => panic!("expected parse error"),
+        }
+    }
 }
@@ -0,0 +1,74 @@
+/*!
+Packet serialization to pcap records
+*/
+use super::{Packet, PacketError};
+use crate::layer::LayerError;
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Length of the pcap record header
+pub const PCAP_RECORD_HEADER_LEN: usize = 16;
+
+impl Packet {
+    /// Write the packet as a pcap record, a record header followed by the packet bytes
+    ///
+    /// The header fields are little endian with a microsecond timestamp, matching a pcap
+    /// file with the `0xa1b2c3d4` magic number written in little endian.
+    pub fn to_pcap_record(&self, ts: SystemTime) -> Result<Vec<u8>, PacketError> {
+        let ts = ts
+            .duration_since(UNIX_EPOCH)
+            .map_err(|_| LayerError::Unexpected("timestamp before unix epoch".to_string()))?;
+        let ts_sec = u32::try_from(ts.as_secs()).map_err(LayerError::from)?;
+        let ts_usec = ts.subsec_micros();
+
+        let data = self.to_bytes()?;
+        let len = u32::try_from(data.len()).map_err(LayerError::from)?;
+
+        let mut ret = Vec::with_capacity(PCAP_RECORD_HEADER_LEN + data.len());
+        ret.extend_from_slice(&ts_sec.to_le_bytes());
+        ret.extend_from_slice(&ts_usec.to_le_bytes());
+        ret.extend_from_slice(&len.to_le_bytes()); // captured length
+        ret.extend_from_slice(&len.to_le_bytes()); // original length
+        ret.extend(data);
+
+        Ok(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+    use std::time::Duration;
+
+    #[test]
+    fn test_packet_to_pcap_record() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        let ts = UNIX_EPOCH + Duration::new(1_600_000_000, 123_456_789);
+
+        let record = pkt.to_pcap_record(ts).unwrap();
+
+        assert_eq!(PCAP_RECORD_HEADER_LEN + test_data.len(), record.len());
+        assert_eq!(1_600_000_000u32.to_le_bytes(), record[0..4]);
+        assert_eq!(123_456u32.to_le_bytes(), record[4..8]);
+        assert_eq!((test_data.len() as u32).to_le_bytes(), record[8..12]);
+        assert_eq!((test_data.len() as u32).to_le_bytes(), record[12..16]);
+        assert_eq!(test_data.to_vec(), record[16..]);
+    }
+
+    #[test]
+    fn test_packet_to_pcap_record_before_epoch() {
+        let pkt = Packet::new(vec![]);
+        let ts = UNIX_EPOCH - Duration::from_secs(1);
+
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Unexpected(
+                "timestamp before unix epoch".to_string()
+            ))),
+            pkt.to_pcap_record(ts)
+        );
+    }
+}
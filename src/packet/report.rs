@@ -0,0 +1,167 @@
+/*!
+Report of the fields changed by a packet update
+*/
+use super::{FieldKind, Packet, PacketError};
+use crate::layer::{Layer, LayerType};
+
+/// A checksum or length field changed by an update
+#[derive(Debug, PartialEq)]
+pub struct FieldChange {
+    /// Index of the layer in the packet
+    pub index: usize,
+    pub layer_type: LayerType,
+    pub field: FieldKind,
+    pub old: u16,
+    pub new: u16,
+}
+
+/// Fields changed by `Packet::update_report`
+#[derive(Debug, PartialEq, Default)]
+pub struct UpdateReport {
+    pub changes: Vec<FieldChange>,
+}
+
+impl UpdateReport {
+    /// Returns true if the update did not change any field
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+// Checksum and length fields re-computed by an update of `layer`
+fn tracked_fields(layer: &Layer) -> Vec<(FieldKind, u16)> {
+    match layer {
+        Layer::Ipv4(ipv4) => vec![
+            (FieldKind::Length, ipv4.length),
+            (FieldKind::Checksum, ipv4.checksum),
+        ],
+        Layer::Ipv6(ipv6) => vec![(FieldKind::Length, ipv6.length)],
+        Layer::Tcp(tcp) => vec![(FieldKind::Checksum, tcp.checksum)],
+        Layer::Udp(udp) => vec![
+            (FieldKind::Length, udp.length),
+            (FieldKind::Checksum, udp.checksum),
+        ],
+        Layer::Icmp(icmp) => vec![(FieldKind::Checksum, icmp.checksum)],
+        Layer::Igmp(igmp) => vec![(FieldKind::Checksum, igmp.checksum)],
+        Layer::Ospf(ospf) => vec![
+            (FieldKind::Length, ospf.length),
+            (FieldKind::Checksum, ospf.checksum),
+        ],
+        Layer::Gtpu(gtpu) => vec![(FieldKind::Length, gtpu.length)],
+        Layer::Radius(radius) => vec![(FieldKind::Length, radius.length)],
+        // one length per TLV
+        Layer::Lldp(lldp) => lldp
+            .tlvs
+            .iter()
+            .map(|v| (FieldKind::Length, v.length))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+impl Packet {
+    /// Update the packet, returning the checksum and length fields which changed
+    pub fn update_report(&mut self) -> Result<UpdateReport, PacketError> {
+        let before: Vec<_> = self.layers.iter().map(tracked_fields).collect();
+
+        self.update()?;
+
+        let mut report = UpdateReport::default();
+        for (index, (layer, before)) in self.layers.iter().zip(before).enumerate() {
+            for ((field, old), (_field, new)) in before.into_iter().zip(tracked_fields(layer)) {
+                if old != new {
+                    report.changes.push(FieldChange {
+                        index,
+                        layer_type: layer.layer_type(),
+                        field,
+                        old,
+                        new,
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_packet_update_report() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert!(pkt.update_report().unwrap().is_empty());
+
+        pkt.ipv4_mut().unwrap().checksum = 0;
+
+        assert_eq!(
+            UpdateReport {
+                changes: vec![FieldChange {
+                    index: 1,
+                    layer_type: LayerType::Ipv4,
+                    field: FieldKind::Checksum,
+                    old: 0,
+                    new: 0x7cc2,
+                }],
+            },
+            pkt.update_report().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_packet_update_report_length() {
+        // Ether / IP / UDP / "hello"
+        let test_data = hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        pkt.raw_mut().unwrap().data = b"hello world".to_vec();
+
+        let report = pkt.update_report().unwrap();
+
+        let changed: Vec<_> = report
+            .changes
+            .iter()
+            .map(|v| (v.index, v.field))
+            .collect();
+        assert_eq!(
+            vec![
+                (1, FieldKind::Length),
+                (1, FieldKind::Checksum),
+                (2, FieldKind::Length),
+                (2, FieldKind::Checksum),
+            ],
+            changed
+        );
+        assert_eq!(19, report.changes[2].new);
+    }
+
+    #[test]
+    fn test_packet_update_report_lldp() {
+        // Ether / LLDP, chassis ID, port ID, TTL and end TLVs / padding
+        let test_data = hex!("0180c200000e00112233445588cc 0207040011223344550405056574683006020078 0000 000000000000");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert!(pkt.update_report().unwrap().is_empty());
+
+        pkt.lldp_mut().unwrap().tlvs[0].value = hex!("04001122").to_vec();
+
+        assert_eq!(
+            UpdateReport {
+                changes: vec![FieldChange {
+                    index: 1,
+                    layer_type: LayerType::Lldp,
+                    field: FieldKind::Length,
+                    old: 7,
+                    new: 4,
+                }],
+            },
+            pkt.update_report().unwrap()
+        );
+    }
+}
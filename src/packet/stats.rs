@@ -0,0 +1,158 @@
+/*!
+Capture statistics accumulated from packets
+*/
+use super::{Packet, PacketError};
+use crate::layer::ether::EtherType;
+use crate::layer::ip::IpProtocol;
+use crate::layer::Layer;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/**
+Per-protocol counters of the observed packets
+
+```rust
+# use rust_packet::packet::CaptureStats;
+let stats = CaptureStats::default();
+assert_eq!(0, stats.packets);
+```
+*/
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CaptureStats {
+    /// Number of observed packets
+    pub packets: u64,
+    /// Total written length of the observed packets
+    pub bytes: u64,
+    /// Packets per ether type of each Ether, LinuxSll and Vlan layer
+    pub ether_types: HashMap<EtherType, u64>,
+    /// Packets per upper-layer protocol of each Ipv4 layer and Ipv6 header chain
+    pub ip_protocols: HashMap<IpProtocol, u64>,
+    /// Packets per TCP port, a port used as source and destination is counted once
+    pub tcp_ports: HashMap<u16, u64>,
+    /// Packets per UDP port, a port used as source and destination is counted once
+    pub udp_ports: HashMap<u16, u64>,
+}
+
+// increment the count of `key`
+fn tally<K: Eq + Hash>(map: &mut HashMap<K, u64>, key: K) {
+    *map.entry(key).or_insert(0) += 1;
+}
+
+// tally both ports of a packet
+fn tally_ports(map: &mut HashMap<u16, u64>, sport: u16, dport: u16) {
+    tally(map, sport);
+    if dport != sport {
+        tally(map, dport);
+    }
+}
+
+impl CaptureStats {
+    /// Add the counters of `packet`
+    pub fn observe(&mut self, packet: &Packet) -> Result<(), PacketError> {
+        self.bytes += packet.to_bytes()?.len() as u64;
+        self.packets += 1;
+
+        let mut layers = packet.layers.iter().peekable();
+        while let Some(layer) = layers.next() {
+            match layer {
+                Layer::Ether(ether) => tally(&mut self.ether_types, ether.ether_type.clone()),
+                Layer::LinuxSll(sll) => tally(&mut self.ether_types, sll.protocol.clone()),
+                Layer::Vlan(vlan) => tally(&mut self.ether_types, vlan.ether_type.clone()),
+                Layer::Ipv4(ipv4) => tally(&mut self.ip_protocols, ipv4.protocol.clone()),
+                // count the last header of an Ipv6 extension header chain
+                Layer::Ipv6(ipv6) if !matches!(layers.peek(), Some(Layer::Ipv6Ext(_))) => {
+                    tally(&mut self.ip_protocols, ipv6.next_header.clone())
+                }
+                Layer::Ipv6Ext(ext) if !matches!(layers.peek(), Some(Layer::Ipv6Ext(_))) => {
+                    tally(&mut self.ip_protocols, ext.next_header.clone())
+                }
+                Layer::Tcp(tcp) => tally_ports(&mut self.tcp_ports, tcp.sport, tcp.dport),
+                Layer::Udp(udp) => tally_ports(&mut self.udp_ports, udp.sport, udp.dport),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// entries of `map` by decreasing count, ties ordered by key
+fn write_counts<K: Debug>(
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+    map: &HashMap<K, u64>,
+) -> std::fmt::Result {
+    let mut entries: Vec<_> = map.iter().map(|(k, v)| (format!("{:?}", k), *v)).collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let entries: Vec<_> = entries.iter().map(|(k, v)| format!("{} {}", k, v)).collect();
+    writeln!(f, "{}: {}", name, entries.join(", "))
+}
+
+/// Summary of the counters, one line per protocol family
+impl std::fmt::Display for CaptureStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "packets: {}, bytes: {}", self.packets, self.bytes)?;
+        write_counts(f, "ether types", &self.ether_types)?;
+        write_counts(f, "ip protocols", &self.ip_protocols)?;
+        write_counts(f, "tcp ports", &self.tcp_ports)?;
+        write_counts(f, "udp ports", &self.udp_ports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_capture_stats() {
+        let packets = vec![
+            // Ether / IP / TCP / "hello world"
+            hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64").to_vec(),
+            // Ether / IP / UDP / "hello"
+            hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f").to_vec(),
+            hex!("ffffffffffff0000000000000800450000210001000040117cc97f0000017f00000104d2162e000d000068656c6c6f").to_vec(),
+            // Ether / IPv6 / UDP / "hello"
+            hex!("ffffffffffff000000000000 86dd 60000000000d1140 00000000000000000000000000000001 00000000000000000000000000000001 04d2162e000d0000 68656c6c6f").to_vec(),
+            // Ether / ARP
+            hex!("ffffffffffff0000000000000806 0001080006040001 001122334455 0a000001 000000000000 0a000002").to_vec(),
+        ];
+
+        let mut stats = CaptureStats::default();
+        for data in &packets {
+            stats.observe(&Packet::from_bytes(data).unwrap()).unwrap();
+        }
+
+        assert_eq!(5, stats.packets);
+        assert_eq!(268, stats.bytes);
+
+        assert_eq!(3, stats.ether_types.len());
+        assert_eq!(Some(&3), stats.ether_types.get(&EtherType::IPv4));
+        assert_eq!(Some(&1), stats.ether_types.get(&EtherType::IPv6));
+        assert_eq!(Some(&1), stats.ether_types.get(&EtherType::ARP));
+
+        assert_eq!(2, stats.ip_protocols.len());
+        assert_eq!(Some(&1), stats.ip_protocols.get(&IpProtocol::TCP));
+        assert_eq!(Some(&3), stats.ip_protocols.get(&IpProtocol::UDP));
+
+        assert_eq!(vec![(20, 1), (80, 1)], sorted_counts(&stats.tcp_ports));
+        assert_eq!(vec![(1234, 3), (5678, 3)], sorted_counts(&stats.udp_ports));
+
+        assert_eq!(
+            "packets: 5, bytes: 268\n\
+             ether types: IPv4 3, ARP 1, IPv6 1\n\
+             ip protocols: UDP 3, TCP 1\n\
+             tcp ports: 20 1, 80 1\n\
+             udp ports: 1234 3, 5678 3\n",
+            stats.to_string()
+        );
+    }
+
+    fn sorted_counts(map: &HashMap<u16, u64>) -> Vec<(u16, u64)> {
+        let mut ret: Vec<_> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        ret.sort_unstable();
+        ret
+    }
+}
@@ -0,0 +1,95 @@
+/*!
+Ordering of captured packets by timestamp
+*/
+use super::Packet;
+use std::time::SystemTime;
+
+/// Sort captured packets by timestamp
+///
+/// The sort is stable, packets with the same timestamp keep their capture order
+pub fn sort_packets_by_time(packets: &mut [(Packet, SystemTime)]) {
+    packets.sort_by_key(|(_pkt, ts)| *ts);
+}
+
+/// Merge two streams of captured packets, each sorted by timestamp, into one sorted stream
+///
+/// Packets with the same timestamp are taken from `a` first
+pub fn merge_packets_by_time(
+    a: Vec<(Packet, SystemTime)>,
+    b: Vec<(Packet, SystemTime)>,
+) -> Vec<(Packet, SystemTime)> {
+    let mut ret = Vec::with_capacity(a.len() + b.len());
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+
+    loop {
+        let take_a = match (a.peek(), b.peek()) {
+            (Some((_, ts_a)), Some((_, ts_b))) => ts_a <= ts_b,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => break,
+        };
+
+        ret.extend(if take_a { a.next() } else { b.next() });
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::{Ipv4, Layer};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    // Packet identified by its Ipv4 identification, captured at `secs`
+    fn capture(id: u16, secs: u64) -> (Packet, SystemTime) {
+        let ipv4 = Ipv4 {
+            identification: id,
+            ..Ipv4::default()
+        };
+
+        (
+            Packet::new(vec![Layer::Ipv4(ipv4)]),
+            UNIX_EPOCH + Duration::from_secs(secs),
+        )
+    }
+
+    fn ids(packets: &[(Packet, SystemTime)]) -> Vec<u16> {
+        packets
+            .iter()
+            .map(|(pkt, _ts)| pkt.ipv4().unwrap().identification)
+            .collect()
+    }
+
+    #[test]
+    fn test_sort_packets_by_time() {
+        let mut packets = vec![capture(1, 30), capture(2, 10), capture(3, 20), capture(4, 10)];
+
+        sort_packets_by_time(&mut packets);
+
+        assert_eq!(vec![2, 4, 3, 1], ids(&packets));
+    }
+
+    #[test]
+    fn test_merge_packets_by_time() {
+        let a = vec![capture(1, 10), capture(2, 20), capture(3, 40)];
+        let b = vec![capture(4, 5), capture(5, 20), capture(6, 30), capture(7, 50)];
+
+        let merged = merge_packets_by_time(a, b);
+
+        assert_eq!(vec![4, 1, 2, 5, 6, 3, 7], ids(&merged));
+
+        let timestamps: Vec<SystemTime> = merged.iter().map(|(_pkt, ts)| *ts).collect();
+        let mut sorted = timestamps.clone();
+        sorted.sort();
+        assert_eq!(sorted, timestamps);
+    }
+
+    #[test]
+    fn test_merge_packets_by_time_empty() {
+        let b = vec![capture(1, 10), capture(2, 20)];
+
+        assert_eq!(vec![1, 2], ids(&merge_packets_by_time(vec![], b)));
+    }
+}
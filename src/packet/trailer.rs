@@ -0,0 +1,84 @@
+/*!
+Link trailer bytes which are not part of any layer
+
+Such as VLAN trailers or vendor metadata appended to a frame, before the FCS
+*/
+use super::{Packet, PacketError};
+use crate::layer::LayerError;
+
+impl Packet {
+    /// Read a packet from bytes, the last `trailer_len` bytes are the trailer
+    ///
+    /// The trailer is kept on the packet and written back by `to_bytes`, after the layers
+    pub fn from_bytes_with_trailer(
+        input: &[u8],
+        trailer_len: usize,
+    ) -> Result<Packet, PacketError> {
+        let split = input
+            .len()
+            .checked_sub(trailer_len)
+            .ok_or_else(|| LayerError::Parse("not enough data to read trailer".to_string()))?;
+        let (frame, trailer) = input.split_at(split);
+
+        let mut pkt = Packet::from_bytes(frame)?;
+        pkt.trailer = trailer.to_vec();
+
+        Ok(pkt)
+    }
+
+    /// Returns the trailer of the packet, empty if there is none
+    pub fn trailer(&self) -> &[u8] {
+        &self.trailer
+    }
+
+    /// Set the trailer of the packet, an empty trailer removes it
+    pub fn set_trailer(&mut self, trailer: Vec<u8>) {
+        self.trailer = trailer;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_packet_set_trailer() {
+        // Ether / IP / TCP / "hello world"
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64");
+
+        let mut pkt = Packet::from_bytes(test_data.as_ref()).unwrap();
+        assert!(pkt.trailer().is_empty());
+
+        pkt.set_trailer(hex!("deadbeef").to_vec());
+        let layers = pkt.layers.len();
+        let ret_write = pkt.to_bytes().unwrap();
+        assert_eq!(test_data.len() + 4, ret_write.len());
+        assert_eq!(test_data.as_ref(), &ret_write[..test_data.len()]);
+        assert_eq!(hex!("deadbeef").as_ref(), &ret_write[test_data.len()..]);
+
+        // the trailer is not a layer, nor part of the payload
+        pkt.update().unwrap();
+        assert_eq!(layers, pkt.layers.len());
+        assert_eq!(b"hello world".to_vec(), pkt.raw().unwrap().data);
+        assert_eq!(0x33, pkt.ipv4().unwrap().length);
+    }
+
+    #[test]
+    fn test_packet_from_bytes_with_trailer() {
+        // Ether / IP / TCP / "hello world" / trailer
+        let test_data = hex!("ffffffffffff0000000000000800450000330001000040067cc27f0000017f00000100140050000000000000000050022000ffa2000068656c6c6f20776f726c64 deadbeef");
+
+        let pkt = Packet::from_bytes_with_trailer(test_data.as_ref(), 4).unwrap();
+        assert_eq!(hex!("deadbeef").as_ref(), pkt.trailer());
+        assert_eq!(b"hello world".to_vec(), pkt.raw().unwrap().data);
+        assert_eq!(test_data.to_vec(), pkt.to_bytes().unwrap());
+
+        assert_eq!(
+            Err(PacketError::LayerError(LayerError::Parse(
+                "not enough data to read trailer".to_string()
+            ))),
+            Packet::from_bytes_with_trailer(&test_data[..2], 4)
+        );
+    }
+}
@@ -4,9 +4,15 @@ pub use crate::datalink::pcap::Pcap;
 pub use crate::datalink::pcapfile::PcapFile;
 #[cfg(feature = "pnet")]
 pub use crate::datalink::pnet::Pnet;
-pub use crate::datalink::{Interface, PacketInterface, PacketRead, PacketWrite};
+#[cfg(all(feature = "raw_socket", target_os = "linux"))]
+pub use crate::datalink::rawsocket::RawSocket;
+pub use crate::datalink::{Interface, LinkType, PacketInterface, PacketRead, PacketWrite};
 // # LAYER: Layer in prelude
-pub use crate::layer::{Ether, Ipv4, Ipv6, Layer, LayerError, LayerType, Raw, Tcp, Udp};
+pub use crate::layer::{
+    Arp, Avtp, Bgp, Bpdu, Coap, Dhcpv6, Ether, Gtpu, Icmp, Igmp, Ipv4, Ipv6, Ipv6Ext, Layer,
+    LayerError, LayerType, LinuxSll, Llc, Lldp, MacSec, NetflowV5, Null, Ospf, Ptp, Radiotap,
+    Radius, Raw, Smb, Tcp, Udp, UnknownHandling, Vlan, Vxlan, WireGuard,
+};
 pub use crate::packet::{Packet, PacketError};
 pub use crate::*;
 pub use deku::prelude::*;
@@ -83,4 +83,43 @@ mod tests {
         (0, 0, 0),
         (1, 0, 1)
     );
+
+    // Ether / 802.1Q x `vlans` / IP / UDP / "hello"
+    fn vlan_packet(vlans: usize) -> Packet {
+        let mut bytes = hex!("ffffffffffff000000000000").to_vec();
+        for _ in 0..vlans {
+            bytes.extend_from_slice(&hex!("8100 0064"));
+        }
+        bytes.extend_from_slice(&hex!(
+            "0800 450000210001000040117cc97f0000017f000001 04d2162e000d0000 68656c6c6f"
+        ));
+
+        Packet::from_bytes(&bytes).unwrap()
+    }
+
+    #[ignore]
+    #[test]
+    fn test_packet_update() {
+        let count_update = |vlans| {
+            let mut pkt = vlan_packet(vlans);
+            count_alloc(|| {
+                pkt.update().unwrap();
+            })
+            .0
+        };
+
+        // each layer is written once, every tag adds the allocations of one vlan layer
+        let (malloc, realloc, free) = count_update(0);
+        let (vlan_malloc, vlan_realloc, vlan_free) = count_update(1);
+        for vlans in 2..=8 {
+            assert_eq!(
+                (
+                    malloc + vlans * (vlan_malloc - malloc),
+                    realloc + vlans * (vlan_realloc - realloc),
+                    free + vlans * (vlan_free - free),
+                ),
+                count_update(vlans)
+            );
+        }
+    }
 }
@@ -38,8 +38,96 @@ mod tests {
     // # LAYER: Tests to ensure correct implementations of all layer functionality
     test_layer!(test_raw, Raw, raw, raw_mut);
     test_layer!(test_ether, Ether, ether, ether_mut);
+    test_layer!(test_linux_sll, LinuxSll, linux_sll, linux_sll_mut);
+    test_layer!(test_vlan, Vlan, vlan, vlan_mut);
     test_layer!(test_ipv4, Ipv4, ipv4, ipv4_mut);
     test_layer!(test_ipv6, Ipv6, ipv6, ipv6_mut);
+    test_layer!(test_ipv6ext, Ipv6Ext, ipv6ext, ipv6ext_mut);
     test_layer!(test_tcp, Tcp, tcp, tcp_mut);
     test_layer!(test_udp, Udp, udp, udp_mut);
+    test_layer!(test_vxlan, Vxlan, vxlan, vxlan_mut);
+    test_layer!(test_bgp, Bgp, bgp, bgp_mut);
+    test_layer!(test_smb, Smb, smb, smb_mut);
+    test_layer!(test_ptp, Ptp, ptp, ptp_mut);
+    test_layer!(test_wireguard, WireGuard, wireguard, wireguard_mut);
+    test_layer!(test_igmp, Igmp, igmp, igmp_mut);
+    test_layer!(test_lldp, Lldp, lldp, lldp_mut);
+    test_layer!(test_avtp, Avtp, avtp, avtp_mut);
+    test_layer!(test_netflow_v5, NetflowV5, netflow_v5, netflow_v5_mut);
+    test_layer!(test_macsec, MacSec, macsec, macsec_mut);
+    test_layer!(test_arp, Arp, arp, arp_mut);
+    test_layer!(test_gtpu, Gtpu, gtpu, gtpu_mut);
+    test_layer!(test_ospf, Ospf, ospf, ospf_mut);
+    test_layer!(test_radiotap, Radiotap, radiotap, radiotap_mut);
+    test_layer!(test_icmp, Icmp, icmp, icmp_mut);
+    test_layer!(test_llc, Llc, llc, llc_mut);
+    test_layer!(test_bpdu, Bpdu, bpdu, bpdu_mut);
+    test_layer!(test_dhcpv6, Dhcpv6, dhcpv6, dhcpv6_mut);
+    test_layer!(test_coap, Coap, coap, coap_mut);
+    test_layer!(test_null, Null, null, null_mut);
+    test_layer!(test_radius, Radius, radius, radius_mut);
+
+    #[test]
+    fn test_layer_name() {
+        assert_eq!("IPv4", LayerType::Ipv4.name());
+        assert_eq!("UDP", LayerType::Udp.name());
+        assert_eq!("Ether", ether!().unwrap().name());
+    }
+
+    #[test]
+    fn test_layer_set_raw_bytes() {
+        let mut layer = tcp! {
+            sport: 1234,
+            dport: 5678,
+        }
+        .unwrap();
+
+        // sport 20, dport 80, seq 1, SYN
+        let tcp_header = [
+            0x00, 0x14, 0x00, 0x50, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x50, 0x02,
+            0x20, 0x00, 0xff, 0xa2, 0x00, 0x00,
+        ];
+        layer.set_raw_bytes(&tcp_header).unwrap();
+
+        if let Layer::Tcp(tcp) = &layer {
+            assert_eq!(20, tcp.sport);
+            assert_eq!(80, tcp.dport);
+            assert_eq!(1, tcp.seq);
+            assert_eq!(1, tcp.flags.syn);
+        } else {
+            panic!("expected tcp layer");
+        }
+        assert_eq!(tcp_header.to_vec(), layer.to_bytes().unwrap());
+
+        // bytes which are not exactly a tcp header leave the layer unchanged
+        assert!(layer.set_raw_bytes(&tcp_header[..10]).is_err());
+        assert_eq!(
+            Err(LayerError::Parse("2 bytes left after TCP layer".to_string())),
+            layer.set_raw_bytes(&[&tcp_header[..], &[0xaa, 0xbb]].concat())
+        );
+        assert_eq!(tcp_header.to_vec(), layer.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_layer_tagged_bytes() {
+        let layer = tcp! {
+            sport: 1234,
+            dport: 5678,
+            seq: 42,
+            offset: 5,
+        }
+        .unwrap();
+
+        let (layer_type, bytes) = layer.to_tagged_bytes().unwrap();
+        assert_eq!(LayerType::Tcp, layer_type);
+        assert_eq!(layer.to_bytes().unwrap(), bytes);
+
+        assert_eq!(layer, Layer::from_tagged_bytes(layer_type, &bytes).unwrap());
+
+        // all of the bytes must be read
+        assert_eq!(
+            Err(LayerError::Parse("20 bytes left after TCP layer".to_string())),
+            Layer::from_tagged_bytes(LayerType::Tcp, &[bytes.clone(), bytes].concat())
+        );
+    }
 }
@@ -0,0 +1,19 @@
+#![cfg(feature = "pcap")]
+
+use rust_packet::layer::ip::IpProtocol;
+use rust_packet::prelude::*;
+
+#[test]
+fn test_pcapfile_raw_linktype() {
+    let mut rx = Interface::<PcapFile>::new("tests/data/raw_ipv4.pcap").unwrap();
+
+    let pkt = rx.next().unwrap();
+
+    // Read as raw IP, no link-layer header
+    assert!(pkt.ether().is_none());
+    assert_eq!(IpProtocol::UDP, pkt.ipv4().unwrap().protocol);
+    assert_eq!(5678, pkt.udp().unwrap().dport);
+    assert_eq!(b"hello".to_vec(), pkt.raw().unwrap().data);
+
+    assert!(rx.next().is_none());
+}